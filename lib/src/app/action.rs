@@ -7,17 +7,25 @@ use crate::Popup;
 use crate::Selectable;
 use crate::UserInterface;
 use crate::app::Actions;
+use crate::audio::CountIn;
+use crate::audio::Gain;
 use crate::metre::Instant;
+use crate::note::expression::Dimension;
 use crate::popup;
 use crate::project::Edit;
 use crate::project::Manager;
+use crate::project::Track;
+use crate::project::track::OverlapPolicy;
 use crate::ui::Length;
 use crate::ui::Point;
 use crate::ui::Rectangle;
 use crate::ui::Vector;
 use crate::view::context::Menu;
+use enum_iterator::next_cycle;
 use serde::Deserialize;
 use std::env::current_dir;
+use std::ops::Bound::Excluded;
+use std::ops::Bound::Unbounded;
 use std::path::Path;
 use std::sync::Arc;
 
@@ -37,16 +45,26 @@ pub enum Action {
     /// Closes a popup.
     #[serde(skip)]
     ClosePopup(Id<Popup>),
+    /// Copies the selected notes, clips or tracks to the clipboard.
+    Copy,
+    /// Copies the selected notes, clips or tracks to the clipboard and deletes them.
+    Cut,
     /// A project edit.
     Edit(Edit),
     /// Enters _edit mode_.
     EnterEditMode,
+    /// Removes the last character from the text field that is being typed into.
+    Erase,
     /// Saves and exits the program
     Exit,
     /// Exits _edit mode_.
     ExitEditMode,
     /// Renders and exports the project to a file.
     ExportProject,
+    /// Moves the cursor to the first marker after it.
+    JumpToNextMarker,
+    /// Moves the cursor to the last marker before it.
+    JumpToPreviousMarker,
     /// Removes the held object.
     LetGo,
     /// Moves the (musical) cursor.
@@ -74,6 +92,8 @@ pub enum Action {
     /// Opens a project saved to a file.
     #[serde(skip)]
     OpenProject(Arc<Path>),
+    /// Pastes the contents of the clipboard.
+    Paste,
     /// Stop playing.
     Pause,
     /// Picks up an object.
@@ -81,6 +101,8 @@ pub enum Action {
     PickUp(Holdable),
     /// Start playing.
     Play,
+    /// Redoes the last undone edit.
+    Redo,
     /// Same the project.
     Save,
     /// Same the project.
@@ -89,12 +111,33 @@ pub enum Action {
     /// Selects an item.
     #[serde(skip)]
     Select(Selectable),
+    /// Sets which dimension of note expression is edited below the piano roll.
+    ///
+    /// `None` means that velocities are edited.
+    SetExpressionLane(Option<Dimension>),
+    /// Sets the gain of the metronome clicks.
+    SetMetronomeGain(Gain),
+    /// Sets what to do when a clip would overlap other clips.
+    SetOverlapPolicy(OverlapPolicy),
+    /// Shows or hides the automation lanes of a track.
+    #[serde(skip)]
+    ToggleAutomation(Id<Track>),
+    /// Cycles through how many measures are counted in before playback.
+    ToggleCountIn,
     /// Toggles _edit mode_.
     ToggleEditMode,
+    /// Toggles whether playback repeats the loop region.
+    ToggleLooping,
+    /// Toggles whether the metronome clicks during playback.
+    ToggleMetronome,
     /// Sets the piano roll's height to half of the screen height.
     TogglePianoRoll,
     /// Toggles whether the app is playing.
     TogglePlayback,
+    /// Types a character into the text field that is being typed into.
+    Type(char),
+    /// Undoes the last edit.
+    Undo,
     // TODO: add scripting
 }
 
@@ -139,51 +182,23 @@ impl<Ui: UserInterface> App<Ui> {
             Action::ClosePopup(popup) => {
                 self.popup_manager.close(popup);
             }
-            Action::Edit(edit) => {
-                self.project_manager
-                    .edit(edit, self.cursor(), &mut self.selection)?;
-
-                self.renderer.restart(
-                    self.project_manager.project(),
-                    self.audio_config.sample_rate()?,
-                    self.ui,
-                )?;
-            }
+            Action::Copy => self.copy()?,
+            Action::Cut => self.cut()?,
+            Action::Edit(edit) => self.edit(edit)?,
             Action::EnterEditMode => self.edit_mode = true,
+            Action::Erase => self.edit_text_field(|text| {
+                text.pop();
+            }),
             Action::Exit => {
                 // TODO: Check if we've saved the project.
                 self.ui.exit();
             }
             Action::ExitEditMode => self.edit_mode = false,
-            Action::ExportProject => {
-                // TODO: read the project path from the app
-                let project_directory = current_dir()?;
-
-                let project_name = self.project_manager.project().name();
-
-                let file_name = if project_name.is_empty() {
-                    DEFAULT_EXPORT_FILE_NAME
-                } else {
-                    &project_name
-                };
-
-                let mut export_file = project_directory.join(file_name);
-
-                // Currently only wav files are supported for export since symphonia only does decoding.
-                export_file.set_extension("wav");
-
-                self.renderer.export_when_finished(export_file)?;
-            }
+            Action::ExportProject => self.export_project()?,
+            Action::JumpToNextMarker => self.jump_to_next_marker(),
+            Action::JumpToPreviousMarker => self.jump_to_previous_marker(),
             Action::LetGo => self.held_object = None,
-            Action::MoveCursor(instant) => {
-                self.cursor = instant;
-
-                if self.audio_config.is_player_playing() {
-                    self.take(Action::Play);
-                } else {
-                    self.audio_config.pause_player();
-                }
-            }
+            Action::MoveCursor(instant) => self.move_cursor(instant),
             Action::MoveHeldObject(to) => self.move_held_object(to),
             Action::MoveOverview(by) => {
                 self.ui_settings.negative_overview_offset -= by.x;
@@ -198,45 +213,282 @@ impl<Ui: UserInterface> App<Ui> {
             Action::OpenPopup(popup) => {
                 self.popup_manager.open(&popup, self.ui);
             }
-            Action::OpenProject(path) => {
-                // TODO: Check if we've got unsaved changes.
-                self.project_manager = Manager::open(path)?;
-            }
-            Action::Pause => {
-                if let Some(position) = self.audio_config.pause_player() {
-                    self.cursor = position / &self.project_manager.project().time_context();
-                }
-            }
+            Action::OpenProject(path) => self.open_project(path)?,
+            Action::Paste => self.paste()?,
+            Action::Pause => self.pause(),
             // The currently held object should already have been let go.
-            Action::PickUp(object) => self.held_object = Some(object),
-            Action::Play => {
-                let from = self.cursor() * &self.project_manager.project().time_context();
-
-                let player = self.audio_config.player()?;
-
-                self.renderer.play_when_finished(from, player);
-            }
+            Action::PickUp(object) => self.pick_up(object),
+            Action::Play => self.play(true)?,
+            Action::Redo => self.redo()?,
             Action::Save => self.project_manager.save()?,
             Action::SaveAs(path) => self.project_manager.save_as(path)?,
             Action::Select(item) => {
                 self.selection.push(item);
             }
+            Action::SetExpressionLane(dimension) => {
+                self.piano_roll.set_expression_lane(dimension);
+            }
+            Action::SetMetronomeGain(gain) => self.set_metronome_gain(gain)?,
+            Action::SetOverlapPolicy(overlap_policy) => self.overlap_policy = overlap_policy,
+            Action::ToggleAutomation(track) => {
+                if !self.expanded_tracks.remove(&track) {
+                    self.expanded_tracks.insert(track);
+                }
+            }
+            Action::ToggleCountIn => {
+                self.metronome
+                    .set_count_in(next_cycle(&self.metronome.count_in()));
+            }
             Action::ToggleEditMode => self.edit_mode = !self.edit_mode,
+            Action::ToggleLooping => self.toggle_looping()?,
+            Action::ToggleMetronome => self.toggle_metronome()?,
             Action::TogglePianoRoll => {
                 self.piano_roll.set_is_open(!self.piano_roll.is_open());
             }
-            Action::TogglePlayback => {
-                if self.audio_config.is_player_playing() {
-                    self.take(Action::Pause);
-                } else {
-                    self.take(Action::Play);
-                }
-            }
+            Action::TogglePlayback => self.toggle_playback(),
+            Action::Type(character) => self.edit_text_field(|text| text.push(character)),
+            Action::Undo => self.undo()?,
+        }
+
+        Ok(())
+    }
+
+    /// Performs an edit on the project.
+    fn edit(&mut self, edit: Edit) -> anyhow::Result<()> {
+        self.project_manager.edit(
+            edit,
+            self.cursor(),
+            &mut self.selection,
+            self.overlap_policy,
+        )?;
+
+        self.restart_renderer()
+    }
+
+    /// Undoes the last edit.
+    fn undo(&mut self) -> anyhow::Result<()> {
+        self.project_manager.undo()?;
+
+        self.restart_renderer()
+    }
+
+    /// Redoes the last undone edit.
+    fn redo(&mut self) -> anyhow::Result<()> {
+        self.project_manager.redo()?;
+
+        self.restart_renderer()
+    }
+
+    /// Opens the project saved at `path`.
+    fn open_project(&mut self, path: Arc<Path>) -> anyhow::Result<()> {
+        // TODO: Check if we've got unsaved changes.
+        self.project_manager = Manager::open(path)?;
+
+        self.restart_renderer()
+    }
+
+    /// Exports the project once it has been rendered.
+    fn export_project(&mut self) -> anyhow::Result<()> {
+        // TODO: read the project path from the app
+        let project_directory = current_dir()?;
+
+        let project_name = self.project_manager.project().name();
+
+        let file_name = if project_name.is_empty() {
+            DEFAULT_EXPORT_FILE_NAME
+        } else {
+            &project_name
+        };
+
+        let mut export_file = project_directory.join(file_name);
+
+        // Currently only wav files are supported for export since symphonia only does decoding.
+        export_file.set_extension("wav");
+
+        let cues = self.project_manager.project().cues();
+
+        self.renderer.export_when_finished(export_file, cues)
+    }
+
+    /// Copies the selection to the clipboard.
+    fn copy(&mut self) -> anyhow::Result<()> {
+        let clipboard = self.project_manager.project().copy(&self.selection)?;
+
+        self.clipboard = Some(Arc::new(clipboard));
+
+        Ok(())
+    }
+
+    /// Copies the selection to the clipboard and deletes it.
+    fn cut(&mut self) -> anyhow::Result<()> {
+        self.copy()?;
+        self.edit(Edit::Delete)
+    }
+
+    /// Pastes the clipboard, if it is not empty.
+    fn paste(&mut self) -> anyhow::Result<()> {
+        if let Some(clipboard) = self.clipboard.clone() {
+            self.edit(Edit::Paste(clipboard))?;
         }
 
         Ok(())
     }
 
+    /// Moves the cursor, continuing playback from it if audio is playing.
+    fn move_cursor(&mut self, instant: Instant) {
+        self.cursor = instant;
+
+        if self.audio_config.is_player_playing() {
+            self.take(Action::Play);
+        } else {
+            self.audio_config.pause_player();
+        }
+    }
+
+    /// Moves the cursor to the first marker after it.
+    fn jump_to_next_marker(&mut self) {
+        let cursor = self.cursor();
+
+        let next = self
+            .project_manager
+            .project()
+            .markers()
+            .range((Excluded(cursor), Unbounded))
+            .next()
+            .map(|(at, _)| *at);
+
+        if let Some(at) = next {
+            self.take(Action::MoveCursor(at));
+        }
+    }
+
+    /// Moves the cursor to the last marker before it.
+    fn jump_to_previous_marker(&mut self) {
+        let cursor = self.cursor();
+
+        let previous = self
+            .project_manager
+            .project()
+            .markers()
+            .range(..cursor)
+            .next_back()
+            .map(|(at, _)| *at);
+
+        if let Some(at) = previous {
+            self.take(Action::MoveCursor(at));
+        }
+    }
+
+    /// Pauses playback, moving the cursor to where it was paused.
+    fn pause(&mut self) {
+        if let Some(position) = self.audio_config.pause_player() {
+            self.cursor = position / &self.project_manager.project().time_context();
+        }
+    }
+
+    /// Pauses playback if audio is playing and starts it otherwise.
+    fn toggle_playback(&mut self) {
+        if self.audio_config.is_player_playing() {
+            self.take(Action::Pause);
+        } else {
+            self.take(Action::Play);
+        }
+    }
+
+    /// Toggles whether playback repeats the loop region.
+    fn toggle_looping(&mut self) -> anyhow::Result<()> {
+        self.looping = !self.looping;
+
+        // Restart playback so that it (stops) repeating the loop region.
+        self.restart_playback()
+    }
+
+    /// Toggles whether the metronome clicks during playback.
+    fn toggle_metronome(&mut self) -> anyhow::Result<()> {
+        self.metronome.set_is_enabled(!self.metronome.is_enabled());
+
+        // Restart playback so that the clicks are (un)mixed.
+        self.restart_playback()
+    }
+
+    /// Sets the gain of the metronome clicks.
+    fn set_metronome_gain(&mut self, gain: Gain) -> anyhow::Result<()> {
+        self.metronome.set_gain(gain);
+
+        self.restart_playback()
+    }
+
+    /// Starts playback from the cursor.
+    ///
+    /// The count-in of the metronome is only played if `count_in` is set.
+    fn play(&mut self, count_in: bool) -> anyhow::Result<()> {
+        let project = self.project_manager.project();
+        let time_context = project.time_context();
+
+        let from = self.cursor() * &time_context;
+        let loop_region = project
+            .loop_region()
+            .filter(|_| self.looping)
+            .map(|region| region.get() * &time_context);
+
+        let mut metronome = self.metronome;
+
+        if !count_in {
+            metronome.set_count_in(CountIn::Off);
+        }
+
+        let click_track = metronome.click_track(project);
+
+        let player = self.audio_config.player()?;
+
+        self.renderer
+            .play_when_finished(from, player, loop_region, click_track);
+
+        Ok(())
+    }
+
+    /// Restarts playback, without a count-in, if audio is playing.
+    fn restart_playback(&mut self) -> anyhow::Result<()> {
+        if self.audio_config.is_player_playing() {
+            self.play(false)?;
+        }
+
+        Ok(())
+    }
+
+    /// Restarts the rendering of the project.
+    fn restart_renderer(&mut self) -> anyhow::Result<()> {
+        self.renderer.restart(
+            self.project_manager.project(),
+            self.audio_config.sample_rate()?,
+            self.ui,
+        )
+    }
+
+    /// Edits the text of the text field that is being typed into.
+    ///
+    /// If no text field is being typed into, nothing happens.
+    fn edit_text_field(&self, edit: impl FnOnce(&mut String)) {
+        if let Some(text_field) = self.popup_manager.text_field() {
+            let mut text = String::from(&*text_field.get());
+            edit(&mut text);
+            text_field.set(Arc::from(text));
+        }
+    }
+
+    /// Picks up an object.
+    fn pick_up(&mut self, object: Holdable) {
+        // A grabbed note is moved along with the selected notes, if it is one of them.
+        if let Holdable::Note { note, .. } | Holdable::NoteEnd { note, .. } = object
+            && !self.selection.contains_note(note)
+        {
+            self.selection.clear();
+            self.selection.push(Selectable::Note(note));
+        }
+
+        self.held_object = Some(object);
+    }
+
     /// Moves the currently held object to the provided position.
     ///
     /// If no object is held, nothing happens.
@@ -260,7 +512,15 @@ impl<Ui: UserInterface> App<Ui> {
                 .popup_manager
                 .transform_popup(popup, |area| side.resize(area, to)),
             // These are processed when they are dropped.
-            Holdable::Clip(_) | Holdable::NoteCreation { .. } | Holdable::SelectionBox { .. } => (),
+            Holdable::Clip(_)
+            | Holdable::ClipEnd(_)
+            | Holdable::ClipStart(_)
+            | Holdable::LoopRegion { .. }
+            | Holdable::Note { .. }
+            | Holdable::NoteCreation { .. }
+            | Holdable::NoteEnd { .. }
+            | Holdable::SelectionBox { .. }
+            | Holdable::Track(_) => (),
         }
     }
 }
@@ -9,16 +9,21 @@ pub use actions::Actions;
 use std::sync::Arc;
 
 use crate::Holdable;
+use crate::Id;
 use crate::PianoRoll;
 use crate::UserInterface;
 use crate::View;
 use crate::app::view::view;
 use crate::audio::Config;
+use crate::audio::Metronome;
 use crate::metre::Instant;
 use crate::metre::NonZeroDuration;
 use crate::metre::Quantisation;
 use crate::popup;
 use crate::project;
+use crate::project::Clipboard;
+use crate::project::Track;
+use crate::project::track::OverlapPolicy;
 use crate::select::Selection;
 use crate::ui;
 use crate::ui::Theme;
@@ -28,6 +33,7 @@ use getset::CloneGetters;
 use getset::CopyGetters;
 use getset::Getters;
 use getset::MutGetters;
+use std::collections::HashSet;
 
 /// A running instance of the DAW.
 #[derive(Debug, Getters, MutGetters, CopyGetters, CloneGetters)]
@@ -74,14 +80,24 @@ pub struct App<Ui: UserInterface> {
     cursor: Instant,
     /// The selection (what clips, tracks, notes, &c. are selected).
     selection: Selection,
+    /// The copied notes, clips or tracks, if anything has been copied.
+    clipboard: Option<Arc<Clipboard>>,
     /// The settings quantisation.
     quantisation: Quantisation,
 
     /// Whether _edit mode_ is enabled.
     edit_mode: bool,
+    /// Whether playback repeats the loop region.
+    looping: bool,
+    /// The settings regarding the metronome.
+    metronome: Metronome,
+    /// What to do when a clip would overlap other clips.
+    overlap_policy: OverlapPolicy,
     /// The settings regarding the piano roll.
     #[get_mut = "pub(crate)"]
     piano_roll: PianoRoll,
+    /// The tracks whose automation lanes are shown.
+    expanded_tracks: HashSet<Id<Track>>,
 }
 
 impl<Ui: UserInterface> App<Ui> {
@@ -109,14 +125,19 @@ impl<Ui: UserInterface> App<Ui> {
             theme: Theme::default(),
 
             selection: Selection::default(),
+            clipboard: None,
             cursor: Instant::START,
 
             edit_mode: false,
+            looping: false,
+            metronome: Metronome::default(),
+            overlap_policy: OverlapPolicy::default(),
             quantisation: Quantisation {
                 cell_duration: NonZeroDuration::QUARTER,
                 cell_width: Ui::CELL_WIDTH,
             },
             piano_roll: PianoRoll::default_in::<Ui>(),
+            expanded_tracks: HashSet::new(),
         };
 
         app.rerender();
@@ -124,6 +145,15 @@ impl<Ui: UserInterface> App<Ui> {
         app
     }
 
+    /// Returns whether text is being typed into a popup.
+    ///
+    /// If it is, key presses should be turned into [`Action::Type`] and [`Action::Erase`]
+    /// rather than looked up in the key map.
+    #[must_use]
+    pub fn is_typing(&self) -> bool {
+        self.popup_manager.text_field().is_some()
+    }
+
     /// Returns the position of the musical cursor.
     fn cursor(&self) -> Instant {
         if let Some(position) = self.audio_config.player_position() {
@@ -9,24 +9,28 @@ use crate::project::workspace;
 /// Constructs a [view](View) of the [app](App).
 pub(super) fn view<Ui: UserInterface>(app: &App<Ui>) -> View {
     let background = View::y_stack([
-        bar::<Ui>(
-            app.project_manager.project(),
-            app.cursor(),
-            app.audio_config.try_player().cloned(),
-            app.edit_mode,
-            app.piano_roll.is_open(),
-        )
-        .quoted(app.ui_settings.project_bar_height),
-        workspace::<Ui>(
-            app.project_manager.project(),
-            &app.selection,
-            app.ui_settings,
-            app.quantisation,
-            app.cursor(),
-            app.audio_config.try_player(),
-            app.held_object,
-        )
-        .fill_remaining(),
+        bar::<Ui>()
+            .project(app.project_manager.project())
+            .cursor(app.cursor())
+            .maybe_player(app.audio_config.try_player().cloned())
+            .edit_mode(app.edit_mode)
+            .overlap_policy(app.overlap_policy)
+            .piano_roll_open(app.piano_roll.is_open())
+            .looping(app.looping)
+            .metronome(app.metronome)
+            .call()
+            .quoted(app.ui_settings.project_bar_height),
+        workspace::<Ui>()
+            .project(app.project_manager.project())
+            .selection(&app.selection)
+            .ui_settings(app.ui_settings)
+            .quantisation(app.quantisation)
+            .cursor(app.cursor())
+            .maybe_player(app.audio_config.try_player())
+            .maybe_held_object(app.held_object)
+            .expanded_tracks(&app.expanded_tracks)
+            .call()
+            .fill_remaining(),
         app.piano_roll
             .view::<Ui>()
             .selection(&app.selection)
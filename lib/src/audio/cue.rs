@@ -0,0 +1,86 @@
+//! Items pertaining to [`Cue`].
+
+use crate::audio::sample;
+use crate::time;
+use arcstr::ArcStr;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Seek as _;
+use std::io::SeekFrom;
+use std::io::Write as _;
+use std::path::Path;
+
+/// The offset of the size field of a RIFF file.
+const RIFF_SIZE_OFFSET: u64 = 4;
+/// The number of bytes in a RIFF file that are not counted by its size field.
+const RIFF_HEADER_SIZE: u64 = 8;
+
+/// A labelled position in exported audio.
+#[derive(Clone, Debug)]
+pub(crate) struct Cue {
+    /// The position of the cue.
+    pub position: time::Instant,
+    /// The label of the cue.
+    pub label: ArcStr,
+}
+
+/// Appends a `cue ` chunk and an associated-data list with the labels of the cues to a wav file.
+pub(super) fn append(file: &Path, cues: &[Cue], sample_rate: sample::Rate) -> io::Result<()> {
+    let mut points = Vec::new();
+    let mut labels = Vec::from(*b"adtl");
+
+    points.extend_from_slice(&riff_u32(cues.len()));
+
+    for (id, cue) in (1_u32..).zip(cues) {
+        let position = riff_u32((cue.position * sample_rate).index());
+
+        points.extend_from_slice(&riff_u32(id));
+        points.extend_from_slice(&position);
+        points.extend_from_slice(b"data");
+        // The start of the data chunk and of the block containing the sample.
+        points.extend_from_slice(&riff_u32(0_u32));
+        points.extend_from_slice(&riff_u32(0_u32));
+        points.extend_from_slice(&position);
+
+        let mut label = Vec::from(riff_u32(id));
+        label.extend_from_slice(cue.label.as_bytes());
+        label.push(0);
+
+        push_chunk(&mut labels, *b"labl", &label);
+    }
+
+    let mut chunks = Vec::new();
+    push_chunk(&mut chunks, *b"cue ", &points);
+    push_chunk(&mut chunks, *b"LIST", &labels);
+
+    let mut file = OpenOptions::new().read(true).write(true).open(file)?;
+
+    let length = file.seek(SeekFrom::End(0))?;
+    file.write_all(&chunks)?;
+
+    let riff_size = length
+        .saturating_add(chunks.len() as u64)
+        .saturating_sub(RIFF_HEADER_SIZE);
+
+    file.seek(SeekFrom::Start(RIFF_SIZE_OFFSET))?;
+    file.write_all(&riff_u32(riff_size))?;
+
+    Ok(())
+}
+
+/// Appends a RIFF chunk, padded to an even length, to a buffer.
+fn push_chunk(buffer: &mut Vec<u8>, id: [u8; 4], data: &[u8]) {
+    buffer.extend_from_slice(&id);
+    buffer.extend_from_slice(&riff_u32(data.len()));
+    buffer.extend_from_slice(data);
+
+    if data.len() % 2 == 1 {
+        buffer.push(0);
+    }
+}
+
+/// Converts a number to the 32-bit little-endian representation used by RIFF, saturating it.
+#[expect(clippy::little_endian_bytes, reason = "RIFF is little-endian")]
+fn riff_u32<N: TryInto<u32>>(number: N) -> [u8; 4] {
+    number.try_into().unwrap_or(u32::MAX).to_le_bytes()
+}
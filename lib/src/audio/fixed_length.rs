@@ -1,6 +1,7 @@
 //! Items pertaining to [`FixedLength`].
 
 use crate::Audio;
+use crate::audio::sample;
 use crate::metre::Changing;
 use crate::metre::Instant;
 use crate::metre::NonZeroDuration;
@@ -23,6 +24,9 @@ pub struct FixedLength {
     pub audio: Audio,
     /// The length of the audio.
     pub duration: NonZeroDuration,
+    /// How much of the start of the audio is skipped.
+    #[serde(default, skip_serializing_if = "time::Duration::is_zero")]
+    pub offset: time::Duration,
 }
 
 impl FixedLength {
@@ -47,9 +51,90 @@ impl FixedLength {
         FixedLength {
             audio,
             duration: NonZeroDuration::from_duration(duration).unwrap_or(NonZeroDuration::QUARTER),
+            offset: time::Duration::ZERO,
         }
     }
 
+    /// Splits the audio, whose clip starts at `start`, at an instant.
+    ///
+    /// The sample data is divided between the two halves.
+    /// If the instant is not inside the clip, `None` is returned.
+    pub(crate) fn split_at(
+        &self,
+        start: Instant,
+        at: Instant,
+        time_context: &Changing<TimeContext>,
+    ) -> Option<(FixedLength, FixedLength)> {
+        let end = start + self.duration.get();
+
+        let first_duration = NonZeroDuration::from_duration(at - start)?;
+        let second_duration = NonZeroDuration::from_duration(end - at)?;
+
+        let split = self.offset + (at * time_context - start * time_context);
+        let split = sample::Instant {
+            since_start: split * self.audio.sample_rate,
+        };
+
+        let (first, second) = self.audio.split_at(split);
+
+        Some((
+            FixedLength {
+                audio: first,
+                duration: first_duration,
+                offset: self.offset,
+            },
+            FixedLength {
+                audio: second,
+                duration: second_duration,
+                offset: time::Duration::ZERO,
+            },
+        ))
+    }
+
+    /// Joins the audio, whose clip starts at `start`,
+    /// with the audio of a clip starting where this one ends.
+    ///
+    /// Parts of the audio outside the clips are left out
+    /// and the audio is extended with silence where it is too short.
+    pub(crate) fn joined(
+        &self,
+        start: Instant,
+        other: &FixedLength,
+        time_context: &Changing<TimeContext>,
+    ) -> Option<FixedLength> {
+        let sample_rate = self.audio.sample_rate;
+
+        let middle = start + self.duration.get();
+        let end = middle + other.duration.get();
+
+        let duration = NonZeroDuration::from_duration(end - start)?;
+
+        let first_duration = (middle * time_context - start * time_context) * sample_rate;
+        let second_duration = (end * time_context - middle * time_context) * sample_rate;
+
+        let mut audio = Audio::empty(sample_rate);
+
+        audio.superpose_cropped(
+            &self.audio,
+            sample::Duration::ZERO,
+            self.offset * sample_rate,
+            first_duration,
+        );
+        audio.extend_to(first_duration);
+        audio.superpose_cropped(
+            &other.audio,
+            first_duration,
+            other.offset * sample_rate,
+            second_duration,
+        );
+
+        Some(FixedLength {
+            audio,
+            duration,
+            offset: time::Duration::ZERO,
+        })
+    }
+
     /// Draws an overview of the audio.
     pub(crate) fn overview_painter(
         &self,
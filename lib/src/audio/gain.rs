@@ -0,0 +1,32 @@
+//! Items pertaining to [`Gain`].
+
+use serde::Deserialize;
+use serde::Serialize;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// An amplification (or attenuation) of audio.
+#[derive(Copy, Clone, PartialEq, PartialOrd, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Gain {
+    /// The gain in decibels.
+    pub decibels: f32,
+}
+
+impl Gain {
+    /// No amplification.
+    pub const UNITY: Gain = Gain { decibels: 0.0 };
+
+    /// Returns the factor by which sample values are multiplied.
+    #[must_use]
+    pub fn amplitude(self) -> f32 {
+        10_f32.powf(self.decibels / 20.0)
+    }
+}
+
+impl Display for Gain {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:+.1} dB", self.decibels)
+    }
+}
@@ -33,6 +33,17 @@ impl InterleavedSamples<'_> {
     pub(super) fn skip_forward(&mut self, duration: Duration) {
         self.position += duration;
     }
+
+    /// Returns whether the next sample to return is the left sample at the given instant.
+    pub(super) fn is_at(&self, instant: Instant) -> bool {
+        self.position == instant && !self.right_channel
+    }
+
+    /// Moves the iterator to the left sample at the given instant.
+    pub(super) fn jump_to(&mut self, instant: Instant) {
+        self.position = instant;
+        self.right_channel = false;
+    }
 }
 
 impl Iterator for InterleavedSamples<'_> {
@@ -0,0 +1,106 @@
+//! Items pertaining to [`Linked`].
+
+use crate::Audio;
+use crate::audio::FixedLength;
+use crate::audio::ImportError;
+use crate::metre::NonZeroDuration;
+use crate::time;
+use getset::CopyGetters;
+use getset::Getters;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::OnceLock;
+use thiserror::Error;
+
+/// An audio clip of a fixed [metric duration](crate::metre::Duration),
+/// whose audio is stored in a separate file.
+///
+/// The file is decoded the first time the audio is needed and is then kept in memory.
+#[derive(Clone, Debug, Serialize, Deserialize, Getters, CopyGetters)]
+pub struct Linked {
+    /// The path to the audio file, relative to the directory of the project.
+    #[get = "pub"]
+    path: PathBuf,
+    /// The length of the audio.
+    #[get_copy = "pub"]
+    duration: NonZeroDuration,
+    /// How much of the start of the audio is skipped.
+    #[serde(default, skip_serializing_if = "time::Duration::is_zero")]
+    #[get_copy = "pub"]
+    offset: time::Duration,
+    /// The decoded audio.
+    ///
+    /// This is shared between copies of the clip, so that the file is only decoded once.
+    #[serde(skip)]
+    cache: Arc<OnceLock<Audio>>,
+}
+
+/// An error when reading the file of a [linked audio clip](Linked).
+#[derive(Debug, Error)]
+#[error("could not read the linked audio file {}: {error}", .path.display())]
+pub struct LinkError {
+    /// The path to the file.
+    pub path: PathBuf,
+    /// The reason the file could not be read.
+    #[source]
+    pub error: ImportError,
+}
+
+impl Linked {
+    /// Links a [fixed-length audio clip](FixedLength) to a file that contains the same audio.
+    ///
+    /// The audio is kept in memory, so the file is not decoded.
+    pub(crate) fn from_fixed_length(audio: FixedLength, path: PathBuf) -> Linked {
+        let FixedLength {
+            audio,
+            duration,
+            offset,
+        } = audio;
+
+        Linked {
+            path,
+            duration,
+            offset,
+            cache: Arc::new(OnceLock::from(audio)),
+        }
+    }
+
+    /// Returns the audio, decoding the file if it has not yet been decoded.
+    ///
+    /// The path of the file is resolved relative to `directory`.
+    pub(crate) fn audio(&self, directory: &Path) -> Result<&Audio, LinkError> {
+        if let Some(audio) = self.cache.get() {
+            return Ok(audio);
+        }
+
+        let path = directory.join(&self.path);
+
+        let audio = Audio::read_from_file(&path).map_err(|error| LinkError { path, error })?;
+
+        Ok(self.cache.get_or_init(|| audio))
+    }
+
+    /// Returns a copy of the clip with another duration and offset.
+    ///
+    /// The copy shares the decoded audio with the original.
+    pub(crate) fn with_extent(&self, duration: NonZeroDuration, offset: time::Duration) -> Linked {
+        Linked {
+            path: self.path.clone(),
+            duration,
+            offset,
+            cache: Arc::clone(&self.cache),
+        }
+    }
+}
+
+impl PartialEq for Linked {
+    fn eq(&self, other: &Linked) -> bool {
+        // The cache is only a copy of the file's content.
+        self.path == other.path && self.duration == other.duration && self.offset == other.offset
+    }
+}
+
+impl Eq for Linked {}
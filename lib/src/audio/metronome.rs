@@ -0,0 +1,208 @@
+//! Items pertaining to the [metronome](Metronome).
+
+use crate::Audio;
+use crate::Project;
+use crate::Ratio;
+use crate::audio::Gain;
+use crate::audio::Sample;
+use crate::audio::sample;
+use crate::metre::Changing;
+use crate::metre::TimeContext;
+use crate::metre::TimeSignature;
+use crate::time;
+use enum_iterator::Sequence;
+use getset::CopyGetters;
+use getset::Setters;
+use std::f32::consts::TAU;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// The frequency of the click on the first beat of a measure, in hertz.
+const ACCENT_FREQUENCY: f32 = 1760.0;
+/// The frequency of the clicks on the other beats, in hertz.
+const BEAT_FREQUENCY: f32 = 880.0;
+/// The amplitude of a click at unity gain.
+const CLICK_AMPLITUDE: f32 = 0.5;
+/// The rate at which a click fades out, per second.
+const CLICK_DECAY: f32 = 100.0;
+/// The duration of a click.
+const CLICK_DURATION: time::Duration = time::Duration {
+    nanoseconds: 50_000_000,
+};
+
+/// Volatile settings for the metronome.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Setters, CopyGetters)]
+pub(crate) struct Metronome {
+    /// Whether the metronome clicks during playback.
+    #[set = "pub(crate)"]
+    #[get_copy = "pub(crate)"]
+    is_enabled: bool,
+    /// The gain of the clicks.
+    #[set = "pub(crate)"]
+    #[get_copy = "pub(crate)"]
+    gain: Gain,
+    /// How many measures are counted in before playback starts.
+    #[set = "pub(crate)"]
+    #[get_copy = "pub(crate)"]
+    count_in: CountIn,
+}
+
+/// How many measures are counted in before playback starts.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default, Sequence)]
+pub(crate) enum CountIn {
+    /// Playback starts immediately.
+    #[default]
+    Off,
+    /// One measure is counted in.
+    OneMeasure,
+    /// Two measures are counted in.
+    TwoMeasures,
+}
+
+/// The clicks of a metronome in a project.
+///
+/// These are only ever mixed into playback, never into exports.
+#[derive(Clone, Debug)]
+pub(crate) struct ClickTrack {
+    /// The metronome settings.
+    metronome: Metronome,
+    /// The time signature of the project.
+    time_signature: Changing<TimeSignature>,
+    /// The time context of the project.
+    time_context: Changing<TimeContext>,
+}
+
+impl Metronome {
+    /// Returns the click track of the metronome for a project.
+    pub(crate) fn click_track(self, project: &Project) -> ClickTrack {
+        ClickTrack {
+            metronome: self,
+            time_signature: project.time_signature().clone(),
+            time_context: project.time_context(),
+        }
+    }
+}
+
+impl CountIn {
+    /// Returns the number of measures that are counted in.
+    fn measures(self) -> usize {
+        match self {
+            CountIn::Off => 0,
+            CountIn::OneMeasure => 1,
+            CountIn::TwoMeasures => 2,
+        }
+    }
+}
+
+impl Display for CountIn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CountIn::Off => write!(f, "off"),
+            CountIn::OneMeasure => write!(f, "1 measure"),
+            CountIn::TwoMeasures => write!(f, "2 measures"),
+        }
+    }
+}
+
+impl ClickTrack {
+    /// Mixes the clicks on every beat that can be heard during a period into audio,
+    /// unless the metronome is disabled.
+    ///
+    /// The first beat of every measure is accented.
+    /// Only the clicks in the period are synthesised, so that playback from late in a project
+    /// does not have to wait for the clicks before it.
+    pub(crate) fn click_into(&self, audio: &mut Audio, period: time::Period) {
+        if !self.metronome.is_enabled {
+            return;
+        }
+
+        let sample_rate = audio.sample_rate;
+
+        let accent = click(ACCENT_FREQUENCY, self.metronome.gain, sample_rate);
+        let beat = click(BEAT_FREQUENCY, self.metronome.gain, sample_rate);
+
+        for measure in self.time_signature.measures() {
+            if period.end() <= measure.start * &self.time_context {
+                break;
+            }
+
+            if measure.period().get().end() * &self.time_context + CLICK_DURATION <= period.start {
+                continue;
+            }
+
+            let beat_duration = measure.time_signature.beat_duration().get();
+
+            for index in 0..measure.time_signature.beats_per_measure.get() {
+                let start = measure.start + beat_duration * Ratio::integer(u64::from(index));
+                let start = start * &self.time_context;
+
+                // Clicks that fade out before the period are left out.
+                if start + CLICK_DURATION <= period.start || period.end() <= start {
+                    continue;
+                }
+
+                let position = start * sample_rate;
+                let click = if index == 0 { &accent } else { &beat };
+
+                audio.superpose_with_offset(click, position.since_start);
+            }
+        }
+    }
+
+    /// Returns the count-in before playback from an instant,
+    /// or `None` if nothing should be counted in.
+    ///
+    /// The count-in follows the tempo and time signature at the instant.
+    pub(crate) fn count_in(&self, from: time::Instant, sample_rate: sample::Rate) -> Option<Audio> {
+        let measures = self.metronome.count_in.measures();
+
+        if measures == 0 {
+            return None;
+        }
+
+        let start = from / &self.time_context;
+        let time_signature = self.time_signature.get(start);
+        let first_beat_end = (start + time_signature.beat_duration().get()) * &self.time_context;
+        let beat_duration = (first_beat_end - from) * sample_rate;
+
+        let accent = click(ACCENT_FREQUENCY, self.metronome.gain, sample_rate);
+        let beat = click(BEAT_FREQUENCY, self.metronome.gain, sample_rate);
+
+        let mut audio = Audio::empty(sample_rate);
+        let mut offset = sample::Duration::ZERO;
+
+        for _ in 0..measures {
+            for index in 0..time_signature.beats_per_measure.get() {
+                let click = if index == 0 { &accent } else { &beat };
+
+                audio.superpose_with_offset(click, offset);
+                offset += beat_duration;
+            }
+        }
+
+        audio.extend_to(offset);
+
+        Some(audio)
+    }
+}
+
+/// Synthesises a single click with the given frequency.
+fn click(frequency: f32, gain: Gain, sample_rate: sample::Rate) -> Audio {
+    let amplitude = CLICK_AMPLITUDE * gain.amplitude();
+
+    let samples: Vec<Sample> = (0..(CLICK_DURATION * sample_rate).samples)
+        .map(|index| {
+            #[expect(clippy::cast_precision_loss, reason = "clicks are short")]
+            let seconds = index as f32 / sample_rate.hz();
+            let envelope = (-seconds * CLICK_DECAY).exp();
+
+            Sample::new(amplitude * envelope * (TAU * frequency * seconds).sin())
+        })
+        .collect();
+
+    Audio {
+        sample_rate,
+        channels: [samples.clone(), samples],
+    }
+}
@@ -3,22 +3,36 @@
 pub mod sample;
 
 mod config;
+mod cue;
 mod fixed_length;
+mod gain;
 mod import;
 mod interleaved_samples;
+mod linked;
+mod metronome;
+mod pan;
 mod player;
 mod resample;
 mod source;
 mod subsection;
 
 pub use fixed_length::FixedLength;
+pub use gain::Gain;
 pub use import::ImportError;
 pub use interleaved_samples::InterleavedSamples;
+pub use linked::LinkError;
+pub use linked::Linked;
+pub use pan::Pan;
+pub use pan::PanLaw;
 #[doc(inline)]
 pub use sample::Sample;
 pub use subsection::Subsection;
 
 pub(crate) use config::Config;
+pub(crate) use cue::Cue;
+pub(crate) use metronome::ClickTrack;
+pub(crate) use metronome::CountIn;
+pub(crate) use metronome::Metronome;
 pub(crate) use player::Player;
 pub(crate) use source::Source;
 
@@ -29,6 +43,7 @@ use hound::WavWriter;
 use serde::Deserialize;
 use serde::Serialize;
 use std::cmp::max;
+use std::cmp::min;
 use std::path::Path;
 
 /// Some stereo 64-bit floating point audio.
@@ -102,6 +117,22 @@ impl Audio {
         self.duration() / self.sample_rate
     }
 
+    /// Multiplies the samples of the left and right channels by the respective factors,
+    /// which may differ from sample to sample.
+    pub(crate) fn amplify<F: Fn(sample::Instant) -> [f32; 2]>(&mut self, factors: F) {
+        let [left, right] = &mut self.channels;
+
+        for (index, (left, right)) in left.iter_mut().zip(right).enumerate() {
+            let instant = sample::Instant {
+                since_start: sample::Duration { samples: index },
+            };
+            let [left_factor, right_factor] = factors(instant);
+
+            *left = Sample::new(left.to_f32() * left_factor);
+            *right = Sample::new(right.to_f32() * right_factor);
+        }
+    }
+
     /// Superposes another audio clip onto this audio clip.
     pub(crate) fn superpose(&mut self, other: &Audio) {
         self.superpose_with_offset(other, sample::Duration::ZERO);
@@ -109,11 +140,31 @@ impl Audio {
 
     /// Superposes another audio clip (offset by an offset) onto this audio clip.
     pub(crate) fn superpose_with_offset(&mut self, other: &Audio, offset: sample::Duration) {
+        let whole = sample::Duration {
+            samples: usize::MAX,
+        };
+
+        self.superpose_cropped(other, offset, sample::Duration::ZERO, whole);
+    }
+
+    /// Superposes at most `duration` of another audio clip (offset by an offset)
+    /// onto this audio clip.
+    ///
+    /// The first `skip` of the other audio clip is left out.
+    pub(crate) fn superpose_cropped(
+        &mut self,
+        other: &Audio,
+        offset: sample::Duration,
+        skip: sample::Duration,
+        duration: sample::Duration,
+    ) {
         let other = other.resample(self.sample_rate);
+        let remaining = other.duration().samples.saturating_sub(skip.samples);
 
-        for index in 0..other.duration().samples {
-            let instant_in_other = sample::Instant::from_index(index);
-            let instant_in_self = instant_in_other + offset;
+        for index in 0..min(remaining, duration.samples) {
+            let instant = sample::Instant::from_index(index);
+            let instant_in_other = instant + skip;
+            let instant_in_self = instant + offset;
 
             let [self_left, self_right] = self.sample_pair_mut(instant_in_self);
             let [other_left, other_right] = other.sample_pair(instant_in_other);
@@ -123,6 +174,30 @@ impl Audio {
         }
     }
 
+    /// Splits the audio into the samples before an instant and the samples from it on.
+    #[must_use]
+    pub(crate) fn split_at(&self, instant: sample::Instant) -> (Audio, Audio) {
+        let [left, right] = &self.channels;
+
+        let (left_before, left_after) = left
+            .split_at_checked(instant.index())
+            .unwrap_or((left.as_slice(), &[]));
+        let (right_before, right_after) = right
+            .split_at_checked(instant.index())
+            .unwrap_or((right.as_slice(), &[]));
+
+        (
+            Audio {
+                sample_rate: self.sample_rate,
+                channels: [left_before.to_vec(), right_before.to_vec()],
+            },
+            Audio {
+                sample_rate: self.sample_rate,
+                channels: [left_after.to_vec(), right_after.to_vec()],
+            },
+        )
+    }
+
     /// Returns a subsection of the audio.
     #[must_use]
     pub fn subsection(&self, period: sample::Period) -> Subsection<'_> {
@@ -168,7 +243,9 @@ impl Audio {
     }
 
     /// Exports the clip to a file at the given path.
-    pub(crate) fn export(&self, to: &Path) -> Result<(), hound::Error> {
+    ///
+    /// The cues are written as labelled cue points.
+    pub(crate) fn export(&self, to: &Path, cues: &[Cue]) -> Result<(), hound::Error> {
         let spec = WavSpec {
             channels: 2,
             sample_rate: self.sample_rate.samples_per_second.get(),
@@ -181,6 +258,12 @@ impl Audio {
             writer.write_sample(sample.to_f32())?;
         }
 
+        writer.finalize()?;
+
+        if !cues.is_empty() {
+            cue::append(to, cues, self.sample_rate)?;
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,121 @@
+//! Items pertaining to [`Pan`].
+
+use enum_iterator::Sequence;
+use serde::Deserialize;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::f32::consts::FRAC_PI_2;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// A stereo position.
+#[derive(
+    Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Serialize, Deserialize,
+)]
+#[serde(from = "i8", into = "i8")]
+pub struct Pan {
+    /// The position in percent, where -100 is hard left and 100 is hard right.
+    ///
+    /// # Invariants
+    ///
+    /// This is on the interval [-100, 100].
+    percent: i8,
+}
+
+/// How [panning](Pan) affects the loudness of the two channels.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default, Sequence, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanLaw {
+    /// The centre is not attenuated; only the channel opposite to the pan is.
+    Balance,
+    /// The centre is attenuated by 4.5 dB, a compromise between the constant power and linear laws.
+    Compromise,
+    /// The centre is attenuated by 3 dB, keeping the power constant.
+    #[default]
+    ConstantPower,
+    /// The centre is attenuated by 6 dB, keeping the sum of the amplitudes constant.
+    Linear,
+}
+
+impl Pan {
+    /// The centre.
+    pub const CENTRE: Pan = Pan { percent: 0 };
+
+    /// The percentage of a hard left pan.
+    const MIN_PERCENT: i8 = -100;
+    /// The percentage of a hard right pan.
+    const MAX_PERCENT: i8 = 100;
+
+    /// Constructs a new pan from a percentage.
+    ///
+    /// If it is not in range, it is clamped.
+    #[must_use]
+    pub fn new(percent: i8) -> Pan {
+        Pan {
+            percent: percent.clamp(Pan::MIN_PERCENT, Pan::MAX_PERCENT),
+        }
+    }
+
+    /// Returns the position in percent, where -100 is hard left and 100 is hard right.
+    #[must_use]
+    pub fn percent(self) -> i8 {
+        self.percent
+    }
+
+    /// Moves the pan by some percentage points.
+    #[must_use]
+    pub fn moved_by(self, percent: i8) -> Pan {
+        Pan::new(self.percent.saturating_add(percent))
+    }
+
+    /// Returns the factors by which the left and right channels are multiplied.
+    #[must_use]
+    pub fn amplitudes(self, law: PanLaw) -> [f32; 2] {
+        // How far to the right the pan is, on the interval [0, 1].
+        let right = f32::midpoint(f32::from(self.percent) / f32::from(Pan::MAX_PERCENT), 1.0);
+        let left = 1.0 - right;
+
+        let angle = right * FRAC_PI_2;
+
+        match law {
+            PanLaw::Balance => [(2.0 * left).min(1.0), (2.0 * right).min(1.0)],
+            PanLaw::Compromise => [(left * angle.cos()).sqrt(), (right * angle.sin()).sqrt()],
+            PanLaw::ConstantPower => [angle.cos(), angle.sin()],
+            PanLaw::Linear => [left, right],
+        }
+    }
+}
+
+impl From<i8> for Pan {
+    fn from(percent: i8) -> Pan {
+        Pan::new(percent)
+    }
+}
+
+impl From<Pan> for i8 {
+    fn from(pan: Pan) -> i8 {
+        pan.percent
+    }
+}
+
+impl Display for Pan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.percent.cmp(&0) {
+            Ordering::Less => write!(f, "L{}", self.percent.unsigned_abs()),
+            Ordering::Equal => write!(f, "C"),
+            Ordering::Greater => write!(f, "R{}", self.percent),
+        }
+    }
+}
+
+impl Display for PanLaw {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PanLaw::Balance => write!(f, "0 dB"),
+            PanLaw::Compromise => write!(f, "-4.5 dB"),
+            PanLaw::ConstantPower => write!(f, "-3 dB"),
+            PanLaw::Linear => write!(f, "-6 dB"),
+        }
+    }
+}
@@ -1,11 +1,16 @@
 //! Items pertaining to [`Player`].
 
 use crate::Audio;
+use crate::audio::ClickTrack;
 use crate::audio::Source;
+use crate::sync::Cell;
 use crate::time::Duration;
 use crate::time::Instant;
+use crate::time::Period;
 use derive_more::Debug;
 use rodio::Sink;
+use std::cmp::max;
+use std::cmp::min;
 use std::sync::Arc;
 
 /// An audio player.
@@ -16,6 +21,10 @@ pub(crate) struct Player {
     /// The underlying audio sink.
     #[debug(skip)]
     sink: Arc<Sink>,
+    /// The region that is being repeated, if the player is looping.
+    loop_region: Arc<Cell<Option<Period>>>,
+    /// The period of the sink's position that is taken up by the count-in, if there is one.
+    count_in: Arc<Cell<Option<Period>>>,
 }
 
 impl Player {
@@ -25,10 +34,45 @@ impl Player {
     }
 
     /// Returns the position if audio is playing or if it has reached the end.
+    ///
+    /// If the player is looping, the position is wrapped into the loop region.
+    /// During the count-in, `None` is returned.
     pub(crate) fn position(&self) -> Option<Instant> {
-        (!self.sink.is_paused()).then_some(Instant {
+        if self.sink.is_paused() {
+            return None;
+        }
+
+        let mut position = Instant {
             since_start: Duration::from(self.sink.get_pos()),
-        })
+        };
+
+        if let Some(count_in) = self.count_in.get() {
+            if position < count_in.end() {
+                return None;
+            }
+
+            position -= count_in.duration;
+        }
+
+        let Some(region) = self.loop_region.get() else {
+            return Some(position);
+        };
+
+        if position < region.end() {
+            return Some(position);
+        }
+
+        let overshoot = (position - region.end()).nanoseconds;
+        let into_region = overshoot
+            .checked_rem(region.duration.nanoseconds)
+            .unwrap_or_default();
+
+        Some(
+            region.start
+                + Duration {
+                    nanoseconds: into_region,
+                },
+        )
     }
 
     /// Pauses the audio player.
@@ -39,9 +83,46 @@ impl Player {
     }
 
     /// Plays the given audio starting at the given position.
-    pub(crate) fn play(&self, audio: Audio, from: Instant) {
+    ///
+    /// If a loop region is given and playback starts before its end, the region is repeated.
+    /// The clicks and count-in of the click track are mixed into the audio.
+    pub(crate) fn play(
+        &self,
+        mut audio: Audio,
+        from: Instant,
+        loop_region: Option<Period>,
+        click_track: &ClickTrack,
+    ) {
+        let loop_region = loop_region.filter(|region| from < region.end());
+
+        let end = max(
+            Instant {
+                since_start: audio.real_duration(),
+            },
+            loop_region.map_or(Instant::START, |region| region.end()),
+        );
+
+        // Only the clicks from where playback starts, or from where it may loop back to, are heard.
+        let start = loop_region.map_or(from, |region| min(from, region.start));
+
+        click_track.click_into(
+            &mut audio,
+            Period {
+                start,
+                duration: end - start,
+            },
+        );
+
+        let count_in = click_track.count_in(from, audio.sample_rate);
+
+        self.loop_region.set(loop_region);
+        self.count_in.set(count_in.as_ref().map(|count_in| Period {
+            start: from,
+            duration: count_in.real_duration(),
+        }));
+
         self.sink.clear();
-        self.sink.append(Source::new(audio));
+        self.sink.append(Source::new(audio, loop_region, count_in));
 
         // `audio::Source::try_seek` always returns `Ok`
         let _ok = self.sink.try_seek(from.since_start.into());
@@ -56,6 +137,8 @@ impl From<Sink> for Player {
 
         Player {
             sink: Arc::new(sink),
+            loop_region: Arc::new(Cell::new(None)),
+            count_in: Arc::new(Cell::new(None)),
         }
     }
 }
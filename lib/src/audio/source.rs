@@ -3,6 +3,7 @@
 use crate::Audio;
 use crate::audio::InterleavedSamples;
 use crate::audio::Sample;
+use crate::audio::sample;
 use crate::time;
 use rodio::source::SeekError;
 use std::time::Duration;
@@ -15,14 +16,46 @@ pub struct Source {
     total_duration: Duration,
     /// The samples.
     samples: InterleavedSamples<'static>,
+    /// The period that is repeated, if the source is looping.
+    loop_region: Option<sample::Period>,
+    /// The samples of the count-in that is played before the audio, if any.
+    count_in: Option<InterleavedSamples<'static>>,
 }
 
 impl Source {
     /// Constructs a new source from an audio clip.
-    pub(super) fn new(audio: Audio) -> Source {
+    ///
+    /// If a loop region is given, the source jumps back to its start whenever its end is reached.
+    /// If a count-in is given, it is played before the audio.
+    /// It should have the same sample rate as the audio.
+    pub(super) fn new(
+        mut audio: Audio,
+        loop_region: Option<time::Period>,
+        count_in: Option<Audio>,
+    ) -> Source {
+        let rate = audio.sample_rate;
+
+        let loop_region = loop_region
+            .map(|region| sample::Period {
+                start: region.start * rate,
+                duration: region.end() * rate - region.start * rate,
+            })
+            .filter(|region| region.duration != sample::Duration::ZERO);
+
+        // The region may extend past the end of the audio, which is then padded with silence.
+        if let Some(region) = loop_region {
+            audio.extend_to(region.end().since_start);
+        }
+
+        let count_in_duration = count_in
+            .as_ref()
+            .map_or(time::Duration::ZERO, Audio::real_duration);
+
         Source {
-            total_duration: audio.real_duration().into(),
+            total_duration: (audio.real_duration() + count_in_duration).into(),
             samples: audio.into_interleaved_samples(),
+            loop_region,
+            count_in: count_in.map(Audio::into_interleaved_samples),
         }
     }
 }
@@ -31,6 +64,16 @@ impl Iterator for Source {
     type Item = f32;
 
     fn next(&mut self) -> Option<f32> {
+        if let Some(sample) = self.count_in.as_mut().and_then(Iterator::next) {
+            return Some(sample.to_f32());
+        }
+
+        if let Some(region) = self.loop_region
+            && self.samples.is_at(region.end())
+        {
+            self.samples.jump_to(region.start);
+        }
+
         self.samples.next().map(Sample::to_f32)
     }
 }
@@ -49,7 +92,8 @@ impl rodio::Source for Source {
     }
 
     fn total_duration(&self) -> Option<Duration> {
-        Some(self.total_duration)
+        // A looping source never ends.
+        self.loop_region.is_none().then_some(self.total_duration)
     }
 
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
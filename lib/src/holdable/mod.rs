@@ -7,6 +7,9 @@ pub use window_size::WindowSide;
 use crate::Id;
 use crate::Popup;
 use crate::metre::Instant;
+use crate::note;
+use crate::note::Pitch;
+use crate::project::Track;
 use crate::project::track::clip;
 use crate::ui::Length;
 use crate::ui::Point;
@@ -19,11 +22,36 @@ pub enum Holdable {
     // TODO: add a relative point
     /// A clip in the track workspace.
     Clip(clip::Path),
+    /// The end of a clip, to resize it.
+    ClipEnd(clip::Path),
+    /// The start of a clip, to trim it.
+    ClipStart(clip::Path),
+    /// A loop region in the process of being dragged out on a ruler.
+    LoopRegion {
+        /// Where the region should start.
+        start: Instant,
+    },
+    /// A note in the piano roll, to move it along with the other selected notes.
+    Note {
+        /// The note.
+        note: note::Path,
+        /// The (quantised) instant at which the note was grabbed.
+        instant: Instant,
+        /// The pitch of the row in which the note was grabbed.
+        pitch: Pitch,
+    },
     /// A note in the process of being created.
     NoteCreation {
         /// Where the note should start.
         start: Instant,
     },
+    /// The end of a note in the piano roll, to resize it along with the other selected notes.
+    NoteEnd {
+        /// The note.
+        note: note::Path,
+        /// Where the note ends.
+        end: Instant,
+    },
     /// The title bar of the piano roll.
     PianoRollHandle {
         /// How far down, on the handle, it was grabbed.
@@ -48,4 +76,6 @@ pub enum Holdable {
         /// The point where the selection started.
         start: Point,
     },
+    /// A track, by the title of its settings, to move it in the track list.
+    Track(Id<Track>),
 }
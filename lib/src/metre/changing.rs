@@ -5,6 +5,7 @@ use crate::metre::NonZeroInstant;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::BTreeMap;
+use std::mem::replace;
 
 // TODO: Use a `serde(with)` that uses `Into<T> for &Self` instead of the `Clone` bound.
 /// A setting that changes over time.
@@ -16,19 +17,57 @@ pub struct Changing<T> {
     /// The starting value.
     pub start: T,
     /// The changes.
-    #[serde(default)]
     pub changes: BTreeMap<NonZeroInstant, T>,
 }
 
+impl<T> Changing<T> {
+    /// Sets the setting at the given instant, returning the value that was previously set there.
+    pub(crate) fn set(&mut self, instant: Instant, value: T) -> Option<T> {
+        match NonZeroInstant::from_instant(instant) {
+            Some(instant) => self.changes.insert(instant, value),
+            None => Some(replace(&mut self.start, value)),
+        }
+    }
+
+    /// Removes the change at the given instant, returning it.
+    ///
+    /// The starting value cannot be removed.
+    pub(crate) fn remove(&mut self, instant: Instant) -> Option<T> {
+        self.changes.remove(&NonZeroInstant::from_instant(instant)?)
+    }
+
+    /// Sets the setting at the given instant if `value` is some, and removes the change otherwise.
+    ///
+    /// Returns the value that was previously set at the instant.
+    pub(crate) fn set_or_remove(&mut self, instant: Instant, value: Option<T>) -> Option<T> {
+        match value {
+            Some(value) => self.set(instant, value),
+            None => self.remove(instant),
+        }
+    }
+
+    /// Returns the instant of the last change at or before the given instant.
+    pub(crate) fn last_change(&self, instant: Instant) -> Option<Instant> {
+        let end = NonZeroInstant::from_instant(instant)?;
+
+        self.changes
+            .range(..=end)
+            .next_back()
+            .map(|(change, _)| change.get())
+    }
+}
+
 impl<T: Copy> Changing<T> {
     /// Gets the setting at the given instant.
+    ///
+    /// A change applies from its own instant onwards.
     pub fn get(&self, instant: Instant) -> T {
         let Some(end) = NonZeroInstant::from_instant(instant) else {
             return self.start;
         };
 
         self.changes
-            .range(..end)
+            .range(..=end)
             .next_back()
             .map_or(self.start, |(_, value)| *value)
     }
@@ -54,10 +93,19 @@ enum Serial<T> {
         /// The starting value.
         start: T,
         /// The timeline of changes to the value.
-        changes: BTreeMap<NonZeroInstant, T>,
+        changes: Vec<Change<T>>,
     },
 }
 
+/// The serial representation of a change of a [changing setting](Changing).
+#[derive(Clone, Serialize, Deserialize)]
+struct Change<T> {
+    /// The instant of the change.
+    at: NonZeroInstant,
+    /// The value from the change onwards.
+    value: T,
+}
+
 impl<T> From<Changing<T>> for Serial<T> {
     fn from(changing: Changing<T>) -> Self {
         let Changing { start, changes } = changing;
@@ -65,7 +113,13 @@ impl<T> From<Changing<T>> for Serial<T> {
         if changes.is_empty() {
             Serial::Constant(start)
         } else {
-            Serial::Changing { start, changes }
+            Serial::Changing {
+                start,
+                changes: changes
+                    .into_iter()
+                    .map(|(at, value)| Change { at, value })
+                    .collect(),
+            }
         }
     }
 }
@@ -73,11 +127,54 @@ impl<T> From<Changing<T>> for Serial<T> {
 impl<T> From<Serial<T>> for Changing<T> {
     fn from(serial: Serial<T>) -> Self {
         match serial {
-            Serial::Constant(start) => Changing {
+            Serial::Constant(start) => Changing::from(start),
+            Serial::Changing { start, changes } => Changing {
                 start,
-                changes: BTreeMap::new(),
+                changes: changes
+                    .into_iter()
+                    .map(|Change { at, value }| (at, value))
+                    .collect(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::Ratio;
+    use crate::metre::Duration;
+    use anyhow::ensure;
+
+    /// Returns the instant a number of whole notes after the start.
+    fn whole_notes(count: u64) -> Instant {
+        Instant {
+            since_start: Duration {
+                whole_notes: Ratio::integer(count),
             },
-            Serial::Changing { start, changes } => Changing { start, changes },
         }
     }
+
+    #[test]
+    fn apply_changes_from_their_instant() -> anyhow::Result<()> {
+        let mut changing = Changing::from(1);
+        changing.set(whole_notes(2), 2);
+
+        ensure!(changing.get(Instant::START) == 1, "the start was changed");
+        ensure!(
+            changing.get(whole_notes(1)) == 1,
+            "the change applied before its instant"
+        );
+        ensure!(
+            changing.get(whole_notes(2)) == 2,
+            "the change did not apply at its instant"
+        );
+        ensure!(
+            changing.get(whole_notes(3)) == 2,
+            "the change did not apply after its instant"
+        );
+
+        Ok(())
+    }
 }
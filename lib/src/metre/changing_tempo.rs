@@ -0,0 +1,163 @@
+//! Items pertaining to [`ChangingTempo`].
+
+use crate::metre::Changing;
+use crate::metre::Instant;
+use crate::metre::NonZeroInstant;
+use crate::metre::Ramp;
+use crate::time::Tempo;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A tempo that changes over time, either instantly or gradually.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default, Serialize, Deserialize)]
+#[serde(from = "Serial", into = "Serial")]
+pub struct ChangingTempo {
+    /// The tempi.
+    pub values: Changing<Tempo>,
+    /// The changes that are approached gradually rather than instantly, and how they are.
+    pub ramps: BTreeMap<NonZeroInstant, Ramp>,
+}
+
+impl ChangingTempo {
+    /// Gets the tempo at the given instant, disregarding ramps.
+    #[must_use]
+    pub fn get(&self, instant: Instant) -> Tempo {
+        self.values.get(instant)
+    }
+
+    /// Sets the tempo at the given instant, returning the tempo that was previously set there.
+    pub(crate) fn set(&mut self, instant: Instant, tempo: Tempo) -> Option<Tempo> {
+        self.values.set(instant, tempo)
+    }
+
+    /// Removes the change at the given instant, returning it.
+    ///
+    /// The starting tempo cannot be removed.
+    pub(crate) fn remove(&mut self, instant: Instant) -> Option<Tempo> {
+        self.values.remove(instant)
+    }
+
+    /// Sets the tempo at the given instant if `tempo` is some, and removes the change otherwise.
+    ///
+    /// Returns the tempo that was previously set at the instant.
+    pub(crate) fn set_or_remove(
+        &mut self,
+        instant: Instant,
+        tempo: Option<Tempo>,
+    ) -> Option<Tempo> {
+        self.values.set_or_remove(instant, tempo)
+    }
+
+    /// Returns the instant of the last change at or before the given instant.
+    pub(crate) fn last_change(&self, instant: Instant) -> Option<Instant> {
+        self.values.last_change(instant)
+    }
+
+    /// Returns how the change at the given instant is approached, if it is approached gradually.
+    pub(crate) fn ramp(&self, instant: Instant) -> Option<Ramp> {
+        self.ramps
+            .get(&NonZeroInstant::from_instant(instant)?)
+            .copied()
+    }
+
+    /// Sets how the change at the given instant is approached.
+    /// `None` means that it is approached instantly.
+    ///
+    /// Returns how it was previously approached.
+    /// The starting tempo cannot be approached gradually.
+    pub(crate) fn set_ramp(&mut self, instant: Instant, ramp: Option<Ramp>) -> Option<Ramp> {
+        let instant = NonZeroInstant::from_instant(instant)?;
+
+        match ramp {
+            Some(ramp) => self.ramps.insert(instant, ramp),
+            None => self.ramps.remove(&instant),
+        }
+    }
+}
+
+impl From<Tempo> for ChangingTempo {
+    fn from(start: Tempo) -> ChangingTempo {
+        ChangingTempo {
+            values: Changing::from(start),
+            ramps: BTreeMap::new(),
+        }
+    }
+}
+
+/// The serial representation of [`ChangingTempo`].
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Serial {
+    /// A single tempo.
+    Constant(Tempo),
+    /// A starting tempo and timeline of changes.
+    Changing {
+        /// The starting tempo.
+        start: Tempo,
+        /// The timeline of changes to the tempo.
+        changes: Vec<Change>,
+    },
+}
+
+/// The serial representation of a change of a [changing tempo](ChangingTempo).
+#[derive(Clone, Serialize, Deserialize)]
+struct Change {
+    /// The instant of the change.
+    at: NonZeroInstant,
+    /// The tempo from the change onwards.
+    value: Tempo,
+    /// How the change is approached, if not instantly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ramp: Option<Ramp>,
+}
+
+impl From<ChangingTempo> for Serial {
+    fn from(tempo: ChangingTempo) -> Self {
+        let ChangingTempo {
+            values: Changing { start, changes },
+            mut ramps,
+        } = tempo;
+
+        if changes.is_empty() {
+            Serial::Constant(start)
+        } else {
+            Serial::Changing {
+                start,
+                changes: changes
+                    .into_iter()
+                    .map(|(at, value)| Change {
+                        at,
+                        value,
+                        ramp: ramps.remove(&at),
+                    })
+                    .collect(),
+            }
+        }
+    }
+}
+
+impl From<Serial> for ChangingTempo {
+    fn from(serial: Serial) -> Self {
+        match serial {
+            Serial::Constant(start) => ChangingTempo::from(start),
+            Serial::Changing { start, changes } => {
+                let ramps = changes
+                    .iter()
+                    .filter_map(|change| Some((change.at, change.ramp?)))
+                    .collect();
+
+                ChangingTempo {
+                    values: Changing {
+                        start,
+                        changes: changes
+                            .into_iter()
+                            .map(|Change { at, value, .. }| (at, value))
+                            .collect(),
+                    },
+                    ramps,
+                }
+            }
+        }
+    }
+}
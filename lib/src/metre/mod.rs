@@ -7,16 +7,19 @@
 pub mod relative;
 
 mod changing;
+mod changing_tempo;
 mod duration;
 mod instant;
 mod measure;
 mod offset_mapping;
 mod period;
 mod quantisation;
+mod ramp;
 mod time_context;
 mod time_signature;
 
 pub use changing::Changing;
+pub use changing_tempo::ChangingTempo;
 pub use duration::Duration;
 pub use duration::NonZeroDuration;
 pub use instant::Instant;
@@ -26,5 +29,6 @@ pub use offset_mapping::OffsetMapping;
 pub use period::NonZeroPeriod;
 pub use period::Period;
 pub use quantisation::Quantisation;
+pub use ramp::Ramp;
 pub use time_context::TimeContext;
 pub use time_signature::TimeSignature;
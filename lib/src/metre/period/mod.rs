@@ -4,10 +4,14 @@ mod non_zero;
 
 pub use non_zero::NonZeroPeriod;
 
+use crate::metre::Changing;
 use crate::metre::Duration;
 use crate::metre::Instant;
+use crate::metre::TimeContext;
+use crate::time;
 use std::cmp::max;
 use std::cmp::min;
+use std::ops::Mul;
 use std::ops::Range;
 
 /// A period of musical time.
@@ -64,3 +68,17 @@ impl Period {
         self.range().contains(&instant)
     }
 }
+
+impl Mul<&Changing<TimeContext>> for Period {
+    type Output = time::Period;
+
+    fn mul(self, rhs: &Changing<TimeContext>) -> time::Period {
+        let start = self.start * rhs;
+        let end = self.end() * rhs;
+
+        time::Period {
+            start,
+            duration: end - start,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Items pertaining to [`Ramp`].
+
+use enum_iterator::Sequence;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// How a [changing tempo](super::ChangingTempo) gradually approaches a change.
+///
+/// The ramp starts at the preceding change and ends at the change it approaches.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Sequence, Serialize, Deserialize)]
+#[remain::sorted]
+#[serde(rename_all = "snake_case")]
+pub enum Ramp {
+    /// The rate changes by a constant factor per unit of distance.
+    Exponential,
+    /// The rate changes by a constant amount per unit of distance.
+    Linear,
+}
+
+impl Ramp {
+    /// Returns the rate at some progress, from 0 to 1, through a ramp from `start` to `end`.
+    pub(crate) fn rate(self, start: f64, end: f64, progress: f64) -> f64 {
+        match self {
+            Ramp::Exponential => start * (end / start).powf(progress),
+            Ramp::Linear => start + (end - start) * progress,
+        }
+    }
+
+    /// Returns the time it takes to cover `distance` of a ramp of length `length`,
+    /// during which the rate goes from `start` to `end`.
+    ///
+    /// The rates are measured in units of distance per unit of time.
+    pub(crate) fn time(self, start: f64, end: f64, length: f64, distance: f64) -> f64 {
+        if start.total_cmp(&end).is_eq() {
+            return distance / start;
+        }
+
+        match self {
+            Ramp::Exponential => {
+                let ratio_logarithm = (end / start).ln();
+
+                length / (start * ratio_logarithm)
+                    * (1.0 - (-ratio_logarithm * distance / length).exp())
+            }
+            Ramp::Linear => {
+                let rate = self.rate(start, end, distance / length);
+
+                length / (end - start) * (rate / start).ln()
+            }
+        }
+    }
+
+    /// The inverse of [`Ramp::time`].
+    pub(crate) fn distance(self, start: f64, end: f64, length: f64, time: f64) -> f64 {
+        if start.total_cmp(&end).is_eq() {
+            return time * start;
+        }
+
+        match self {
+            Ramp::Exponential => {
+                let ratio_logarithm = (end / start).ln();
+
+                -length / ratio_logarithm * (1.0 - time * start * ratio_logarithm / length).ln()
+            }
+            Ramp::Linear => {
+                start * length / (end - start) * ((time * (end - start) / length).exp() - 1.0)
+            }
+        }
+    }
+}
+
+impl Display for Ramp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Ramp::Exponential => write!(f, "exponential"),
+            Ramp::Linear => write!(f, "linear"),
+        }
+    }
+}
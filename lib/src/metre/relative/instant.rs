@@ -16,6 +16,16 @@ pub struct Instant {
     pub since_start: Duration,
 }
 
+impl Add<Duration> for Instant {
+    type Output = Instant;
+
+    fn add(self, rhs: Duration) -> Instant {
+        Instant {
+            since_start: self.since_start + rhs,
+        }
+    }
+}
+
 impl Add<Instant> for metre::Instant {
     type Output = metre::Instant;
 
@@ -1,12 +1,16 @@
 //! Items pertaining to [`TimeContext`].
 
+use crate::NonZeroRatio;
+use crate::Ratio;
 use crate::metre;
 use crate::metre::Changing;
+use crate::metre::ChangingTempo;
+use crate::metre::Ramp;
 use crate::metre::TimeSignature;
 use crate::time;
 use crate::time::Tempo;
-use std::cmp::min;
-use std::collections::BTreeMap;
+use std::ops::Bound::Excluded;
+use std::ops::Bound::Unbounded;
 use std::ops::Div;
 use std::ops::Mul;
 
@@ -17,6 +21,122 @@ pub struct TimeContext {
     tempo: Tempo,
     /// The time signature.
     time_signature: TimeSignature,
+    /// The rest of the tempo ramp that the context is on, if any.
+    ramp: Option<TempoRamp>,
+}
+
+/// The rest of a gradual change of tempo.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+struct TempoRamp {
+    /// The tempo at the end of the ramp.
+    to: Tempo,
+    /// The duration of the rest of the ramp.
+    duration: metre::NonZeroDuration,
+    /// The shape of the ramp.
+    shape: Ramp,
+}
+
+impl TimeContext {
+    /// Converts a musical duration to real time.
+    ///
+    /// Without a ramp, the conversion is exact up until the final rounding to nanoseconds.
+    /// With one, it is computed with floats,
+    /// but converting the result back to musical time still lands on the same nanosecond.
+    fn real_duration(self, duration: metre::Duration) -> time::Duration {
+        let beat = self.time_signature.beat_duration();
+        let beats = duration / beat;
+
+        let Some(ramp) = self.ramp else {
+            return time::Duration::MINUTE * (beats / self.tempo.bpm());
+        };
+
+        let start = self.tempo.bpm().get().to_float();
+        let end = ramp.to.bpm().get().to_float();
+        let length = (ramp.duration.get() / beat).to_float();
+        let beats = beats.to_float();
+
+        let minutes = if beats <= length {
+            ramp.shape.time(start, end, length, beats)
+        } else {
+            ramp.shape.time(start, end, length, length) + (beats - length) / end
+        };
+
+        time::Duration::MINUTE * Ratio::approximate(minutes)
+    }
+
+    /// Converts a real duration to musical time.
+    fn musical_duration(self, duration: time::Duration) -> metre::Duration {
+        let beat = self.time_signature.beat_duration().get();
+        let minutes = duration / time::NonZeroDuration::MINUTE;
+
+        let Some(ramp) = self.ramp else {
+            return beat * (minutes * self.tempo.bpm().get());
+        };
+
+        let start = self.tempo.bpm().get().to_float();
+        let end = ramp.to.bpm().get().to_float();
+        let length = (ramp.duration.get() / self.time_signature.beat_duration()).to_float();
+        let minutes = minutes.to_float();
+
+        let ramp_minutes = ramp.shape.time(start, end, length, length);
+
+        let beats = if minutes <= ramp_minutes {
+            ramp.shape.distance(start, end, length, minutes)
+        } else {
+            length + (minutes - ramp_minutes) * end
+        };
+
+        beat * Ratio::approximate(beats)
+    }
+}
+
+impl ChangingTempo {
+    /// Returns the tempo at the given instant, taking ramps into account,
+    /// along with the rest of the ramp that the instant is on.
+    fn ramped(&self, instant: metre::Instant) -> (Tempo, Option<TempoRamp>) {
+        let tempo = self.get(instant);
+
+        let next_change = match metre::NonZeroInstant::from_instant(instant) {
+            Some(instant) => self
+                .values
+                .changes
+                .range((Excluded(instant), Unbounded))
+                .next(),
+            None => self.values.changes.iter().next(),
+        };
+
+        let Some((end, target)) = next_change else {
+            return (tempo, None);
+        };
+        let Some(shape) = self.ramps.get(end).copied() else {
+            return (tempo, None);
+        };
+
+        let start = self.last_change(instant).unwrap_or(metre::Instant::START);
+
+        let (Some(length), Some(duration)) = (
+            metre::NonZeroDuration::from_duration(end.get() - start),
+            metre::NonZeroDuration::from_duration(end.get() - instant),
+        ) else {
+            return (tempo, None);
+        };
+
+        let progress = ((instant - start) / length).to_float();
+        let bpm = shape.rate(
+            tempo.bpm().get().to_float(),
+            target.bpm().get().to_float(),
+            progress,
+        );
+        let tempo = NonZeroRatio::from_ratio(Ratio::approximate(bpm)).map_or(tempo, Tempo::new);
+
+        let ramp = TempoRamp {
+            to: *target,
+            duration,
+            shape,
+        };
+
+        (tempo, Some(ramp))
+    }
 }
 
 impl Div<Tempo> for TimeSignature {
@@ -26,70 +146,37 @@ impl Div<Tempo> for TimeSignature {
         TimeContext {
             tempo: rhs,
             time_signature: self,
+            ramp: None,
         }
     }
 }
 
-impl Div<&Changing<Tempo>> for &Changing<TimeSignature> {
+impl Div<&ChangingTempo> for &Changing<TimeSignature> {
     type Output = Changing<TimeContext>;
 
-    fn div(self, rhs: &Changing<Tempo>) -> Changing<TimeContext> {
-        let time_signature = self;
-        let tempo = rhs;
-
-        let mut tempo_iter = tempo.changes.iter();
-        let mut time_signature_iter = time_signature.changes.iter();
-
-        let mut current_tempo = tempo.start;
-        let mut current_time_signature = time_signature.start;
-
-        let mut next_tempo = tempo_iter.next();
-        let mut next_time_signature = time_signature_iter.next();
+    fn div(self, rhs: &ChangingTempo) -> Changing<TimeContext> {
+        let context = |instant| {
+            let (tempo, ramp) = rhs.ramped(instant);
 
-        let mut output = Changing {
-            start: current_time_signature / current_tempo,
-            changes: BTreeMap::new(),
+            TimeContext {
+                tempo,
+                time_signature: self.get(instant),
+                ramp,
+            }
         };
 
-        loop {
-            match (next_tempo, next_time_signature) {
-                (Some((tempo_change, tempo)), Some((time_signature_change, time_signature))) => {
-                    let change = min(tempo_change, time_signature_change);
-
-                    if change == tempo_change {
-                        next_tempo = tempo_iter.next();
-                        current_tempo = *tempo;
-                    }
-                    if change == time_signature_change {
-                        next_time_signature = time_signature_iter.next();
-                        current_time_signature = *time_signature;
-                    }
-
-                    output
-                        .changes
-                        .insert(*change, current_time_signature / current_tempo);
-                }
-                (Some((change, tempo)), None) => {
-                    next_tempo = tempo_iter.next();
-                    current_tempo = *tempo;
-
-                    output
-                        .changes
-                        .insert(*change, current_time_signature / current_tempo);
-                }
-                (None, Some((change, time_signature))) => {
-                    next_time_signature = time_signature_iter.next();
-                    current_time_signature = *time_signature;
-
-                    output
-                        .changes
-                        .insert(*change, current_time_signature / current_tempo);
-                }
-                (None, None) => break,
-            }
+        // Any change of either the time signature or the tempo is a change of the context.
+        let changes = self
+            .changes
+            .keys()
+            .chain(rhs.values.changes.keys())
+            .map(|change| (*change, context(change.get())))
+            .collect();
+
+        Changing {
+            start: context(metre::Instant::START),
+            changes,
         }
-
-        output
     }
 }
 
@@ -97,7 +184,7 @@ impl Mul<TimeContext> for metre::Duration {
     type Output = time::Duration;
 
     fn mul(self, rhs: TimeContext) -> time::Duration {
-        rhs.tempo.beat_duration().get() * (self / rhs.time_signature.beat_duration())
+        rhs.real_duration(self)
     }
 }
 
@@ -115,17 +202,13 @@ impl Mul<&Changing<TimeContext>> for metre::Instant {
                 break;
             }
 
-            let duration = next_change.get() - change;
-            instant += context.tempo.beat_duration().get()
-                * (duration / context.time_signature.beat_duration());
+            instant += context.real_duration(next_change.get() - change);
 
             change = next_change.get();
             context = *next_context;
         }
 
-        let remaining = self - change;
-        instant += context.tempo.beat_duration().get()
-            * (remaining / context.time_signature.beat_duration());
+        instant += context.real_duration(self - change);
 
         instant
     }
@@ -143,8 +226,7 @@ impl Div<&Changing<TimeContext>> for time::Instant {
 
         for (next_change, next_context) in &rhs.changes {
             let duration = next_change.get() - change;
-            let real_duration = context.tempo.beat_duration().get()
-                * (duration / context.time_signature.beat_duration());
+            let real_duration = context.real_duration(duration);
 
             if remaining < real_duration {
                 break;
@@ -157,9 +239,145 @@ impl Div<&Changing<TimeContext>> for time::Instant {
             context = *next_context;
         }
 
-        instant += context.time_signature.beat_duration().get()
-            * (remaining / context.tempo.beat_duration());
+        instant += context.musical_duration(remaining);
 
         instant
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use anyhow::ensure;
+    use non_zero::non_zero;
+    use std::num::NonZeroU64;
+
+    /// The musical duration, in whole notes, of a nanosecond at the fastest tempo of the tests.
+    ///
+    /// At 180 bpm in common time, a second is three quarters of a whole note.
+    const NANOSECOND: f64 = 0.75e-9;
+
+    /// Returns the instant a number of thirds of a whole note after the start.
+    fn thirds(thirds: u64) -> metre::Instant {
+        metre::Instant {
+            since_start: metre::Duration {
+                whole_notes: Ratio::new(thirds, non_zero!(3)),
+            },
+        }
+    }
+
+    /// Returns the instant a number of sixteenth notes after the start.
+    fn sixteenths(sixteenths: u64) -> metre::Instant {
+        metre::Instant {
+            since_start: metre::Duration {
+                whole_notes: Ratio::new(sixteenths, non_zero!(16)),
+            },
+        }
+    }
+
+    /// Returns the tempo with the given number of beats per minute.
+    fn bpm(bpm: NonZeroU64) -> Tempo {
+        Tempo::new(NonZeroRatio::integer(bpm))
+    }
+
+    /// Returns a time context in common time that slows down linearly from 120 to 60 bpm,
+    /// jumps to 90 bpm, and then speeds up exponentially to 180 bpm.
+    fn ramped_context() -> Changing<TimeContext> {
+        let mut tempo = ChangingTempo::from(bpm(non_zero!(120)));
+
+        tempo.set(thirds(12), bpm(non_zero!(60)));
+        tempo.set_ramp(thirds(12), Some(Ramp::Linear));
+        tempo.set(thirds(18), bpm(non_zero!(90)));
+        tempo.set(thirds(30), bpm(non_zero!(180)));
+        tempo.set_ramp(thirds(30), Some(Ramp::Exponential));
+
+        &Changing::from(TimeSignature::default()) / &tempo
+    }
+
+    #[test]
+    fn round_trip_without_ramps() -> anyhow::Result<()> {
+        let mut tempo = ChangingTempo::from(bpm(non_zero!(120)));
+        tempo.set(sixteenths(64), bpm(non_zero!(60)));
+
+        let context = &Changing::from(TimeSignature::default()) / &tempo;
+
+        // A sixteenth note takes a whole number of nanoseconds at both tempi, so nothing is rounded.
+        for instant in (0..=96).map(sixteenths) {
+            let time = instant * &context;
+            let round_trip = time / &context;
+
+            ensure!(
+                round_trip == instant,
+                "{instant:?} became {round_trip:?} after a round trip through {time:?}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_across_ramps() -> anyhow::Result<()> {
+        let context = ramped_context();
+
+        for instant in (0..=45).map(thirds) {
+            let time = instant * &context;
+            let round_trip = time / &context;
+
+            let difference = round_trip.since_start.whole_notes.to_float()
+                - instant.since_start.whole_notes.to_float();
+
+            ensure!(
+                round_trip * &context == time,
+                "{instant:?} became {round_trip:?}, which is not at {time:?}"
+            );
+            ensure!(
+                difference.abs() <= NANOSECOND,
+                "{instant:?} became {round_trip:?} after a round trip through {time:?}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn ramps_do_not_jump_at_their_boundaries() -> anyhow::Result<()> {
+        let context = ramped_context();
+
+        // A third of a whole note takes 1/135 of a minute at 180 bpm and 1/45 of one at 60 bpm.
+        let shortest = time::Duration::MINUTE * Ratio::new(1, non_zero!(140));
+        let longest = time::Duration::MINUTE * Ratio::new(1, non_zero!(44));
+
+        let mut previous = time::Instant::START;
+
+        for instant in (1..=45).map(thirds) {
+            let time = instant * &context;
+            let elapsed = time - previous;
+
+            ensure!(
+                shortest < elapsed && elapsed < longest,
+                "the third of a whole note up to {instant:?} took {elapsed:?}"
+            );
+
+            previous = time;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn linear_ramp_duration() -> anyhow::Result<()> {
+        let context = ramped_context();
+
+        // 16 beats from 120 to 60 bpm take 16 / (60 - 120) * ln(60 / 120) minutes.
+        let expected = 16.0 / -60.0 * 0.5_f64.ln() * 60.0;
+        let seconds = (thirds(12) * &context).since_start / time::NonZeroDuration::SECOND;
+
+        ensure!(
+            (seconds.to_float() - expected).abs() <= 1e-9,
+            "the ramp took {seconds:?} seconds instead of {expected}"
+        );
+
+        Ok(())
+    }
+}
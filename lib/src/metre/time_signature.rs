@@ -1,6 +1,7 @@
 //! Items pertaining to [`TimeSignature`].
 
 use crate::NonZeroRatio;
+use crate::Ratio;
 use crate::metre::Changing;
 use crate::metre::Instant;
 use crate::metre::Measure;
@@ -8,6 +9,8 @@ use crate::metre::NonZeroDuration;
 use non_zero::non_zero;
 use serde::Deserialize;
 use serde::Serialize;
+use std::cmp::max;
+use std::cmp::min;
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
@@ -92,6 +95,45 @@ impl Changing<TimeSignature> {
         }
     }
 
+    /// Returns the measure containing the given instant.
+    pub(crate) fn measure_at(&self, instant: Instant) -> Measure {
+        self.measures()
+            .find(|measure| instant < measure.period().get().end())
+            .unwrap_or_default()
+    }
+
+    /// Returns the line of a quantisation grid that is nearest to an instant.
+    ///
+    /// The grid resets every measure.
+    /// Every other line in a measure is delayed by `swing` cells.
+    pub(crate) fn nearest_grid_line(
+        &self,
+        instant: Instant,
+        cell_duration: NonZeroDuration,
+        swing: Ratio,
+    ) -> Instant {
+        let measure = self.measure_at(instant);
+        let measure_end = measure.period().get().end();
+
+        let line = |index: u64| {
+            let mut line = measure.start + cell_duration.get() * Ratio::integer(index);
+
+            if index % 2 == 1 {
+                line += cell_duration.get() * swing;
+            }
+
+            min(line, measure_end)
+        };
+
+        let index = ((instant - measure.start) / cell_duration).floor();
+
+        [index.saturating_sub(1), index, index.saturating_add(1)]
+            .into_iter()
+            .map(line)
+            .min_by_key(|line| max(*line, instant) - min(*line, instant))
+            .unwrap_or(instant)
+    }
+
     /// Returns an iterator over all measures.
     pub(crate) fn measures(&self) -> impl Iterator<Item = Measure> + Clone + use<'_> {
         let mut start = Instant::START;
@@ -3,15 +3,85 @@
 use crate::Audio;
 use crate::Id;
 use crate::Note;
+use crate::audio::Gain;
 use crate::audio::Sample;
 use crate::audio::Subsection;
 use crate::audio::sample;
 use crate::audio::sample::Duration;
 use crate::node::ProcessResult;
+use crate::note::Articulation;
 use crate::note::Event;
 use crate::note::Pitch;
 use crate::note::event::Subsequence;
+use crate::note::expression::Dimension;
+use crate::project::track::Automation;
+use crate::project::track::automation;
+use crate::project::track::automation::Parameter;
+use crate::project::track::automation::Target;
+use crate::time;
 use std::collections::HashMap;
+use std::f32::consts::TAU;
+
+/// How much louder an accented note is played.
+const ACCENT_GAIN: f32 = 1.5;
+/// How long a staccato note is held at most.
+const STACCATO_DURATION: time::Duration = time::Duration {
+    nanoseconds: 100_000_000,
+};
+/// How long a legato note is held past its end.
+const LEGATO_OVERLAP: time::Duration = time::Duration {
+    nanoseconds: 50_000_000,
+};
+
+/// A pressed key.
+#[derive(Copy, Clone, Debug)]
+struct Key {
+    /// The pitch of the key.
+    pitch: Pitch,
+    /// The amplitude with which the key is played.
+    amplitude: f32,
+    /// The articulation of the note that pressed the key.
+    articulation: Option<Articulation>,
+    /// When the key is to be released, if that is known.
+    release: Option<sample::Instant>,
+    /// How far the pitch is bent, in cents.
+    bend: i16,
+    /// The pressure, from 0 to 127.
+    pressure: i16,
+    /// The timbre, from 0 to 127.
+    timbre: i16,
+    /// The phase of the oscillator.
+    phase: f32,
+}
+
+impl Key {
+    /// Returns a mutable reference to the value of a dimension of expression.
+    fn expression_mut(&mut self, dimension: Dimension) -> &mut i16 {
+        match dimension {
+            Dimension::PitchBend => &mut self.bend,
+            Dimension::Pressure => &mut self.pressure,
+            Dimension::Timbre => &mut self.timbre,
+        }
+    }
+
+    /// Returns the next sample of the key and advances the oscillator.
+    ///
+    /// The sample is amplified by `level`, and `brightness` scales the effect of the timbre.
+    fn next_sample(&mut self, sample_rate: sample::Rate, level: f32, brightness: f32) -> Sample {
+        let bend = 2_f32.powf(f32::from(self.bend) / 1200.0);
+        let frequency = self.pitch.frequency() * bend / sample_rate.hz();
+
+        let pressure = f32::from(self.pressure) / 127.0;
+        let brightness = brightness * f32::from(self.timbre) / 127.0;
+
+        // The timbre mixes in the second harmonic.
+        let wave = f32::sin(self.phase) + brightness * f32::sin(2.0 * self.phase) / 2.0;
+
+        self.phase = (self.phase + frequency).rem_euclid(TAU);
+
+        Sample::new(level * self.amplitude * pressure * wave)
+    }
+}
 
 /// An instance of a node chain.
 pub(crate) struct Instance {
@@ -21,7 +91,7 @@ pub(crate) struct Instance {
     position: sample::Instant,
 
     /// The currently pressed keys.
-    keys: HashMap<Id<Note>, Pitch>,
+    keys: HashMap<Id<Note>, Key>,
 }
 
 impl Instance {
@@ -37,12 +107,24 @@ impl Instance {
         }
     }
 
+    /// Sets the value of a dimension of expression for a pressed key.
+    ///
+    /// Does nothing if the key is not pressed.
+    fn set_expression(&mut self, id: Id<Note>, dimension: Dimension, value: i16) {
+        if let Some(key) = self.keys.get_mut(&id) {
+            *key.expression_mut(dimension) = dimension.clamp_value(value);
+        }
+    }
+
     /// Process a slice of a clip.
+    ///
+    /// The [parameters](Parameter) of the chain follow the `automation` of the track sample by sample.
     pub(crate) fn process(
         &mut self,
         duration: Duration,
         input_audio: Subsection,
         events: Subsequence,
+        automation: &[(Target, automation::Sampled)],
     ) -> ProcessResult {
         // TODO: pass to a plugin instance (& remember to fix docs)
 
@@ -53,29 +135,79 @@ impl Instance {
         for index in 0..buffer_size {
             let instant = sample::Instant::from_index(index);
 
+            let now = self.position + instant.since_start;
+
             for event in events.get(instant) {
-                match event {
-                    Event::NoteOn { id, pitch } => {
-                        self.keys.insert(*id, *pitch);
+                match *event {
+                    Event::NoteOn {
+                        id,
+                        pitch,
+                        velocity,
+                        articulation,
+                    } => {
+                        let mut amplitude = velocity.amplitude();
+                        let mut release = None;
+
+                        match articulation {
+                            Some(Articulation::Accent) => amplitude *= ACCENT_GAIN,
+                            Some(Articulation::Staccato) => {
+                                release = Some(now + STACCATO_DURATION * self.sample_rate);
+                            }
+                            Some(Articulation::Legato) | None => (),
+                        }
+
+                        self.keys.insert(
+                            id,
+                            Key {
+                                pitch,
+                                amplitude,
+                                articulation,
+                                release,
+                                bend: Dimension::PitchBend.neutral_value(),
+                                pressure: Dimension::Pressure.neutral_value(),
+                                timbre: Dimension::Timbre.neutral_value(),
+                                phase: 0.0,
+                            },
+                        );
                     }
                     Event::NoteOff(id) => {
-                        self.keys.remove(id);
+                        if let Some(key) = self.keys.get_mut(&id)
+                            && key.articulation == Some(Articulation::Legato)
+                        {
+                            key.release = Some(now + LEGATO_OVERLAP * self.sample_rate);
+                        } else {
+                            self.keys.remove(&id);
+                        }
+                    }
+                    Event::PitchBend { id, cents } => {
+                        self.set_expression(id, Dimension::PitchBend, cents);
+                    }
+                    Event::Pressure { id, value } => {
+                        self.set_expression(id, Dimension::Pressure, value);
+                    }
+                    Event::Timbre { id, value } => {
+                        self.set_expression(id, Dimension::Timbre, value);
                     }
                 }
             }
 
+            self.keys
+                .retain(|_, key| key.release.is_none_or(|release| now < release));
+
             let [left_input, right_input] = input_audio.sample_pair(instant);
             let [left_output, right_output] = output_audio.sample_pair_mut(instant);
 
+            let level = Gain {
+                decibels: parameter_value(automation, Parameter::Level, now),
+            }
+            .amplitude();
+            let brightness = parameter_value(automation, Parameter::Brightness, now) / 100.0;
+
             let mut delta = Sample::ZERO;
 
             #[expect(clippy::iter_over_hash_type, reason = "order is irrelevant")]
-            for pitch in self.keys.values() {
-                let frequency = pitch.frequency() / self.sample_rate.hz();
-                #[expect(clippy::cast_precision_loss, reason = "approximating is fine")]
-                let time = (self.position.since_start + instant.since_start).samples as f32;
-
-                delta += Sample::new(f32::sin(frequency * time));
+            for key in self.keys.values_mut() {
+                delta += key.next_sample(self.sample_rate, level, brightness);
             }
 
             *left_output = left_input + delta;
@@ -91,3 +223,13 @@ impl Instance {
         }
     }
 }
+
+/// Returns the value of a parameter at an instant, following the automation if there is any.
+fn parameter_value(
+    automation: &[(Target, automation::Sampled)],
+    parameter: Parameter,
+    instant: sample::Instant,
+) -> f32 {
+    Automation::value_at(automation, Target::Node(parameter), instant)
+        .unwrap_or_else(|| parameter.default_value())
+}
@@ -3,10 +3,12 @@
 // TODO: clean up this total mess of premature abstractions.
 
 pub(crate) mod chain;
+mod parameter;
 mod process_result;
 
 #[doc(inline)]
 pub(crate) use chain::Chain;
+pub use parameter::Parameter;
 pub(crate) use process_result::ProcessResult;
 
 /// A node in a [chain](Chain) of audio processing.
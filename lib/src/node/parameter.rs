@@ -0,0 +1,35 @@
+//! Items pertaining to [`Parameter`].
+
+use enum_iterator::Sequence;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// A parameter of the nodes in a [chain](super::Chain) that can be automated.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Sequence)]
+#[remain::sorted]
+pub enum Parameter {
+    /// How strongly the timbre of synthesised notes brings out their second harmonic, in percent.
+    Brightness,
+    /// The gain of synthesised notes, in decibels.
+    Level,
+}
+
+impl Parameter {
+    /// Returns the value of the parameter when it is not automated.
+    pub(crate) fn default_value(self) -> f32 {
+        match self {
+            Parameter::Brightness => 100.0,
+            Parameter::Level => 0.0,
+        }
+    }
+}
+
+impl Display for Parameter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Parameter::Brightness => write!(f, "note brightness"),
+            Parameter::Level => write!(f, "note level"),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! Items pertaining to [`Articulation`].
+
+use enum_iterator::Sequence;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// How a [note](super::Note) is played, other than its pitch, duration and velocity.
+#[derive(
+    Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Sequence, Serialize, Deserialize,
+)]
+#[remain::sorted]
+#[serde(rename_all = "snake_case")]
+pub enum Articulation {
+    /// The note is played louder than its velocity suggests.
+    Accent,
+    /// The note is held slightly past its end, so that it is connected to the following note.
+    Legato,
+    /// The note is cut short.
+    Staccato,
+}
+
+impl Display for Articulation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Articulation::Accent => write!(f, "accent"),
+            Articulation::Legato => write!(f, "legato"),
+            Articulation::Staccato => write!(f, "staccato"),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Items pertaining to [`Lane`].
+
+use crate::note::Pitch;
+use crate::note::Velocity;
+use arcstr::ArcStr;
+use getset::CopyGetters;
+use getset::Getters;
+use std::collections::BTreeMap;
+
+/// A named row of a [drum clip](super::Drums) that plays a single pitch.
+#[derive(Clone, Eq, PartialEq, Debug, Getters, CopyGetters)]
+pub struct Lane {
+    /// The name, e.g. "kick".
+    #[get = "pub"]
+    name: ArcStr,
+    /// The pitch that is played.
+    #[get_copy = "pub"]
+    pitch: Pitch,
+    /// A map from step indices to the velocities of the hits on those steps.
+    #[get = "pub"]
+    hits: BTreeMap<u64, Velocity>,
+}
+
+impl Lane {
+    /// Constructs a new lane without any hits.
+    pub(super) fn new(name: ArcStr, pitch: Pitch) -> Lane {
+        Lane {
+            name,
+            pitch,
+            hits: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the velocity of the hit on a step, if there is one.
+    #[must_use]
+    pub fn hit(&self, step: u64) -> Option<Velocity> {
+        self.hits.get(&step).copied()
+    }
+
+    /// Sets or removes the hit on a step.
+    ///
+    /// Returns the velocity of the hit that was overwritten, if any.
+    pub(crate) fn set_hit(&mut self, step: u64, velocity: Option<Velocity>) -> Option<Velocity> {
+        match velocity {
+            Some(velocity) => self.hits.insert(step, velocity),
+            None => self.hits.remove(&step),
+        }
+    }
+
+    /// Removes the hits on the steps from `step_count` onwards.
+    pub(super) fn truncate(&mut self, step_count: u64) {
+        self.hits.retain(|step, _| *step < step_count);
+    }
+}
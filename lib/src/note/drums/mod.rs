@@ -0,0 +1,161 @@
+//! Items pertaining to [`Drums`].
+
+mod lane;
+mod serial;
+
+pub use lane::Lane;
+
+pub(crate) use serial::Serial;
+
+use crate::Id;
+use crate::audio::sample;
+use crate::metre::Changing;
+use crate::metre::Instant;
+use crate::metre::NonZeroDuration;
+use crate::metre::TimeContext;
+use crate::note::Event;
+use crate::note::Interval;
+use crate::note::Pitch;
+use crate::note::event::Sequence;
+use crate::view::Painter;
+use arcstr::literal;
+use std::cmp::min;
+use std::num::NonZeroU64;
+
+/// A drum pattern, i.e. a grid of hits on named lanes.
+///
+/// Each hit lasts exactly one step.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Drums {
+    /// The duration of the whole pattern.
+    duration: NonZeroDuration,
+    /// The duration of a single step.
+    step_duration: NonZeroDuration,
+    /// The lanes, from top to bottom.
+    lanes: Vec<Lane>,
+}
+
+impl Drums {
+    /// Constructs an empty drum pattern with the default lanes.
+    ///
+    /// The lanes use the pitches of the General MIDI percussion key map.
+    #[must_use]
+    pub fn new(duration: NonZeroDuration, step_duration: NonZeroDuration) -> Drums {
+        let lane = |name, midi_number| {
+            Lane::new(name, Pitch::LOWEST + Interval::from_semitones(midi_number))
+        };
+
+        Drums {
+            duration,
+            step_duration,
+            lanes: vec![
+                lane(literal!("kick"), 36),
+                lane(literal!("snare"), 38),
+                lane(literal!("closed hat"), 42),
+                lane(literal!("open hat"), 46),
+            ],
+        }
+    }
+
+    /// Returns the duration of the drum pattern.
+    #[must_use]
+    pub fn duration(&self) -> NonZeroDuration {
+        self.duration
+    }
+
+    /// Returns the duration of a single step.
+    #[must_use]
+    pub fn step_duration(&self) -> NonZeroDuration {
+        self.step_duration
+    }
+
+    /// Returns the number of steps.
+    ///
+    /// If the duration is not a multiple of the step duration, the last step is cut short.
+    #[must_use]
+    pub fn step_count(&self) -> NonZeroU64 {
+        (self.duration / self.step_duration).ceiling()
+    }
+
+    /// Returns the lanes, from top to bottom.
+    #[must_use]
+    pub fn lanes(&self) -> &[Lane] {
+        &self.lanes
+    }
+
+    /// Returns a mutable reference to a lane.
+    pub(crate) fn lane_mut(&mut self, index: usize) -> Option<&mut Lane> {
+        self.lanes.get_mut(index)
+    }
+
+    /// Returns a copy of the drum pattern cropped to a duration.
+    ///
+    /// Hits on steps that start after the new end are dropped.
+    pub(crate) fn cropped(&self, duration: NonZeroDuration) -> Drums {
+        let mut drums = self.clone();
+        drums.duration = duration;
+
+        let step_count = drums.step_count().get();
+
+        for lane in &mut drums.lanes {
+            lane.truncate(step_count);
+        }
+
+        drums
+    }
+
+    /// Returns a [painter](Painter) that can paint a clip overview.
+    pub(crate) fn overview_painter(&self) -> Box<Painter> {
+        // TODO: draw the hits
+        let _: &Drums = self;
+        Box::new(|_| ())
+    }
+
+    /// Turns the drum pattern into a sequence of [note events](crate::note::Event).
+    ///
+    /// Hits are cropped to `duration`.
+    pub(crate) fn to_events(
+        &self,
+        start: Instant,
+        duration: NonZeroDuration,
+        time_context: &Changing<TimeContext>,
+        sample_rate: sample::Rate,
+    ) -> Sequence {
+        let end = min(self.duration, duration).get();
+
+        self.lanes
+            .iter()
+            .flat_map(|lane| {
+                lane.hits().iter().filter_map(move |(step, velocity)| {
+                    let hit_start = self.step_duration.get() * *step;
+
+                    if end <= hit_start {
+                        return None;
+                    }
+
+                    let hit_end = min(hit_start + self.step_duration.get(), end);
+
+                    // Hits are not notes, so they get a new id every time they are played.
+                    let id = Id::generate();
+
+                    let hit_start = (start + hit_start) * time_context * sample_rate;
+                    let hit_end = (start + hit_end) * time_context * sample_rate;
+
+                    Some([
+                        (
+                            hit_start,
+                            Event::NoteOn {
+                                id,
+                                pitch: lane.pitch(),
+                                velocity: *velocity,
+                                articulation: None,
+                            },
+                        ),
+                        (hit_end, Event::NoteOff(id)),
+                    ])
+                })
+            })
+            .flatten()
+            .collect()
+    }
+}
@@ -0,0 +1,98 @@
+//! Items pertaining to [`Serial`].
+
+use crate::metre::NonZeroDuration;
+use crate::note::Drums;
+use crate::note::Pitch;
+use crate::note::Velocity;
+use crate::note::drums::Lane;
+use arcstr::ArcStr;
+use serde::Deserialize;
+use serde::Serialize;
+use std::borrow::Cow;
+
+/// The serial representation of a [drum pattern](Drums).
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Serial<'data> {
+    /// The duration.
+    pub duration: NonZeroDuration,
+    /// The duration of a step.
+    pub step_duration: NonZeroDuration,
+    /// The lanes.
+    pub lanes: Vec<SerialLane<'data>>,
+}
+
+/// The serial representation of a [lane](Lane).
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SerialLane<'data> {
+    /// The name.
+    pub name: Cow<'data, str>,
+    /// The pitch.
+    pub pitch: Pitch,
+    /// The hits.
+    pub hits: Vec<Hit>,
+}
+
+/// The serial representation of a hit in a [lane](Lane).
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Hit {
+    /// The index of the step.
+    pub step: u64,
+    /// The velocity.
+    pub velocity: Velocity,
+}
+
+impl<'data> From<&'data Drums> for Serial<'data> {
+    fn from(drums: &'data Drums) -> Self {
+        Serial {
+            duration: drums.duration,
+            step_duration: drums.step_duration,
+            lanes: drums
+                .lanes
+                .iter()
+                .map(|lane| SerialLane {
+                    name: Cow::Borrowed(lane.name().as_str()),
+                    pitch: lane.pitch(),
+                    hits: lane
+                        .hits()
+                        .iter()
+                        .map(|(step, velocity)| Hit {
+                            step: *step,
+                            velocity: *velocity,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<Serial<'_>> for Drums {
+    fn from(serial: Serial<'_>) -> Self {
+        let Serial {
+            duration,
+            step_duration,
+            lanes,
+        } = serial;
+
+        let lanes = lanes
+            .into_iter()
+            .map(|SerialLane { name, pitch, hits }| {
+                let mut lane = Lane::new(ArcStr::from(name), pitch);
+
+                for Hit { step, velocity } in hits {
+                    lane.set_hit(step, Some(velocity));
+                }
+
+                lane
+            })
+            .collect();
+
+        // Hits outside the pattern are dropped.
+        Drums {
+            duration,
+            step_duration,
+            lanes,
+        }
+        .cropped(duration)
+    }
+}
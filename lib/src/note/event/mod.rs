@@ -8,7 +8,9 @@ pub(crate) use subsequence::Subsequence;
 
 use crate::Id;
 use crate::Note;
+use crate::note::Articulation;
 use crate::note::Pitch;
+use crate::note::Velocity;
 
 /// A note event (similar to MIDI).
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
@@ -19,7 +21,32 @@ pub(crate) enum Event {
         id: Id<Note>,
         /// The pitch of the note.
         pitch: Pitch,
+        /// How hard the note is played.
+        velocity: Velocity,
+        /// The articulation of the note, or `None` if it is played normally.
+        articulation: Option<Articulation>,
     },
     /// Turns a note off.
     NoteOff(Id<Note>),
+    /// Bends the pitch of a note.
+    PitchBend {
+        /// The id of the note.
+        id: Id<Note>,
+        /// How far the pitch is bent, in cents.
+        cents: i16,
+    },
+    /// Changes the pressure with which a note is held.
+    Pressure {
+        /// The id of the note.
+        id: Id<Note>,
+        /// The pressure, from 0 to 127.
+        value: i16,
+    },
+    /// Changes the timbre of a note.
+    Timbre {
+        /// The id of the note.
+        id: Id<Note>,
+        /// The timbre, from 0 to 127.
+        value: i16,
+    },
 }
@@ -0,0 +1,140 @@
+//! Items pertaining to [`Curve`].
+
+use crate::audio::sample;
+use crate::metre::Changing;
+use crate::metre::Duration;
+use crate::metre::Instant;
+use crate::metre::TimeContext;
+use crate::metre::relative;
+use itertools::Itertools as _;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// The number of samples between the values emitted for an interpolated segment of a curve.
+const INTERPOLATION_INTERVAL: usize = 64;
+
+/// A curve of values over the duration of a note.
+///
+/// The curve is linearly interpolated between its points.
+/// After the last point, the curve keeps its value.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Default)]
+pub struct Curve {
+    /// A map from positions, relative to the start of the note, to values.
+    points: BTreeMap<relative::Instant, i16>,
+}
+
+/// The serial representation of a point on a [curve](Curve).
+#[derive(Serialize, Deserialize)]
+struct Point {
+    /// The position relative to the start of the note.
+    at: relative::Instant,
+    /// The value.
+    value: i16,
+}
+
+impl Curve {
+    /// Returns whether the curve has no points.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns an iterator over the points of the curve.
+    pub fn points(&self) -> impl Iterator<Item = (relative::Instant, i16)> {
+        self.points.iter().map(|(at, value)| (*at, *value))
+    }
+
+    /// Sets or removes the point at a position.
+    ///
+    /// Returns the value of the point that was overwritten, if any.
+    pub(crate) fn set_point(&mut self, at: relative::Instant, value: Option<i16>) -> Option<i16> {
+        match value {
+            Some(value) => self.points.insert(at, value),
+            None => self.points.remove(&at),
+        }
+    }
+
+    /// Returns the values of the curve at sample positions for a note
+    /// starting at `start` and lasting for `duration`.
+    ///
+    /// Points after the end of the note are ignored.
+    pub(crate) fn sample(
+        &self,
+        start: Instant,
+        duration: Duration,
+        time_context: &Changing<TimeContext>,
+        sample_rate: sample::Rate,
+    ) -> Vec<(sample::Instant, i16)> {
+        let points: Vec<(sample::Instant, i16)> = self
+            .points
+            .iter()
+            .filter(|(at, _)| at.since_start < duration)
+            .map(|(at, value)| ((start + *at) * time_context * sample_rate, *value))
+            .collect();
+
+        let mut values: Vec<(sample::Instant, i16)> = points
+            .iter()
+            .tuple_windows()
+            .flat_map(|(&(from, from_value), &(to, to_value))| {
+                let length = (to - from).samples;
+
+                (0..length)
+                    .step_by(INTERPOLATION_INTERVAL)
+                    .map(move |offset| {
+                        let offset = sample::Duration { samples: offset };
+
+                        (
+                            from + offset,
+                            interpolate(from_value, to_value, offset.samples, length),
+                        )
+                    })
+            })
+            .collect();
+
+        values.extend(points.last().copied());
+
+        values
+    }
+}
+
+impl Serialize for Curve {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.points.iter().map(|(at, value)| Point {
+            at: *at,
+            value: *value,
+        }))
+    }
+}
+
+impl<'de> Deserialize<'de> for Curve {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let points = Vec::<Point>::deserialize(deserializer)?;
+
+        Ok(Curve {
+            points: points
+                .into_iter()
+                .map(|Point { at, value }| (at, value))
+                .collect(),
+        })
+    }
+}
+
+/// Linearly interpolates between two values.
+#[expect(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    reason = "the values are small enough and rounding is fine"
+)]
+fn interpolate(from: i16, to: i16, offset: usize, length: usize) -> i16 {
+    let fraction = offset as f32 / length as f32;
+    let value = f32::from(from) + (f32::from(to) - f32::from(from)) * fraction;
+
+    value.round() as i16
+}
@@ -0,0 +1,90 @@
+//! Items pertaining to [`Dimension`].
+
+use crate::Ratio;
+use enum_iterator::Sequence;
+use saturating_cast::SaturatingCast as _;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::num::NonZeroU64;
+use std::ops::RangeInclusive;
+
+/// A dimension of per-note [expression](super::Expression).
+#[derive(
+    Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Sequence, Serialize, Deserialize,
+)]
+#[remain::sorted]
+#[serde(rename_all = "snake_case")]
+pub enum Dimension {
+    /// How far the pitch of the note is bent, in cents.
+    PitchBend,
+    /// How hard the key is pressed after the note starts.
+    Pressure,
+    /// The brightness of the sound.
+    Timbre,
+}
+
+impl Dimension {
+    /// Returns the range of values in the dimension.
+    #[must_use]
+    pub fn range(self) -> RangeInclusive<i16> {
+        match self {
+            // Four octaves in either direction, the default range of MPE.
+            Dimension::PitchBend => -4800..=4800,
+            Dimension::Pressure | Dimension::Timbre => 0..=127,
+        }
+    }
+
+    /// Returns the value that a note has in the dimension before the first point of its curve.
+    #[must_use]
+    pub fn neutral_value(self) -> i16 {
+        match self {
+            Dimension::PitchBend | Dimension::Timbre => 0,
+            Dimension::Pressure => 127,
+        }
+    }
+
+    /// Clamps a value to the range of the dimension.
+    #[must_use]
+    pub fn clamp_value(self, value: i16) -> i16 {
+        let range = self.range();
+        value.clamp(*range.start(), *range.end())
+    }
+
+    /// Returns where a value lies in the range of the dimension, from zero to one.
+    pub(crate) fn fraction(self, value: i16) -> Ratio {
+        let range = self.range();
+        let span = range.start().abs_diff(*range.end());
+
+        let Some(span) = NonZeroU64::new(u64::from(span)) else {
+            return Ratio::ZERO;
+        };
+
+        Ratio::new(
+            u64::from(self.clamp_value(value).abs_diff(*range.start())),
+            span,
+        )
+    }
+
+    /// Returns the value that lies at a fraction, from zero to one, of the range of the dimension.
+    pub(crate) fn value_at(self, fraction: Ratio) -> i16 {
+        let range = self.range();
+        let span = range.start().abs_diff(*range.end());
+
+        let offset: u16 = (fraction * Ratio::from(span)).round().saturating_cast();
+
+        self.clamp_value(range.start().saturating_add_unsigned(offset))
+    }
+}
+
+impl Display for Dimension {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Dimension::PitchBend => write!(f, "pitch bend"),
+            Dimension::Pressure => write!(f, "pressure"),
+            Dimension::Timbre => write!(f, "timbre"),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Items pertaining to [`Expression`].
+
+mod curve;
+mod dimension;
+
+pub use curve::Curve;
+pub use dimension::Dimension;
+
+use crate::Id;
+use crate::Note;
+use crate::audio::sample;
+use crate::metre::Changing;
+use crate::metre::Duration;
+use crate::metre::Instant;
+use crate::metre::TimeContext;
+use crate::note::Event;
+use enum_iterator::all;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Per-note expression, in the style of MIDI polyphonic expression (MPE).
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Default, Serialize, Deserialize)]
+pub struct Expression {
+    /// The pitch bend, in cents.
+    #[serde(default, skip_serializing_if = "Curve::is_empty")]
+    pitch_bend: Curve,
+    /// The pressure.
+    #[serde(default, skip_serializing_if = "Curve::is_empty")]
+    pressure: Curve,
+    /// The timbre.
+    #[serde(default, skip_serializing_if = "Curve::is_empty")]
+    timbre: Curve,
+}
+
+impl Expression {
+    /// Returns whether none of the curves have any points.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        all::<Dimension>().all(|dimension| self.curve(dimension).is_empty())
+    }
+
+    /// Returns the curve of a dimension.
+    #[must_use]
+    pub fn curve(&self, dimension: Dimension) -> &Curve {
+        match dimension {
+            Dimension::PitchBend => &self.pitch_bend,
+            Dimension::Pressure => &self.pressure,
+            Dimension::Timbre => &self.timbre,
+        }
+    }
+
+    /// Returns a mutable reference to the curve of a dimension.
+    pub(crate) fn curve_mut(&mut self, dimension: Dimension) -> &mut Curve {
+        match dimension {
+            Dimension::PitchBend => &mut self.pitch_bend,
+            Dimension::Pressure => &mut self.pressure,
+            Dimension::Timbre => &mut self.timbre,
+        }
+    }
+
+    /// Returns the [events](Event) of a note with this expression.
+    pub(crate) fn to_events(
+        &self,
+        id: Id<Note>,
+        start: Instant,
+        duration: Duration,
+        time_context: &Changing<TimeContext>,
+        sample_rate: sample::Rate,
+    ) -> impl Iterator<Item = (sample::Instant, Event)> {
+        all::<Dimension>().flat_map(move |dimension| {
+            self.curve(dimension)
+                .sample(start, duration, time_context, sample_rate)
+                .into_iter()
+                .map(move |(instant, value)| {
+                    let event = match dimension {
+                        Dimension::PitchBend => Event::PitchBend { id, cents: value },
+                        Dimension::Pressure => Event::Pressure { id, value },
+                        Dimension::Timbre => Event::Timbre { id, value },
+                    };
+
+                    (instant, event)
+                })
+        })
+    }
+}
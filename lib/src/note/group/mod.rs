@@ -16,9 +16,13 @@ use crate::note::Event;
 use crate::note::Pitch;
 use crate::note::event::Sequence;
 use crate::view::Painter;
+use itertools::Itertools as _;
+use itertools::chain;
 use serde::Deserialize;
+use std::cmp::max;
 use std::cmp::min;
 use std::collections::HashMap;
+use std::iter::once;
 use thiserror::Error;
 
 /// A sequence of musical notes.
@@ -43,11 +47,25 @@ pub enum InsertionError {
     /// The note was inside another note.
     #[error("cannot insert a note inside another one")]
     InsideOther,
+    /// The note to move did not exist.
+    #[error("the note does not exist")]
+    NonExistent,
     /// The note was outside the clip.
     #[error("cannot insert a note outside the selected clip")]
     OutsideClip,
 }
 
+/// Where a note is placed in a [group](Group) and how long it is.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Placement {
+    /// The position of the note.
+    pub position: relative::Instant,
+    /// The pitch of the note.
+    pub pitch: Pitch,
+    /// The duration of the note.
+    pub duration: NonZeroDuration,
+}
+
 impl Group {
     /// Constructs an empty note group.
     #[must_use]
@@ -72,8 +90,39 @@ impl Group {
         &mut self,
         position: relative::Instant,
         pitch: Pitch,
-        mut note: Note,
+        note: Note,
     ) -> Result<(), InsertionError> {
+        self.try_insert_or_return(position, pitch, note)
+            .map_err(|(error, _)| error)
+    }
+
+    /// Tries inserting a note into the group like [`try_insert`](Group::try_insert),
+    /// but gives back the note if it could not be inserted.
+    pub(crate) fn try_insert_or_return(
+        &mut self,
+        position: relative::Instant,
+        pitch: Pitch,
+        mut note: Note,
+    ) -> Result<(), (InsertionError, Box<Note>)> {
+        note.duration = match self.fitted_duration(position, pitch, note.duration) {
+            Ok(duration) => duration,
+            Err(error) => return Err((error, Box::new(note))),
+        };
+
+        self.note_positions.insert(note.id, (position, pitch));
+        self.notes.insert((position, pitch), note);
+
+        Ok(())
+    }
+
+    /// Returns the duration that a note inserted into the group would get,
+    /// or why it could not be inserted.
+    fn fitted_duration(
+        &self,
+        position: relative::Instant,
+        pitch: Pitch,
+        duration: NonZeroDuration,
+    ) -> Result<NonZeroDuration, InsertionError> {
         let end_of_group = relative::Instant {
             since_start: self.duration.get(),
         };
@@ -96,12 +145,10 @@ impl Group {
             return Err(InsertionError::InsideOther);
         };
 
-        note.duration = min(note.duration, max_duration);
-
         if let Some(last_note_end) = self
             .with_pitch(pitch)
-            .map(|(note_position, _)| note_position)
-            .filter(|note_position| *note_position < position)
+            .filter(|(note_position, _)| *note_position < position)
+            .map(|(note_position, note)| note_position + note.duration.get())
             .max()
             && position < last_note_end
         {
@@ -112,10 +159,160 @@ impl Group {
             return Err(InsertionError::InsideOther);
         }
 
-        self.note_positions.insert(note.id, (position, pitch));
-        self.notes.insert((position, pitch), note);
+        Ok(min(duration, max_duration))
+    }
 
-        Ok(())
+    /// Moves and resizes a note in the group.
+    ///
+    /// Returns where the note was placed before.
+    /// If the note does not fit at its new place, without being shortened, it is left where it was.
+    pub(crate) fn place(
+        &mut self,
+        note: Id<Note>,
+        placement: Placement,
+    ) -> Result<Placement, InsertionError> {
+        let Some((position, pitch, mut note)) = self.remove(note) else {
+            return Err(InsertionError::NonExistent);
+        };
+
+        let previous = Placement {
+            position,
+            pitch,
+            duration: note.duration,
+        };
+
+        // Unlike when inserting, the note is not shortened to fit.
+        let fits = self
+            .fitted_duration(placement.position, placement.pitch, placement.duration)
+            .and_then(|duration| {
+                let end = placement.position + placement.duration.get();
+
+                if duration == placement.duration {
+                    Ok(())
+                } else if self.duration.get() < end.since_start {
+                    Err(InsertionError::OutsideClip)
+                } else {
+                    Err(InsertionError::InsideOther)
+                }
+            });
+
+        if let Err(error) = fits {
+            // The note is put back where it was, which was just vacated.
+            self.note_positions.insert(note.id, (position, pitch));
+            self.notes.insert((position, pitch), note);
+
+            return Err(error);
+        }
+
+        note.duration = placement.duration;
+
+        self.note_positions
+            .insert(note.id, (placement.position, placement.pitch));
+        self.notes
+            .insert((placement.position, placement.pitch), note);
+
+        Ok(previous)
+    }
+
+    /// Returns a copy of the group, with new ids.
+    pub(crate) fn copied(&self) -> Group {
+        let mut group = Group::empty(self.duration);
+
+        // The copied notes satisfy the invariants since the original ones do.
+        for (position, pitch, note) in self.notes() {
+            let note = note.copy_with_duration(note.duration);
+
+            group.note_positions.insert(note.id(), (position, pitch));
+            group.notes.insert((position, pitch), note);
+        }
+
+        group
+    }
+
+    /// Returns a copy of the group, with new ids, cropped to a duration.
+    ///
+    /// Notes that start after the new end are dropped and notes that cross it are truncated.
+    ///
+    /// # Errors
+    ///
+    /// As long as the group's invariants hold, this will not return an error.
+    pub(crate) fn cropped(&self, duration: NonZeroDuration) -> Result<Group, InsertionError> {
+        let mut group = Group::empty(duration);
+
+        for (position, pitch, note) in self.ordered_notes() {
+            if duration.get() <= position.since_start {
+                continue;
+            }
+
+            group.try_insert(position, pitch, note.copy_with_duration(note.duration))?;
+        }
+
+        Ok(group)
+    }
+
+    /// Returns a copy of the group, with new ids, whose start is moved from `start` to `new_start`
+    /// and whose duration is `duration`.
+    ///
+    /// The notes keep their positions in the track.
+    /// Notes outside the new bounds are dropped and notes that cross them are truncated.
+    ///
+    /// # Errors
+    ///
+    /// As long as the group's invariants hold, this will not return an error.
+    pub(crate) fn with_start(
+        &self,
+        start: Instant,
+        new_start: Instant,
+        duration: NonZeroDuration,
+    ) -> Result<Group, InsertionError> {
+        let mut group = Group::empty(duration);
+
+        for (position, pitch, note) in self.ordered_notes() {
+            let note_start = start + position;
+            let note_end = note_start + note.duration.get();
+
+            let Some(note_duration) =
+                NonZeroDuration::from_duration(note_end - max(note_start, new_start))
+            else {
+                continue;
+            };
+
+            let position = relative::Instant {
+                since_start: note_start - new_start,
+            };
+
+            if duration.get() <= position.since_start {
+                continue;
+            }
+
+            group.try_insert(position, pitch, note.copy_with_duration(note_duration))?;
+        }
+
+        Ok(group)
+    }
+
+    /// Returns a copy of the group, with new ids, followed by another group.
+    ///
+    /// # Errors
+    ///
+    /// As long as the groups' invariants hold, this will not return an error.
+    pub(crate) fn joined(&self, other: &Group) -> Result<Group, InsertionError> {
+        let duration = NonZeroDuration::from_duration(self.duration.get() + other.duration.get())
+            .unwrap_or(self.duration);
+
+        let mut group = Group::empty(duration);
+
+        for (position, pitch, note) in self.ordered_notes() {
+            group.try_insert(position, pitch, note.copy_with_duration(note.duration))?;
+        }
+
+        for (position, pitch, note) in other.ordered_notes() {
+            let position = position + self.duration.get();
+
+            group.try_insert(position, pitch, note.copy_with_duration(note.duration))?;
+        }
+
+        Ok(group)
     }
 
     /// Remove a note from the group.
@@ -127,6 +324,36 @@ impl Group {
         Some((instant, pitch, note))
     }
 
+    /// Returns a note in the group, along with its position and pitch.
+    pub(crate) fn note(&self, note: Id<Note>) -> Option<(relative::Instant, Pitch, &Note)> {
+        let position = self.note_positions.get(&note)?;
+        let note = self.notes.get(position)?;
+
+        let (instant, pitch) = *position;
+        Some((instant, pitch, note))
+    }
+
+    /// Returns a mutable reference to a note in the group.
+    pub(crate) fn note_mut(&mut self, note: Id<Note>) -> Option<&mut Note> {
+        let position = self.note_positions.get(&note)?;
+        self.notes.get_mut(position)
+    }
+
+    /// Returns an iterator over all notes in the group, along with their positions and pitches.
+    pub(crate) fn notes(&self) -> impl Iterator<Item = (relative::Instant, Pitch, &Note)> {
+        self.notes
+            .iter()
+            .map(|((instant, pitch), note)| (*instant, *pitch, note))
+    }
+
+    /// Returns an iterator over all notes in the group, ordered by their positions and pitches.
+    ///
+    /// This is used where the order matters, such as when copying notes, since they get new ids.
+    fn ordered_notes(&self) -> impl Iterator<Item = (relative::Instant, Pitch, &Note)> {
+        self.notes()
+            .sorted_unstable_by_key(|(position, pitch, _)| (*position, *pitch))
+    }
+
     /// Returns an iterator over all notes with a given pitch.
     pub(crate) fn with_pitch(
         &self,
@@ -147,27 +374,50 @@ impl Group {
     }
 
     /// Turns the note group into a sequence of [note events](crate::note::Event).
+    ///
+    /// Notes are cropped to `duration`.
     pub(crate) fn to_events(
         &self,
         start: Instant,
+        duration: NonZeroDuration,
         time_context: &Changing<TimeContext>,
         sample_rate: sample::Rate,
     ) -> Sequence {
         self.notes
             .iter()
+            .filter(|((note_start, _), _)| note_start.since_start < duration.get())
             .flat_map(|((note_start, pitch), note)| {
                 let id = note.id;
                 let pitch = *pitch;
 
+                let note_duration =
+                    min(note.duration.get(), duration.get() - note_start.since_start);
+
                 let note_start = start + *note_start;
 
                 let start = note_start * time_context * sample_rate;
-                let end = (note_start + note.duration.get()) * time_context * sample_rate;
+                let end = (note_start + note_duration) * time_context * sample_rate;
+
+                let note_on = Event::NoteOn {
+                    id,
+                    pitch,
+                    velocity: note.velocity,
+                    articulation: note.articulation,
+                };
 
-                [
-                    (start, Event::NoteOn { id, pitch }),
-                    (end, Event::NoteOff(id)),
-                ]
+                // The expression events are put between the note-on and the note-off events,
+                // since events at the same instant are processed in order.
+                chain!(
+                    once((start, note_on)),
+                    note.expression.to_events(
+                        id,
+                        note_start,
+                        note_duration,
+                        time_context,
+                        sample_rate,
+                    ),
+                    once((end, Event::NoteOff(id))),
+                )
             })
             .collect()
     }
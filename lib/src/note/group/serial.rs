@@ -4,6 +4,7 @@ use crate::Note;
 use crate::metre::NonZeroDuration;
 use crate::note;
 use crate::note::InsertionError;
+use crate::note::Velocity;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::BTreeSet;
@@ -38,6 +39,9 @@ impl From<&note::Group> for Serial {
                     position: *position,
                     pitch: *pitch,
                     duration: note.duration,
+                    velocity: (note.velocity != Velocity::default()).then_some(note.velocity),
+                    articulation: note.articulation,
+                    expression: note.expression.clone(),
                 })
                 .collect(),
         }
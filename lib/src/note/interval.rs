@@ -1,11 +1,15 @@
 //! Items pertaining to [`Interval`].
 
+use serde::Deserialize;
+use serde::Serialize;
+
 /// An interval between two pitches.
 ///
 /// Note: No [`Eq`] or [`PartialEq`] implementation is provided.
 /// This is due to "equality"/"equivalence" is not semantically ubiquitous.
 /// To compare intervals, the [`semitones`](Interval::semitones) and [`simple`](todo) methods may be used.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Interval {
     /// The number of semitones that make up the interval..
     semitones: i8,
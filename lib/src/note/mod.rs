@@ -1,8 +1,12 @@
 //! Types relating to [`Group`].
 
+pub mod drums;
+pub mod expression;
+
 pub(crate) mod event;
 pub(crate) mod group;
 
+mod articulation;
 mod id;
 mod interval;
 mod key;
@@ -11,10 +15,19 @@ mod pitch;
 mod pitch_class;
 mod serial;
 mod sign;
+mod transposition;
+mod velocity;
 
+pub use articulation::Articulation;
+pub use drums::Drums;
+pub use expression::Expression;
 use getset::CopyGetters;
+use getset::Getters;
+use getset::MutGetters;
+use getset::Setters;
 pub use group::Group;
 pub use group::InsertionError;
+pub use group::Placement;
 pub use id::Path;
 pub use interval::Interval;
 pub use key::Key;
@@ -22,6 +35,8 @@ pub use non_unison_simple_interval::NonUnisonSimpleInterval;
 pub use pitch::Pitch;
 pub use pitch_class::PitchClass;
 pub use sign::Sign;
+pub use transposition::Transposition;
+pub use velocity::Velocity;
 
 #[doc(inline)]
 pub(crate) use event::Event;
@@ -33,10 +48,8 @@ use sign::FLAT;
 use sign::SHARP;
 
 // TODO: Test that this isn't `Clone` (bc. id).
-// TODO: pitch-bends?
 /// A [note](https://en.wikipedia.org/wiki/Musical_note).
-#[derive(Eq, PartialEq, Debug, CopyGetters)]
-#[expect(missing_copy_implementations, reason = "`Id`s should be unique")]
+#[derive(Eq, PartialEq, Debug, Getters, MutGetters, CopyGetters, Setters)]
 pub struct Note {
     /// The id.
     #[get_copy = "pub(crate)"]
@@ -44,15 +57,40 @@ pub struct Note {
     /// The duration of the note.
     #[get_copy = "pub(crate)"]
     duration: NonZeroDuration,
-    // TODO: articulation
+    /// How hard the note is played.
+    #[get_copy = "pub(crate)"]
+    #[set = "pub(crate)"]
+    velocity: Velocity,
+    /// The articulation, or `None` if the note is played normally.
+    #[get_copy = "pub(crate)"]
+    #[set = "pub(crate)"]
+    articulation: Option<Articulation>,
+    /// The pitch bend, pressure and timbre curves.
+    #[get = "pub(crate)"]
+    #[get_mut = "pub(crate)"]
+    expression: Expression,
 }
 
 impl Note {
-    /// Constructs a new note.
+    /// Constructs a new note with the default velocity and neither articulation nor expression.
     pub(crate) fn new(duration: NonZeroDuration) -> Note {
         Note {
             id: Id::generate(),
             duration,
+            velocity: Velocity::default(),
+            articulation: None,
+            expression: Expression::default(),
+        }
+    }
+
+    /// Returns a copy of the note with a new id and another duration.
+    pub(crate) fn copy_with_duration(&self, duration: NonZeroDuration) -> Note {
+        Note {
+            id: Id::generate(),
+            duration,
+            velocity: self.velocity,
+            articulation: self.articulation,
+            expression: self.expression.clone(),
         }
     }
 }
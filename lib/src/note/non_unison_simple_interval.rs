@@ -54,6 +54,24 @@ impl NonUnisonSimpleInterval {
             | NonUnisonSimpleInterval::m7
     );
 
+    /// Returns the number of semitones in the interval.
+    #[must_use]
+    pub fn semitones(self) -> i8 {
+        match self {
+            NonUnisonSimpleInterval::m2 => 1,
+            NonUnisonSimpleInterval::M2 => 2,
+            NonUnisonSimpleInterval::m3 => 3,
+            NonUnisonSimpleInterval::M3 => 4,
+            NonUnisonSimpleInterval::P4 => 5,
+            NonUnisonSimpleInterval::TT => 6,
+            NonUnisonSimpleInterval::P5 => 7,
+            NonUnisonSimpleInterval::m6 => 8,
+            NonUnisonSimpleInterval::M6 => 9,
+            NonUnisonSimpleInterval::m7 => 10,
+            NonUnisonSimpleInterval::M7 => 11,
+        }
+    }
+
     /// Returns the name for a collection of intervals.
     #[must_use]
     pub fn collection_name(intervals: EnumSet<NonUnisonSimpleInterval>) -> &'static str {
@@ -12,6 +12,7 @@ use std::ops::AddAssign;
 use std::ops::Sub;
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize)]
+#[serde(transparent)]
 /// A pitch / frequency within the MIDI range.
 pub struct Pitch {
     // INVARIANT: this is non-negative
@@ -59,6 +60,15 @@ impl Pitch {
         format!("{}{}", self.class().name(sign), self.octave_number())
     }
 
+    /// Returns the pitch moved by an interval,
+    /// or [`None`] if the result is outside the MIDI range.
+    #[must_use]
+    pub fn checked_add(self, interval: Interval) -> Option<Pitch> {
+        let midi_number = self.midi_number.checked_add(interval.semitones())?;
+
+        (!midi_number.is_negative()).then_some(Pitch { midi_number })
+    }
+
     /// Returns the frequency (per Hertz) that the pitch represents.
     pub(crate) fn frequency(self) -> f32 {
         440.0 * 2_f32.powf((f32::from(self.midi_number) - 69.0) / 12.0)
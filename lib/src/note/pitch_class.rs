@@ -101,6 +101,24 @@ impl PitchClass {
         }
     }
 
+    /// Returns the number of semitones from C up to the pitch class.
+    pub(crate) fn semitones_above_c(self) -> i8 {
+        match self {
+            PitchClass::C => 0,
+            PitchClass::Db => 1,
+            PitchClass::D => 2,
+            PitchClass::Eb => 3,
+            PitchClass::E => 4,
+            PitchClass::F => 5,
+            PitchClass::Gb => 6,
+            PitchClass::G => 7,
+            PitchClass::Ab => 8,
+            PitchClass::A => 9,
+            PitchClass::Bb => 10,
+            PitchClass::B => 11,
+        }
+    }
+
     /// The name of the pitch class using sharp signs.
     fn sharp_name(self) -> ArcStr {
         match self {
@@ -4,7 +4,10 @@ use crate::Id;
 use crate::Note;
 use crate::metre::NonZeroDuration;
 use crate::metre::relative;
+use crate::note::Articulation;
+use crate::note::Expression;
 use crate::note::Pitch;
+use crate::note::Velocity;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -17,6 +20,15 @@ pub(crate) struct Serial {
     pub pitch: Pitch,
     /// The duration.
     pub duration: NonZeroDuration,
+    /// The velocity, or `None` if it is the default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub velocity: Option<Velocity>,
+    /// The articulation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub articulation: Option<Articulation>,
+    /// The expression.
+    #[serde(default, skip_serializing_if = "Expression::is_empty")]
+    pub expression: Expression,
 }
 
 impl From<Serial> for Note {
@@ -24,6 +36,9 @@ impl From<Serial> for Note {
         Note {
             id: Id::generate(),
             duration: serial.duration,
+            velocity: serial.velocity.unwrap_or_default(),
+            articulation: serial.articulation,
+            expression: serial.expression,
         }
     }
 }
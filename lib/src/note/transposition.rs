@@ -0,0 +1,81 @@
+//! Items pertaining to [`Transposition`].
+
+use crate::note::Interval;
+use crate::note::Key;
+use crate::note::Pitch;
+use serde::Deserialize;
+use serde::Serialize;
+use std::iter::once;
+
+/// A way of moving pitches up or down.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transposition {
+    /// Moves pitches by an interval.
+    Chromatic(Interval),
+    /// Moves pitches by a number of steps in the key.
+    ///
+    /// Pitches outside the key keep their distance to the closest step below them.
+    Diatonic(i8),
+}
+
+impl Transposition {
+    /// Whether the transposition moves pitches upwards.
+    #[must_use]
+    pub fn is_upwards(self) -> bool {
+        match self {
+            Transposition::Chromatic(interval) => interval.semitones().is_positive(),
+            Transposition::Diatonic(steps) => steps.is_positive(),
+        }
+    }
+
+    /// Transposes a pitch in a key.
+    ///
+    /// Returns [`None`] if the result is outside the MIDI range.
+    #[must_use]
+    pub fn transpose(self, pitch: Pitch, key: Key) -> Option<Pitch> {
+        let steps = match self {
+            Transposition::Chromatic(interval) => return pitch.checked_add(interval),
+            Transposition::Diatonic(steps) => i32::from(steps),
+        };
+
+        let tonic = Pitch::LOWEST + Interval::from_semitones(key.tonic.semitones_above_c());
+
+        // The semitones of the steps of the key, relative to the tonic.
+        let degrees: Vec<i32> = once(0)
+            .chain(
+                key.intervals
+                    .iter()
+                    .map(|interval| i32::from(interval.semitones())),
+            )
+            .collect();
+        let degree_count = i32::try_from(degrees.len()).ok()?;
+
+        let above_tonic = i32::from((pitch - tonic).semitones());
+        let octave = above_tonic.div_euclid(12);
+        let within_octave = above_tonic.rem_euclid(12);
+
+        let (degree, degree_semitones) = degrees
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, semitones)| **semitones <= within_octave)?;
+        let alteration = within_octave.saturating_sub(*degree_semitones);
+
+        let step = octave
+            .saturating_mul(degree_count)
+            .saturating_add(i32::try_from(degree).ok()?)
+            .saturating_add(steps);
+
+        let octave = step.div_euclid(degree_count);
+        let degree = usize::try_from(step.rem_euclid(degree_count)).ok()?;
+        let degree_semitones = degrees.get(degree)?;
+
+        let semitones = octave
+            .saturating_mul(12)
+            .saturating_add(*degree_semitones)
+            .saturating_add(alteration);
+
+        tonic.checked_add(Interval::from_semitones(i8::try_from(semitones).ok()?))
+    }
+}
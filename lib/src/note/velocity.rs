@@ -0,0 +1,83 @@
+//! Items pertaining to [`Velocity`].
+
+use crate::Ratio;
+use non_zero::non_zero;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// How hard a note is played, within the MIDI range.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize)]
+#[serde(transparent)]
+pub struct Velocity {
+    /// The MIDI velocity.
+    ///
+    /// # Invariants
+    ///
+    /// This is in the range `1..=127`.
+    /// A velocity of zero is used by MIDI to turn notes off.
+    value: u8,
+}
+
+impl Velocity {
+    /// The softest velocity.
+    pub const MIN: Velocity = Velocity { value: 1 };
+
+    /// The loudest velocity.
+    pub const MAX: Velocity = Velocity { value: 127 };
+
+    /// Constructs a new velocity from a MIDI velocity.
+    ///
+    /// Returns `None` if the value is outside the range `1..=127`.
+    #[must_use]
+    pub const fn new(value: u8) -> Option<Velocity> {
+        if value < Velocity::MIN.value || Velocity::MAX.value < value {
+            return None;
+        }
+
+        Some(Velocity { value })
+    }
+
+    /// Returns the MIDI velocity.
+    #[must_use]
+    pub fn get(self) -> u8 {
+        self.value
+    }
+
+    /// Returns the velocity as a fraction of the loudest velocity.
+    pub(crate) fn fraction(self) -> Ratio {
+        Ratio::new(u64::from(self.value), non_zero!(127))
+    }
+
+    /// Returns the amplitude, between zero and one, with which a note of this velocity is played.
+    pub(crate) fn amplitude(self) -> f32 {
+        f32::from(self.value) / f32::from(Velocity::MAX.value)
+    }
+}
+
+impl Default for Velocity {
+    fn default() -> Self {
+        Velocity { value: 100 }
+    }
+}
+
+impl Display for Velocity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl<'de> Deserialize<'de> for Velocity {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let value = u8::deserialize(deserializer)?;
+
+        Velocity::new(value).ok_or_else(|| Error::custom("velocities must be in the range 1..=127"))
+    }
+}
@@ -9,6 +9,7 @@ use crate::UserInterface;
 use crate::View;
 use crate::app::Action;
 use crate::audio::Player;
+use crate::metre;
 use crate::metre::Changing;
 use crate::metre::Instant;
 use crate::metre::NonZeroDuration;
@@ -16,13 +17,18 @@ use crate::metre::OffsetMapping;
 use crate::metre::Quantisation;
 use crate::metre::TimeContext;
 use crate::note;
+use crate::note::Drums;
 use crate::note::Group;
 use crate::note::Interval;
 use crate::note::Key;
 use crate::note::Pitch;
+use crate::note::Velocity;
+use crate::note::drums::Lane;
+use crate::note::expression::Dimension;
 use crate::project::Edit;
 use crate::project::Track;
 use crate::project::track::Clip;
+use crate::project::track::clip;
 use crate::select::Selection;
 use crate::ui::Colour;
 use crate::ui::Length;
@@ -33,6 +39,7 @@ use crate::ui::Vector;
 use crate::ui::relative;
 use crate::view::Alignment;
 use crate::view::CursorWindow;
+use crate::view::OnClick;
 use crate::view::Quoted;
 use crate::view::RenderArea;
 use crate::view::ToText as _;
@@ -40,6 +47,7 @@ use crate::view::ruler;
 use arcstr::ArcStr;
 use arcstr::literal;
 use bon::bon;
+use enum_iterator::next_cycle;
 use getset::CopyGetters;
 use getset::Setters;
 use itertools::chain;
@@ -55,6 +63,16 @@ const NO_CLIP_SELECTED: ArcStr = literal!("please select a clip to edit");
 // TODO: add audio clip "editing"
 /// The error message shown if an audio clip is selected.
 const AUDIO_CLIP_SELECTED: ArcStr = literal!("cannot edit audio clips (yet)");
+/// How much the velocity of a drum hit decreases each time it is clicked.
+const DRUM_VELOCITY_DECREMENT: u8 = 32;
+/// The label of the velocity lane.
+const VELOCITY: ArcStr = literal!("velocity");
+/// The label of the articulation button of a note that is played normally.
+const NO_ARTICULATION: ArcStr = literal!("-");
+/// The label of the button that clears the curve of a note.
+const CLEAR: ArcStr = literal!("clear");
+/// The height of the lane below the piano roll, in keys.
+const LANE_KEYS: u64 = 4;
 
 /// Volatile settings for the piano roll.
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Setters, CopyGetters)]
@@ -72,6 +90,11 @@ pub struct PianoRoll {
     #[set = "pub(crate)"]
     #[get_copy = "pub(crate)"]
     is_open: bool,
+    /// The dimension of expression that is edited in the lane below the piano roll.
+    ///
+    /// If this is `None`, velocities are edited instead.
+    #[set = "pub(crate)"]
+    expression_lane: Option<Dimension>,
 
     /// The width of piano key.
     key_width: NonZeroLength,
@@ -94,6 +117,7 @@ impl PianoRoll {
             y_offset: a3_offset,
             content_height: three_octaves,
             is_open: false,
+            expression_lane: None,
             key_width: Ui::KEY_WIDTH,
             piano_depth: Ui::PIANO_DEPTH,
             black_key_depth: Ui::BLACK_KEY_DEPTH,
@@ -103,11 +127,16 @@ impl PianoRoll {
     /// Returns [`self.y_offset`] but clamped such that the piano roll is not scrolled past the top key.
     fn clamped_y_offset<Ui: UserInterface>(self) -> Length {
         let full_roll_height = self.key_width.get() * Ratio::integer(128);
-        let workspace_height = self.content_height - Ui::RULER_HEIGHT.get();
+        let workspace_height = self.content_height - Ui::RULER_HEIGHT.get() - self.lane_height();
 
         min(self.y_offset, full_roll_height - workspace_height)
     }
 
+    /// Returns the height of the lane below the piano roll.
+    fn lane_height(self) -> Length {
+        self.key_width.get() * Ratio::integer(LANE_KEYS)
+    }
+
     /// Moves the piano roll by an offset.
     pub(crate) fn move_by<Ui: UserInterface>(&mut self, by: Vector) {
         self.negative_x_offset -= by.x;
@@ -181,12 +210,34 @@ impl PianoRoll {
             return NO_CLIP_SELECTED.centred();
         };
 
-        let Some(notes) = clip.content().as_notes() else {
-            return AUDIO_CLIP_SELECTED.centred();
+        let Some(content) = clip.content().resolve(project.linked_contents()) else {
+            return NO_CLIP_SELECTED.centred();
         };
 
         let offset_mapping = OffsetMapping::new(project.time_signature().clone(), quantisation);
 
+        if let Some(drums) = content.as_drums() {
+            let grid = self
+                .drum_grid()
+                .clip_path(clip_path)
+                .clip_start(clip_start)
+                .clip(clip)
+                .drums(drums)
+                .offset_mapping(&offset_mapping)
+                .call();
+
+            let ruler = ruler(self.negative_x_offset, offset_mapping)
+                .fill_remaining()
+                .x_positioned(self.piano_depth.get());
+
+            return View::y_stack([ruler.quoted(Ui::RULER_HEIGHT), grid.fill_remaining()])
+                .scrollable(Action::MovePianoRoll);
+        }
+
+        let Some(notes) = content.as_notes() else {
+            return AUDIO_CLIP_SELECTED.centred();
+        };
+
         // The piano roll has a fixed lower pitch.
         // Resizing it will thus cause the bottom to be fixed.
         // Since the top is the thing being moved, this seems intuitive.
@@ -205,12 +256,35 @@ impl PianoRoll {
             .key(project.key())
             .call();
 
+        let lane = if let Some(dimension) = self.expression_lane {
+            self.curve_lane()
+                .track(clip_path.track)
+                .clip_start(clip_start)
+                .clip(clip)
+                .notes(notes)
+                .dimension(dimension)
+                .offset_mapping(&offset_mapping)
+                .call()
+        } else {
+            self.velocity_lane()
+                .track(clip_path.track)
+                .clip_start(clip_start)
+                .clip(clip)
+                .notes(notes)
+                .offset_mapping(&offset_mapping)
+                .call()
+        };
+
         let ruler = ruler(self.negative_x_offset, offset_mapping)
             .fill_remaining()
             .x_positioned(self.piano_depth.get());
 
-        View::y_stack([ruler.quoted(Ui::RULER_HEIGHT), workspace.fill_remaining()])
-            .scrollable(Action::MovePianoRoll)
+        View::y_stack([
+            ruler.quoted(Ui::RULER_HEIGHT),
+            workspace.fill_remaining(),
+            lane.quoted(self.lane_height()),
+        ])
+        .scrollable(Action::MovePianoRoll)
     }
 
     #[builder]
@@ -278,7 +352,8 @@ impl PianoRoll {
             lowest_row_height
         };
 
-        let remaining_space = self.content_height - Ui::RULER_HEIGHT.get() - lowest_row_height;
+        let remaining_space =
+            self.content_height - Ui::RULER_HEIGHT.get() - self.lane_height() - lowest_row_height;
 
         let number_of_full_keys = (remaining_space / self.key_width).floor();
 
@@ -287,7 +362,7 @@ impl PianoRoll {
             + Interval::SEMITONE;
 
         let lowest_row = self
-            .row()
+            .row::<Ui>()
             .track(track)
             .clip_start(clip_start)
             .clip(clip)
@@ -300,7 +375,7 @@ impl PianoRoll {
             .quoted(lowest_row_height);
 
         let highest_row = self
-            .row()
+            .row::<Ui>()
             .track(track)
             .clip_start(clip_start)
             .clip(clip)
@@ -319,7 +394,7 @@ impl PianoRoll {
             let pitch = lowest_visible_pitch + interval;
 
             rows.push(
-                self.row()
+                self.row::<Ui>()
                     .track(track)
                     .clip_start(clip_start)
                     .clip(clip)
@@ -340,7 +415,7 @@ impl PianoRoll {
 
     /// Return the view for a row in the piano roll.
     #[builder]
-    fn row(
+    fn row<Ui: UserInterface>(
         self,
         track: Id<Track>,
         clip_start: Instant,
@@ -401,8 +476,17 @@ impl PianoRoll {
 
                     let path = note::Path::new(track, clip.id(), note.id());
 
+                    let note_end = clip_start + note_start.since_start + note.duration().get();
+
                     Self::note_visual(clip.colour())
                         .selectable(Selectable::Note(path))
+                        .grabbable(self.note_grabber::<Ui>(
+                            path,
+                            pitch,
+                            start,
+                            note_end,
+                            offset_mapping.clone(),
+                        ))
                         .quoted(width)
                         .x_positioned(start)
                 }),
@@ -413,22 +497,11 @@ impl PianoRoll {
         let grabber = grabber(self.negative_x_offset, offset_mapping.clone(), edit_mode);
 
         let dropper = move |object, render_area: RenderArea| {
-            let Holdable::NoteCreation { start } = object else {
-                return None;
-            };
-            let end = offset_mapping.quantised_instant(
+            let instant = offset_mapping.quantised_instant(
                 render_area.relative_mouse_position()?.x + self.negative_x_offset,
             );
 
-            let (start, end) = (min(start, end), max(start, end));
-
-            let duration = NonZeroDuration::from_duration(end - start)?;
-
-            Some(Action::Edit(Edit::AddNote {
-                position: start,
-                pitch,
-                duration,
-            }))
+            Some(Action::Edit(dropped_note_edit(object, instant, pitch)?))
         };
 
         let overview = view.grabbable(grabber).object_accepting(dropper);
@@ -439,6 +512,311 @@ impl PianoRoll {
         ])
     }
 
+    /// Returns the velocity lane, in which the velocities and articulations of notes are edited.
+    ///
+    /// Clicking in the column of a note sets its velocity according to the height of the click.
+    #[builder]
+    fn velocity_lane(
+        self,
+        track: Id<Track>,
+        clip_start: Instant,
+        clip: &Clip,
+        notes: &Group,
+        offset_mapping: &OffsetMapping,
+    ) -> View {
+        let bars = notes.notes().map(|(note_start, _, note)| {
+            let start =
+                offset_mapping.offset(clip_start + note_start.since_start) - self.negative_x_offset;
+            let end = offset_mapping
+                .offset(clip_start + note_start.since_start + note.duration().get())
+                - self.negative_x_offset;
+
+            let path = note::Path::new(track, clip.id(), note.id());
+
+            let articulation = note.articulation();
+            let label = articulation.map_or(NO_ARTICULATION, |articulation| {
+                arcstr::format!("{articulation}")
+            });
+            let articulation = View::simple_button(
+                label,
+                OnClick::from(Edit::SetArticulation {
+                    note: path,
+                    articulation: next_cycle(&articulation),
+                }),
+            );
+
+            let colour = clip.colour();
+            let velocity = note.velocity();
+
+            let bar = View::reactive(move |render_area| {
+                let height = render_area.area.size.height * velocity.fraction();
+
+                View::y_stack([
+                    View::Empty.fill_remaining(),
+                    Self::note_visual(colour).quoted(height),
+                ])
+            })
+            .on_click(OnClick::new(move |render_area, actions| {
+                if let Some(velocity) = velocity_at(render_area) {
+                    actions.push(Action::Edit(Edit::SetVelocity {
+                        note: path,
+                        velocity,
+                    }));
+                }
+            }));
+
+            View::y_stack([articulation.quoted_minimally(), bar.fill_remaining()])
+                .quoted(end - start)
+                .x_positioned(start)
+        });
+
+        View::x_stack([
+            self.lane_selector().quoted(self.piano_depth),
+            View::Layers(
+                chain(once(View::Solid(ThemeColour::PianoRollBackground)), bars).collect(),
+            )
+            .fill_remaining(),
+        ])
+    }
+
+    /// Returns the curve lane, in which one dimension of the expression of notes is edited.
+    ///
+    /// Clicking in the column of a note sets a point on its curve at the (quantised) time and height of the click.
+    #[builder]
+    fn curve_lane(
+        self,
+        track: Id<Track>,
+        clip_start: Instant,
+        clip: &Clip,
+        notes: &Group,
+        dimension: Dimension,
+        offset_mapping: &OffsetMapping,
+    ) -> View {
+        let columns = notes.notes().map(|(note_start, _, note)| {
+            let note_start = clip_start + note_start.since_start;
+            let note_end = note_start + note.duration().get();
+
+            let start = offset_mapping.offset(note_start) - self.negative_x_offset;
+            let end = offset_mapping.offset(note_end) - self.negative_x_offset;
+
+            let path = note::Path::new(track, clip.id(), note.id());
+
+            let clear = View::simple_button(
+                CLEAR,
+                OnClick::from(Edit::ClearCurve {
+                    note: path,
+                    dimension,
+                }),
+            );
+
+            let colour = clip.colour();
+            let point_size = Size {
+                width: self.key_width.get(),
+                height: self.key_width.get(),
+            };
+
+            let points: Vec<_> = note
+                .expression()
+                .curve(dimension)
+                .points()
+                .map(|(at, value)| (offset_mapping.offset(note_start + at) - start, value))
+                .collect();
+
+            let curve = View::reactive(move |render_area| {
+                let height = render_area.area.size.height - point_size.height;
+
+                View::Layers(
+                    points
+                        .iter()
+                        .map(|(x, value)| {
+                            let position = relative::Point {
+                                x: *x,
+                                y: height - height * dimension.fraction(*value),
+                            };
+
+                            Self::note_visual(colour)
+                                .quoted_2d(point_size)
+                                .positioned(position)
+                        })
+                        .collect(),
+                )
+            });
+
+            let negative_x_offset = self.negative_x_offset;
+            let offset_mapping = offset_mapping.clone();
+
+            let curve = curve.on_click(OnClick::new(move |render_area, actions| {
+                let Some(mouse_position) = render_area.relative_mouse_position() else {
+                    return;
+                };
+                let Some(height) = NonZeroLength::from_length(render_area.area.size.height) else {
+                    return;
+                };
+
+                let instant =
+                    offset_mapping.quantised_instant(start + mouse_position.x + negative_x_offset);
+                let at = metre::relative::Instant {
+                    since_start: instant - note_start,
+                };
+
+                let value = dimension.value_at((height.get() - mouse_position.y) / height);
+
+                actions.push(Action::Edit(Edit::SetExpressionPoint {
+                    note: path,
+                    dimension,
+                    at,
+                    value: Some(value),
+                }));
+            }));
+
+            View::y_stack([clear.quoted_minimally(), curve.fill_remaining()])
+                .quoted(end - start)
+                .x_positioned(start)
+        });
+
+        View::x_stack([
+            self.lane_selector().quoted(self.piano_depth),
+            View::Layers(
+                chain(once(View::Solid(ThemeColour::PianoRollBackground)), columns).collect(),
+            )
+            .fill_remaining(),
+        ])
+    }
+
+    /// Returns the button that labels the lane below the piano roll and cycles what it edits.
+    fn lane_selector(self) -> View {
+        let label = self
+            .expression_lane
+            .map_or(VELOCITY, |dimension| arcstr::format!("{dimension}"));
+
+        View::simple_button(
+            label,
+            OnClick::from(Action::SetExpressionLane(next_cycle(&self.expression_lane))),
+        )
+    }
+
+    /// Returns the grid editor for a drum clip, which is shown in place of the piano roll.
+    #[builder]
+    fn drum_grid(
+        self,
+        clip_path: clip::Path,
+        clip_start: Instant,
+        clip: &Clip,
+        drums: &Drums,
+        offset_mapping: &OffsetMapping,
+    ) -> View {
+        View::y_stack(drums.lanes().iter().enumerate().map(|(index, lane)| {
+            self.drum_lane()
+                .clip_path(clip_path)
+                .clip_start(clip_start)
+                .clip(clip)
+                .drums(drums)
+                .index(index)
+                .lane(lane)
+                .offset_mapping(offset_mapping)
+                .call()
+                .quoted(self.key_width)
+        }))
+    }
+
+    /// Returns the view for a lane in the drum grid.
+    ///
+    /// Clicking a step cycles it from a loud hit, through softer ones, to no hit.
+    #[builder]
+    fn drum_lane(
+        self,
+        clip_path: clip::Path,
+        clip_start: Instant,
+        clip: &Clip,
+        drums: &Drums,
+        index: usize,
+        lane: &Lane,
+        offset_mapping: &OffsetMapping,
+    ) -> View {
+        let clip_end = clip_start + min(clip.duration(), drums.duration()).get();
+
+        let background = View::Solid(if index.is_multiple_of(2) {
+            ThemeColour::PianoRollBackground
+        } else {
+            ThemeColour::AlternatePianoRollBackground
+        });
+
+        let steps = (0..drums.step_count().get()).map_while(|step| {
+            let step_start = clip_start + drums.step_duration().get() * step;
+
+            if clip_end <= step_start {
+                return None;
+            }
+
+            let step_end = min(step_start + drums.step_duration().get(), clip_end);
+
+            let start = offset_mapping.offset(step_start) - self.negative_x_offset;
+            let end = offset_mapping.offset(step_end) - self.negative_x_offset;
+
+            let velocity = lane.hit(step);
+
+            let hit = velocity.map_or(View::Empty, |velocity| {
+                View::Layers(vec![
+                    Self::note_visual(clip.colour()),
+                    arcstr::format!("{velocity}").centred(),
+                ])
+            });
+
+            let on_click = OnClick::from(Action::Edit(Edit::SetDrumHit {
+                clip: clip_path,
+                lane: index,
+                step,
+                velocity: next_drum_velocity(velocity),
+            }));
+
+            Some(
+                hit.bordered()
+                    .on_click(on_click)
+                    .quoted(end - start)
+                    .x_positioned(start),
+            )
+        });
+
+        let name = View::Layers(vec![
+            View::Solid(ThemeColour::WhiteKey),
+            lane.name().clone().aligned_to(Alignment::Right),
+        ]);
+
+        View::x_stack([
+            name.quoted(self.piano_depth),
+            View::Layers(chain(once(background), steps).collect()).fill_remaining(),
+        ])
+    }
+
+    /// Returns a grabber for a note that starts at `start` in the row and ends at `end`.
+    ///
+    /// Grabbing the right edge of the note grabs its end.
+    fn note_grabber<Ui: UserInterface>(
+        self,
+        note: note::Path,
+        pitch: Pitch,
+        start: Length,
+        end: Instant,
+        offset_mapping: OffsetMapping,
+    ) -> impl Fn(RenderArea) -> Option<Holdable> {
+        move |render_area: RenderArea| {
+            let mouse_position = render_area.relative_mouse_position()?;
+
+            if render_area.area.size.width - Ui::BORDER_THICKNESS <= mouse_position.x {
+                return Some(Holdable::NoteEnd { note, end });
+            }
+
+            let instant =
+                offset_mapping.quantised_instant(start + mouse_position.x + self.negative_x_offset);
+
+            Some(Holdable::Note {
+                note,
+                instant,
+                pitch,
+            })
+        }
+    }
+
     /// Return a purely visual [view](View) of a note.
     fn note_visual(colour: Colour) -> View {
         View::Solid(ThemeColour::Custom(colour))
@@ -488,10 +866,16 @@ impl PianoRoll {
         let start = match held_object {
             Holdable::NoteCreation { start } => start,
             Holdable::Clip(_)
+            | Holdable::ClipEnd(_)
+            | Holdable::ClipStart(_)
+            | Holdable::LoopRegion { .. }
+            | Holdable::Note { .. }
+            | Holdable::NoteEnd { .. }
             | Holdable::PianoRollHandle { .. }
             | Holdable::Popup { .. }
             | Holdable::PopupSide { .. }
-            | Holdable::SelectionBox { .. } => {
+            | Holdable::SelectionBox { .. }
+            | Holdable::Track(_) => {
                 return View::Empty;
             }
         };
@@ -523,3 +907,78 @@ impl PianoRoll {
         })
     }
 }
+
+/// Returns the velocity that a drum hit is given when its step is clicked.
+///
+/// Empty steps get the loudest hit and hits get softer until they are removed.
+fn next_drum_velocity(velocity: Option<Velocity>) -> Option<Velocity> {
+    let Some(velocity) = velocity else {
+        return Some(Velocity::MAX);
+    };
+
+    Velocity::new(velocity.get().saturating_sub(DRUM_VELOCITY_DECREMENT))
+        .filter(|velocity| DRUM_VELOCITY_DECREMENT <= velocity.get())
+}
+
+/// Returns the velocity corresponding to the height of the mouse in the velocity lane.
+fn velocity_at(render_area: RenderArea) -> Option<Velocity> {
+    let mouse_position = render_area.relative_mouse_position()?;
+    let height = NonZeroLength::from_length(render_area.area.size.height)?;
+
+    let value = (height.get() - mouse_position.y) * Ratio::from(Velocity::MAX.get()) / height;
+
+    Velocity::new(value.round().saturating_cast()).or(Some(Velocity::MIN))
+}
+
+/// Returns the edit for dropping an object on the row of `pitch` in the piano roll, at `instant`.
+fn dropped_note_edit(object: Holdable, instant: Instant, pitch: Pitch) -> Option<Edit> {
+    let edit = match object {
+        Holdable::Note {
+            instant: grabbed_at,
+            pitch: grabbed_pitch,
+            ..
+        } => {
+            let by_pitch = pitch - grabbed_pitch;
+
+            if instant == grabbed_at && by_pitch.semitones() == 0 {
+                return None;
+            }
+
+            Edit::MoveNotes {
+                by_time: max(instant, grabbed_at) - min(instant, grabbed_at),
+                earlier: instant < grabbed_at,
+                by_pitch,
+            }
+        }
+        Holdable::NoteCreation { start } => {
+            let (start, end) = (min(start, instant), max(start, instant));
+
+            Edit::AddNote {
+                position: start,
+                pitch,
+                duration: NonZeroDuration::from_duration(end - start)?,
+            }
+        }
+        Holdable::NoteEnd { end, .. } => {
+            if instant == end {
+                return None;
+            }
+
+            Edit::ResizeNotes {
+                by: max(instant, end) - min(instant, end),
+                shorten: instant < end,
+            }
+        }
+        Holdable::Clip(_)
+        | Holdable::ClipEnd(_)
+        | Holdable::ClipStart(_)
+        | Holdable::LoopRegion { .. }
+        | Holdable::PianoRollHandle { .. }
+        | Holdable::Popup { .. }
+        | Holdable::PopupSide { .. }
+        | Holdable::SelectionBox { .. }
+        | Holdable::Track(_) => return None,
+    };
+
+    Some(edit)
+}
@@ -4,9 +4,11 @@ use crate::Id;
 use crate::Popup;
 use crate::UserInterface;
 use crate::popup::Specification;
+use crate::sync::ArcCell;
 use crate::ui::Rectangle;
 use indexmap::IndexMap;
 use parking_lot::Mutex;
+use std::sync::Arc;
 
 /// A manager for the open [popups](PopupSpecification).
 /// It uses internal mutability so that multiple threads can open popups concurrently.
@@ -31,6 +33,17 @@ impl Manager {
         self.popups.lock().clone().into_values()
     }
 
+    /// Returns the text field of the most recently opened popup that has one.
+    ///
+    /// This is the text field that typed text goes into.
+    pub(crate) fn text_field(&self) -> Option<Arc<ArcCell<str>>> {
+        self.popups
+            .lock()
+            .values()
+            .rev()
+            .find_map(|popup| popup.text_field.clone())
+    }
+
     /// Closes a [popup](Popup).
     pub fn close(&self, id: Id<Popup>) {
         let popup = self.popups.lock().shift_remove(&id);
@@ -8,6 +8,7 @@ pub use specification::Specification;
 pub(crate) use manager::Manager;
 
 use crate::View;
+use crate::sync::ArcCell;
 use crate::ui::Point;
 use crate::ui::Rectangle;
 use getset::CopyGetters;
@@ -25,12 +26,22 @@ pub struct Popup {
     area: Rectangle,
     /// The view of the popup.
     view: Arc<View>,
+    /// The text field of the popup, if it has one.
+    text_field: Option<Arc<ArcCell<str>>>,
 }
 
 impl Popup {
     /// Constructs a new `Instance`.
-    pub(crate) fn new(view: Arc<View>, area: Rectangle) -> Popup {
-        Popup { area, view }
+    pub(crate) fn new(
+        view: Arc<View>,
+        area: Rectangle,
+        text_field: Option<Arc<ArcCell<str>>>,
+    ) -> Popup {
+        Popup {
+            area,
+            view,
+            text_field,
+        }
     }
 
     /// Converts the popup into a [window view](View::Window).
@@ -8,12 +8,21 @@ use crate::UserInterface;
 use crate::View;
 use crate::app::Action;
 use crate::holdable::WindowSide;
+use crate::metre::Instant;
+use crate::metre::Ramp;
+use crate::metre::TimeSignature;
 use crate::note::Key;
 use crate::note::NonUnisonSimpleInterval;
 use crate::note::PitchClass;
 use crate::note::Sign;
 use crate::project::Edit;
+use crate::project::Track;
+use crate::project::track::clip;
+use crate::string::ToArcStr as _;
+use crate::sync::ArcCell;
 use crate::sync::Cell;
+use crate::time::Tempo;
+use crate::ui::Colour;
 use crate::ui::Point;
 use crate::ui::Rectangle;
 use crate::ui::ThemeColour;
@@ -31,12 +40,17 @@ use arcstr::ArcStr;
 use arcstr::literal;
 use derive_more::Debug;
 use enumset::EnumSet;
+use non_zero::non_zero;
 use serde::Deserialize;
+use std::fmt::Display;
+use std::num::NonZeroU8;
 use std::sync::Arc;
 use std::sync::LazyLock;
 
 /// The label for buttons that acknowledge a message.
 const ACKNOWLEDGE: ArcStr = literal!("ok");
+/// The label for buttons that remove a change point.
+const REMOVE_CHANGE: ArcStr = literal!("remove change");
 
 // TODO: keyboard navigation of popups
 /// A specification for a popup window.
@@ -47,6 +61,20 @@ const ACKNOWLEDGE: ArcStr = literal!("ok");
 pub enum Specification {
     /// A file selector for importing an audio file.
     AudioImporter,
+    /// A window for choosing the colour of a clip.
+    #[serde(skip)]
+    ClipColourPicker {
+        /// The clip.
+        clip: clip::Path,
+    },
+    /// A window for renaming a clip.
+    #[serde(skip)]
+    ClipRenamer {
+        /// The clip.
+        clip: clip::Path,
+        /// The current name of the clip.
+        name: ArcStr,
+    },
     /// An error message.
     #[serde(skip)]
     Error(Arc<anyhow::Error>),
@@ -56,10 +84,40 @@ pub enum Specification {
         /// The current key.
         key: Key,
     },
+    /// A window for renaming a marker.
+    #[serde(skip)]
+    MarkerRenamer {
+        /// The position of the marker.
+        at: Instant,
+        /// The current name of the marker.
+        name: ArcStr,
+    },
     /// A file selector for opening a project.
     ProjectOpener,
     /// A file selector for selecting the save location.
     SaveLocationPicker,
+    /// A window for selecting a tempo.
+    #[serde(skip)]
+    TempoSelector {
+        /// The current tempo.
+        tempo: Tempo,
+        /// How the current tempo is approached.
+        ramp: Option<Ramp>,
+    },
+    /// A window for selecting a time signature.
+    #[serde(skip)]
+    TimeSignatureSelector {
+        /// The current time signature.
+        time_signature: TimeSignature,
+    },
+    /// A window for renaming a track.
+    #[serde(skip)]
+    TrackRenamer {
+        /// The track.
+        track: Id<Track>,
+        /// The current name of the track.
+        name: ArcStr,
+    },
 }
 
 impl Specification {
@@ -67,38 +125,83 @@ impl Specification {
     #[must_use]
     pub const fn title(&self) -> ArcStr {
         const AUDIO_IMPORTER_TITLE: ArcStr = literal!("import audio");
+        const CLIP_COLOUR_PICKER_TITLE: ArcStr = literal!("choose clip colour");
+        const CLIP_RENAMER_TITLE: ArcStr = literal!("rename clip");
         const ERROR_TITLE: ArcStr = literal!("error");
         const KEY_SELECTOR_TITLE: ArcStr = literal!("select key");
+        const MARKER_RENAMER_TITLE: ArcStr = literal!("rename marker");
         const SAVE_LOCATION_PICKER_TITLE: ArcStr = literal!("save project as");
         const PROJECT_OPENER_TITLE: ArcStr = literal!("open project");
+        const TEMPO_SELECTOR_TITLE: ArcStr = literal!("select tempo");
+        const TIME_SIGNATURE_SELECTOR_TITLE: ArcStr = literal!("select time signature");
+        const TRACK_RENAMER_TITLE: ArcStr = literal!("rename track");
 
         match self {
             Specification::AudioImporter => AUDIO_IMPORTER_TITLE,
+            Specification::ClipColourPicker { .. } => CLIP_COLOUR_PICKER_TITLE,
+            Specification::ClipRenamer { .. } => CLIP_RENAMER_TITLE,
             Specification::Error { .. } => ERROR_TITLE,
             Specification::KeySelector { .. } => KEY_SELECTOR_TITLE,
+            Specification::MarkerRenamer { .. } => MARKER_RENAMER_TITLE,
             Specification::SaveLocationPicker => SAVE_LOCATION_PICKER_TITLE,
             Specification::ProjectOpener => PROJECT_OPENER_TITLE,
+            Specification::TempoSelector { .. } => TEMPO_SELECTOR_TITLE,
+            Specification::TimeSignatureSelector { .. } => TIME_SIGNATURE_SELECTOR_TITLE,
+            Specification::TrackRenamer { .. } => TRACK_RENAMER_TITLE,
         }
     }
 
     /// Generate and id for a popup following the specification.
     pub(super) fn generate_id(&self) -> Id<Popup> {
         static AUDIO_FILE_IMPORTER: LazyLock<Id<Popup>> = LazyLock::new(Id::generate);
+        static CLIP_COLOUR_PICKER: LazyLock<Id<Popup>> = LazyLock::new(Id::generate);
+        static CLIP_RENAMER: LazyLock<Id<Popup>> = LazyLock::new(Id::generate);
         static KEY_SELECTOR: LazyLock<Id<Popup>> = LazyLock::new(Id::generate);
+        static MARKER_RENAMER: LazyLock<Id<Popup>> = LazyLock::new(Id::generate);
         static SAVE_LOCATION_PICKER: LazyLock<Id<Popup>> = LazyLock::new(Id::generate);
         static PROJECT_OPENER: LazyLock<Id<Popup>> = LazyLock::new(Id::generate);
+        static TEMPO_SELECTOR: LazyLock<Id<Popup>> = LazyLock::new(Id::generate);
+        static TIME_SIGNATURE_SELECTOR: LazyLock<Id<Popup>> = LazyLock::new(Id::generate);
+        static TRACK_RENAMER: LazyLock<Id<Popup>> = LazyLock::new(Id::generate);
 
         match self {
             Specification::AudioImporter => *AUDIO_FILE_IMPORTER,
+            Specification::ClipColourPicker { .. } => *CLIP_COLOUR_PICKER,
+            Specification::ClipRenamer { .. } => *CLIP_RENAMER,
             Specification::Error(_) => Id::generate(),
             Specification::KeySelector { .. } => *KEY_SELECTOR,
+            Specification::MarkerRenamer { .. } => *MARKER_RENAMER,
             Specification::SaveLocationPicker => *SAVE_LOCATION_PICKER,
             Specification::ProjectOpener => *PROJECT_OPENER,
+            Specification::TempoSelector { .. } => *TEMPO_SELECTOR,
+            Specification::TimeSignatureSelector { .. } => *TIME_SIGNATURE_SELECTOR,
+            Specification::TrackRenamer { .. } => *TRACK_RENAMER,
+        }
+    }
+
+    /// Returns the initial text of the popup's text field, or `None` if it has no text field.
+    fn initial_text(&self) -> Option<ArcStr> {
+        match self {
+            Specification::ClipRenamer { name, .. }
+            | Specification::MarkerRenamer { name, .. }
+            | Specification::TrackRenamer { name, .. } => Some(name.clone()),
+            Specification::AudioImporter
+            | Specification::ClipColourPicker { .. }
+            | Specification::Error(_)
+            | Specification::KeySelector { .. }
+            | Specification::ProjectOpener
+            | Specification::SaveLocationPicker
+            | Specification::TempoSelector { .. }
+            | Specification::TimeSignatureSelector { .. } => None,
         }
     }
 
     /// Returns the popups [view](View) with a border and title.
-    fn view<Ui: UserInterface>(&self, id: Id<Popup>) -> View {
+    fn view<Ui: UserInterface>(
+        &self,
+        id: Id<Popup>,
+        text_field: Option<&Arc<ArcCell<str>>>,
+    ) -> View {
         let grab_edge = move |render_area: RenderArea| {
             let mouse_position = render_area.relative_mouse_position()?;
 
@@ -135,7 +238,7 @@ impl Specification {
         };
 
         let foreground = self
-            .inner_view(id)
+            .inner_view(id, text_field)
             .bordered_with_title(self.title())
             .grabbable(grab_edge)
             .on_click(OnClick::from(Action::CloseContextMenu));
@@ -144,11 +247,28 @@ impl Specification {
     }
 
     /// Returns the popups inner [view](View), with no border and title.
-    fn inner_view(&self, id: Id<Popup>) -> View {
+    fn inner_view(&self, id: Id<Popup>, text_field: Option<&Arc<ArcCell<str>>>) -> View {
         match self {
             Specification::AudioImporter => {
                 file::picker_in_popup(|file| Action::Edit(Edit::ImportAudio { file }), id)
             }
+            Specification::ClipColourPicker { clip } => {
+                let clip = *clip;
+
+                colour_picker(
+                    move |colour| Action::Edit(Edit::SetClipColour { clip, colour }),
+                    id,
+                )
+            }
+            Specification::ClipRenamer { clip, .. } => {
+                let clip = *clip;
+
+                renamer(
+                    text_field,
+                    move |name| Action::Edit(Edit::RenameClip { clip, name }),
+                    id,
+                )
+            }
             Specification::Error(error) => {
                 let acknowledge_button = ACKNOWLEDGE.centred().bordered();
 
@@ -160,59 +280,31 @@ impl Specification {
                     ],
                 )
             }
-            Specification::KeySelector { key } => {
-                fn confirm_action(
-                    tonic: Arc<Cell<PitchClass>>,
-                    sign: Arc<Cell<Sign>>,
-                    intervals: Arc<Cell<EnumSet<NonUnisonSimpleInterval>>>,
-                ) -> impl Fn() -> Action {
-                    move || {
-                        Action::Edit(Edit::SetKey(Key {
-                            tonic: tonic.get(),
-                            sign: sign.get(),
-                            intervals: intervals.get(),
-                        }))
-                    }
-                }
-
-                fn pitch_class_formatter(
-                    sign: Arc<Cell<Sign>>,
-                ) -> impl Fn(&PitchClass) -> ArcStr + Clone {
-                    move |class| class.name(sign.get())
-                }
-
-                let tonic = Arc::new(Cell::new(key.tonic));
-                let sign = Arc::new(Cell::new(key.sign));
-                let intervals = Arc::new(Cell::new(key.intervals));
-
-                let sign_selector = single::selector(&sign, Axis::X);
-                let interval_selector = multi::selector(&intervals, Axis::X);
-
-                let tonic_selector = single::selector_with_formatter(
-                    &tonic,
-                    Axis::X,
-                    pitch_class_formatter(Arc::clone(&sign)),
-                );
-
-                let buttons = View::minimal_stack(
-                    Axis::X,
-                    vec![
-                        CANCEL.centred().bordered().terminating(id),
-                        View::standard_button(
-                            CONFIRM,
-                            OnClick::action(confirm_action(tonic, sign, intervals)),
-                        )
-                        .terminating(id),
-                    ],
-                );
+            Specification::KeySelector { key } => key_selector(*key, id),
+            Specification::MarkerRenamer { at, .. } => {
+                let at = *at;
 
-                View::minimal_stack(
-                    Axis::Y,
-                    vec![tonic_selector, sign_selector, interval_selector, buttons],
+                renamer(
+                    text_field,
+                    move |name| Action::Edit(Edit::RenameMarker { at, name }),
+                    id,
                 )
             }
             Specification::SaveLocationPicker => file::picker_in_popup(Action::SaveAs, id),
             Specification::ProjectOpener => file::picker_in_popup(Action::OpenProject, id),
+            Specification::TempoSelector { tempo, ramp } => tempo_selector(*tempo, *ramp, id),
+            Specification::TimeSignatureSelector { time_signature } => {
+                time_signature_selector(*time_signature, id)
+            }
+            Specification::TrackRenamer { track, .. } => {
+                let track = *track;
+
+                renamer(
+                    text_field,
+                    move |name| Action::Edit(Edit::RenameTrack { track, name }),
+                    id,
+                )
+            }
         }
         .on_click(OnClick::from(Action::CloseContextMenu))
     }
@@ -221,7 +313,11 @@ impl Specification {
     pub(crate) fn instantiate<Ui: UserInterface>(&self, id: Id<Popup>, ui: &Ui) -> Popup {
         let ui_size = ui.size();
 
-        let view = Arc::new(self.view::<Ui>(id));
+        let text_field = self
+            .initial_text()
+            .map(|text| Arc::new(ArcCell::new(Arc::from(text.as_str()))));
+
+        let view = Arc::new(self.view::<Ui>(id, text_field.as_ref()));
 
         let size = view.minimum_size::<Ui>(ui.render_area());
 
@@ -232,8 +328,247 @@ impl Specification {
 
         let area = Rectangle { position, size };
 
-        Popup::new(view, area)
+        Popup::new(view, area, text_field)
+    }
+}
+
+/// Returns a view of a key selector.
+fn key_selector(key: Key, id: Id<Popup>) -> View {
+    fn confirm_action(
+        tonic: Arc<Cell<PitchClass>>,
+        sign: Arc<Cell<Sign>>,
+        intervals: Arc<Cell<EnumSet<NonUnisonSimpleInterval>>>,
+    ) -> impl Fn() -> Action {
+        move || {
+            Action::Edit(Edit::SetKey(Key {
+                tonic: tonic.get(),
+                sign: sign.get(),
+                intervals: intervals.get(),
+            }))
+        }
+    }
+
+    fn pitch_class_formatter(sign: Arc<Cell<Sign>>) -> impl Fn(&PitchClass) -> ArcStr + Clone {
+        move |class| class.name(sign.get())
     }
+
+    let tonic = Arc::new(Cell::new(key.tonic));
+    let sign = Arc::new(Cell::new(key.sign));
+    let intervals = Arc::new(Cell::new(key.intervals));
+
+    let sign_selector = single::selector(&sign, Axis::X);
+    let interval_selector = multi::selector(&intervals, Axis::X);
+
+    let tonic_selector =
+        single::selector_with_formatter(&tonic, Axis::X, pitch_class_formatter(Arc::clone(&sign)));
+
+    let buttons = View::minimal_stack(
+        Axis::X,
+        vec![
+            CANCEL.centred().bordered().terminating(id),
+            View::standard_button(
+                CONFIRM,
+                OnClick::action(confirm_action(tonic, sign, intervals)),
+            )
+            .terminating(id),
+        ],
+    );
+
+    View::minimal_stack(
+        Axis::Y,
+        vec![tonic_selector, sign_selector, interval_selector, buttons],
+    )
+}
+
+/// Returns a view of a tempo selector.
+fn tempo_selector(tempo: Tempo, ramp: Option<Ramp>, id: Id<Popup>) -> View {
+    const INSTANT: ArcStr = literal!("instant");
+    const TEN: Ratio = Ratio::integer(10);
+    const TENTH: Ratio = Ratio::reciprocal_of(non_zero!(10));
+
+    let tempo = Arc::new(Cell::new(tempo));
+
+    let tempo_adjuster = adjuster(
+        &tempo,
+        [
+            (literal!("-10"), |tempo: Tempo| tempo.slower_by(TEN)),
+            (literal!("-1"), |tempo: Tempo| tempo.slower_by(Ratio::ONE)),
+            (literal!("-0.1"), |tempo: Tempo| tempo.slower_by(TENTH)),
+        ],
+        [
+            (literal!("+0.1"), |tempo: Tempo| tempo.faster_by(TENTH)),
+            (literal!("+1"), |tempo: Tempo| tempo.faster_by(Ratio::ONE)),
+            (literal!("+10"), |tempo: Tempo| tempo.faster_by(TEN)),
+        ],
+    );
+
+    let ramp = Arc::new(Cell::new(ramp));
+    let ramp_selector = single::selector_with_formatter(&ramp, Axis::X, |ramp: &Option<Ramp>| {
+        ramp.map_or(INSTANT, |ramp| ramp.to_arc_str())
+    });
+
+    let buttons = change_buttons(
+        Edit::RemoveTempoChange,
+        move || {
+            Action::Edit(Edit::SetTempo {
+                tempo: tempo.get(),
+                ramp: ramp.get(),
+            })
+        },
+        id,
+    );
+
+    View::minimal_stack(Axis::Y, vec![tempo_adjuster, ramp_selector, buttons])
+}
+
+/// Returns a view of a time signature selector.
+fn time_signature_selector(time_signature: TimeSignature, id: Id<Popup>) -> View {
+    let beats_per_measure = Arc::new(Cell::new(time_signature.beats_per_measure));
+    let beats_per_whole_note = Arc::new(Cell::new(time_signature.beats_per_whole_note));
+
+    let beats_per_measure_adjuster = adjuster(
+        &beats_per_measure,
+        [(literal!("-"), |number: NonZeroU8| offset(number, -1))],
+        [(literal!("+"), |number: NonZeroU8| offset(number, 1))],
+    );
+    let beats_per_whole_note_adjuster = adjuster(
+        &beats_per_whole_note,
+        [(literal!("-"), |number: NonZeroU8| offset(number, -1))],
+        [(literal!("+"), |number: NonZeroU8| offset(number, 1))],
+    );
+
+    let buttons = change_buttons(
+        Edit::RemoveTimeSignatureChange,
+        move || {
+            Action::Edit(Edit::SetTimeSignature(TimeSignature {
+                beats_per_measure: beats_per_measure.get(),
+                beats_per_whole_note: beats_per_whole_note.get(),
+            }))
+        },
+        id,
+    );
+
+    View::minimal_stack(
+        Axis::Y,
+        vec![
+            beats_per_measure_adjuster,
+            beats_per_whole_note_adjuster,
+            buttons,
+        ],
+    )
+}
+
+/// Labelled functions that adjust a value, one for each button of an [adjuster].
+type Adjustments<T, const N: usize> = [(ArcStr, fn(T) -> T); N];
+
+/// Returns a view of the value of a cell surrounded by buttons that modify it.
+fn adjuster<T, const N: usize>(
+    cell: &Arc<Cell<T>>,
+    decreasers: Adjustments<T, N>,
+    increasers: Adjustments<T, N>,
+) -> View
+where
+    T: Copy + Display + Send + Sync + 'static,
+{
+    let button = |(label, modify): (ArcStr, fn(T) -> T)| {
+        let cell = Arc::clone(cell);
+
+        View::standard_button(
+            label,
+            OnClick::new(move |_, _| {
+                cell.set(modify(cell.get()));
+            }),
+        )
+    };
+
+    let value = {
+        let cell = Arc::clone(cell);
+        View::reactive(move |_| cell.get().to_arc_str().centred())
+    };
+
+    View::balanced_stack(
+        Axis::X,
+        decreasers
+            .into_iter()
+            .map(button)
+            .chain([value])
+            .chain(increasers.into_iter().map(button)),
+    )
+}
+
+/// Returns the cancel, remove and confirm buttons of a popup for editing a change point.
+fn change_buttons<Confirm>(remove: Edit, confirm: Confirm, id: Id<Popup>) -> View
+where
+    Confirm: Fn() -> Action + Send + Sync + 'static,
+{
+    View::minimal_stack(
+        Axis::X,
+        vec![
+            CANCEL.centred().bordered().terminating(id),
+            View::standard_button(REMOVE_CHANGE, OnClick::from(remove)).terminating(id),
+            View::standard_button(CONFIRM, OnClick::action(confirm)).terminating(id),
+        ],
+    )
+}
+
+/// Returns a view of a text field with cancel and confirm buttons.
+///
+/// The text typed into the field is passed to `confirm` when the confirm button is clicked.
+fn renamer<Confirm>(text_field: Option<&Arc<ArcCell<str>>>, confirm: Confirm, id: Id<Popup>) -> View
+where
+    Confirm: Fn(ArcStr) -> Action + Send + Sync + 'static,
+{
+    let Some(text_field) = text_field else {
+        return View::Empty;
+    };
+
+    let field = {
+        let text_field = Arc::clone(text_field);
+        View::reactive(move |_| ArcStr::from(&*text_field.get()).aligned_to(Alignment::Left))
+            .bordered()
+    };
+
+    let text_field = Arc::clone(text_field);
+
+    let buttons = View::minimal_stack(
+        Axis::X,
+        vec![
+            CANCEL.centred().bordered().terminating(id),
+            View::standard_button(
+                CONFIRM,
+                OnClick::action(move || confirm(ArcStr::from(&*text_field.get()))),
+            )
+            .terminating(id),
+        ],
+    );
+
+    View::minimal_stack(Axis::Y, vec![field, buttons])
+}
+
+/// Returns a view of a swatch for every colour in the palette and a cancel button.
+fn colour_picker<Choose>(choose: Choose, id: Id<Popup>) -> View
+where
+    Choose: Fn(Colour) -> Action,
+{
+    let swatches = View::balanced_stack(
+        Axis::X,
+        Colour::PALETTE.map(|colour| {
+            View::Solid(ThemeColour::Custom(colour))
+                .bordered()
+                .on_click(OnClick::from(choose(colour)))
+                .terminating(id)
+        }),
+    );
+
+    View::minimal_stack(
+        Axis::Y,
+        vec![swatches, CANCEL.centred().bordered().terminating(id)],
+    )
+}
+
+/// Offsets a non-zero number, saturating it.
+fn offset(number: NonZeroU8, by: i8) -> NonZeroU8 {
+    NonZeroU8::new(number.get().saturating_add_signed(by)).unwrap_or(NonZeroU8::MIN)
 }
 
 impl<E: Into<anyhow::Error>> From<E> for Specification {
@@ -4,15 +4,20 @@ use crate::Project;
 use crate::UserInterface;
 use crate::View;
 use crate::app::Action;
+use crate::audio::Gain;
+use crate::audio::Metronome;
 use crate::audio::Player;
 use crate::metre::Instant;
 use crate::popup::Specification;
+use crate::project::track::OverlapPolicy;
 use crate::string::ToArcStr as _;
 use crate::view::Axis;
 use crate::view::OnClick;
 use crate::view::ToText as _;
 use arcstr::ArcStr;
 use arcstr::literal;
+use bon::builder;
+use enum_iterator::next_cycle;
 
 // TODO: add a symbol view instead of using chars
 // there is sadly no "single" variant
@@ -25,12 +30,22 @@ const PLAY: ArcStr = literal!(" \u{25B6} ");
 /// "BLACK CIRCLE FOR RECORD".
 const RECORD: ArcStr = literal!(" \u{23FA} ");
 
+/// The label for the button that decreases the gain of the metronome.
+const QUIETER: ArcStr = literal!("-");
+/// The label for the button that increases the gain of the metronome.
+const LOUDER: ArcStr = literal!("+");
+
+/// The number of decibels by which the metronome gain buttons change the gain.
+const GAIN_STEP: f32 = 1.0;
+
 /// The label for the button to activate edit mode.
 const EDIT: ArcStr = literal!("edit mode");
 /// The label for the button to export the track.
 const EXPORT: ArcStr = literal!("export");
 /// The label for the button to activate looping.
 const LOOP: ArcStr = literal!("loop");
+/// The label for the button to activate the metronome.
+const METRONOME: ArcStr = literal!("metronome");
 /// The label for the button to open the piano roll.
 const PIANO: ArcStr = literal!("piano roll");
 /// The label for the button to open the plugins pane.
@@ -39,12 +54,16 @@ const PLUGINS: ArcStr = literal!("plugins");
 const SETTINGS: ArcStr = literal!("settings");
 
 /// The bar att the top of the window.
+#[builder]
 pub(crate) fn bar<Ui: UserInterface>(
     project: &Project,
     cursor: Instant,
     player: Option<Player>,
     edit_mode: bool,
+    overlap_policy: OverlapPolicy,
     piano_roll_open: bool,
+    looping: bool,
+    metronome: Metronome,
 ) -> View {
     // --- BUTTONS ---
 
@@ -56,21 +75,32 @@ pub(crate) fn bar<Ui: UserInterface>(
         piano_roll_open,
     );
     let edit_mode_button = View::toggle(EDIT, OnClick::from(Action::ToggleEditMode), edit_mode);
+    let overlap_policy_button = View::standard_button(
+        arcstr::format!("overlaps: {overlap_policy}"),
+        OnClick::from(Action::SetOverlapPolicy(next_cycle(&overlap_policy))),
+    );
 
     let key_button = View::standard_button(
-        project.key.start.to_arc_str(),
+        project.key.get(cursor).to_arc_str(),
         OnClick::from(Action::OpenPopup(Specification::KeySelector {
             key: project.key.get(cursor),
         })),
     );
-    // TODO: add functionality
+    let time_signature = project.time_signature.get(cursor);
     let time_signature_button = View::standard_button(
-        project.time_signature.get(cursor).to_arc_str(),
-        OnClick::default(),
+        time_signature.to_arc_str(),
+        OnClick::from(Action::OpenPopup(Specification::TimeSignatureSelector {
+            time_signature,
+        })),
+    );
+    let tempo = project.tempo.get(cursor);
+    let tempo_button = View::standard_button(
+        tempo.to_arc_str(),
+        OnClick::from(Action::OpenPopup(Specification::TempoSelector {
+            tempo,
+            ramp: project.tempo.ramp(cursor),
+        })),
     );
-    // TODO: add functionality
-    let tempo_button =
-        View::standard_button(project.tempo.get(cursor).to_arc_str(), OnClick::default());
 
     let back_button =
         View::standard_button(BACK, OnClick::from(Action::MoveCursor(Instant::START)));
@@ -85,9 +115,7 @@ pub(crate) fn bar<Ui: UserInterface>(
     });
     // TODO: add functionality
     let record_button = View::standard_button(RECORD, OnClick::default());
-    // TODO: add functionality
-    let loop_button = View::standard_button(LOOP, OnClick::default());
-
+    let loop_button = View::toggle(LOOP, OnClick::from(Action::ToggleLooping), looping);
     // TODO: add functionality
     let loudness_metre = literal!("0 LUFS").centred().bordered();
 
@@ -100,7 +128,12 @@ pub(crate) fn bar<Ui: UserInterface>(
 
     let leftmost_buttons = View::minimal_stack(
         Axis::X,
-        [plugins_button, piano_roll_button, edit_mode_button],
+        [
+            plugins_button,
+            piano_roll_button,
+            edit_mode_button,
+            overlap_policy_button,
+        ],
     );
 
     let project_settings =
@@ -108,7 +141,10 @@ pub(crate) fn bar<Ui: UserInterface>(
 
     let left_playback_buttons = back_button;
 
-    let right_playback_buttons = View::minimal_stack(Axis::X, [record_button, loop_button]);
+    let right_playback_buttons = View::minimal_stack(
+        Axis::X,
+        [record_button, loop_button, metronome_buttons(metronome)],
+    );
 
     let rightmost_buttons = View::minimal_stack(Axis::X, [export_button, settings_button]);
 
@@ -129,3 +165,36 @@ pub(crate) fn bar<Ui: UserInterface>(
     ])
     .bordered_with_title_and_thickness(project.name.clone(), true)
 }
+
+/// The buttons that control the metronome.
+fn metronome_buttons(metronome: Metronome) -> View {
+    let metronome_button = View::toggle(
+        METRONOME,
+        OnClick::from(Action::ToggleMetronome),
+        metronome.is_enabled(),
+    );
+    let count_in_button = View::standard_button(
+        arcstr::format!("count-in: {}", metronome.count_in()),
+        OnClick::from(Action::ToggleCountIn),
+    );
+
+    let gain_with_offset = |offset: f32| Gain {
+        decibels: metronome.gain().decibels + offset,
+    };
+    let metronome_gain = View::minimal_stack(
+        Axis::X,
+        [
+            View::standard_button(
+                QUIETER,
+                OnClick::from(Action::SetMetronomeGain(gain_with_offset(-GAIN_STEP))),
+            ),
+            metronome.gain().to_arc_str().centred().bordered(),
+            View::standard_button(
+                LOUDER,
+                OnClick::from(Action::SetMetronomeGain(gain_with_offset(GAIN_STEP))),
+            ),
+        ],
+    );
+
+    View::minimal_stack(Axis::X, [metronome_button, metronome_gain, count_in_button])
+}
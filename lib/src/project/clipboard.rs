@@ -0,0 +1,103 @@
+//! Items pertaining to [`Clipboard`].
+
+use crate::Note;
+use crate::Project;
+use crate::metre::Duration;
+use crate::note::Pitch;
+use crate::project::Track;
+use crate::project::edit::Error;
+use crate::project::track::Clip;
+use crate::select::Selection;
+use mitsein::iter1::IteratorExt as _;
+use mitsein::vec1::Vec1;
+use std::collections::HashSet;
+
+/// Copies of items that can be pasted into a [project](Project).
+///
+/// The copies are copied again when pasted, so that every pasted item gets a fresh id.
+#[derive(Debug)]
+pub enum Clipboard {
+    /// Clips, along with their offsets from the start of the earliest one.
+    Clips(Vec1<(Duration, Clip)>),
+    /// Notes, along with their offsets from the start of the earliest one and their pitches.
+    Notes(Vec1<(Duration, Pitch, Note)>),
+    /// Tracks, in the order that they appeared in the project.
+    Tracks(Vec1<Track>),
+}
+
+impl Project {
+    /// Copies the selected notes, clips or tracks onto a clipboard.
+    ///
+    /// Notes take precedence over clips, which take precedence over tracks.
+    pub(crate) fn copy(&self, selection: &Selection) -> Result<Clipboard, Error> {
+        let mut selection = selection.clone();
+
+        if let Some(notes) = selection.take_notes() {
+            let notes: Vec<_> = notes
+                .into_iter()
+                .filter_map(|path| {
+                    let (clip_start, content) = self.content(path.clip)?;
+                    let (position, pitch, note) = content.as_notes()?.note(path.note)?;
+
+                    Some((
+                        clip_start + position,
+                        pitch,
+                        note.copy_with_duration(note.duration()),
+                    ))
+                })
+                .collect();
+
+            let first = notes
+                .iter()
+                .map(|(instant, _, _)| *instant)
+                .min()
+                .ok_or(Error::NoNoteSelected)?;
+
+            let notes = notes
+                .into_iter()
+                .map(|(instant, pitch, note)| (instant - first, pitch, note))
+                .try_collect1()
+                .map_err(|_empty| Error::NoNoteSelected)?;
+
+            return Ok(Clipboard::Notes(notes));
+        }
+
+        if let Some(clips) = selection.take_clips() {
+            let clips = clips
+                .into_iter()
+                .filter_map(|path| self.clip(path))
+                .map(|(start, clip)| (start, clip.copied()))
+                .collect::<Vec<_>>();
+
+            let first = clips
+                .iter()
+                .map(|(start, _)| *start)
+                .min()
+                .ok_or(Error::NoClipSelected)?;
+
+            let clips = clips
+                .into_iter()
+                .map(|(start, clip)| (start - first, clip))
+                .try_collect1()
+                .map_err(|_empty| Error::NoClipSelected)?;
+
+            return Ok(Clipboard::Clips(clips));
+        }
+
+        if let Some(tracks) = selection.take_tracks() {
+            let tracks: HashSet<_> = tracks.into_iter().collect();
+
+            let tracks = self
+                .tracks
+                .values()
+                .filter(|track| tracks.contains(&track.id()))
+                .map(Track::copied)
+                .try_collect1()
+                .map_err(|_empty| Error::NoTrackSelected)?;
+
+            return Ok(Clipboard::Tracks(tracks));
+        }
+
+        Err(Error::NothingSelected)
+    }
+}
@@ -4,36 +4,76 @@ use crate::Audio;
 use crate::Id;
 use crate::Note;
 use crate::Project;
+use crate::Ratio;
+use crate::Selectable;
+use crate::audio;
 use crate::audio::FixedLength;
 use crate::audio::ImportError;
+use crate::metre::Duration;
 use crate::metre::Instant;
 use crate::metre::NonZeroDuration;
-use crate::metre::NonZeroInstant;
+use crate::metre::NonZeroPeriod;
+use crate::metre::Ramp;
+use crate::metre::TimeSignature;
+use crate::metre::relative;
 use crate::note;
+use crate::note::Articulation;
+use crate::note::Interval;
 use crate::note::Key;
 use crate::note::Pitch;
+use crate::note::Placement;
+use crate::note::Transposition;
+use crate::note::Velocity;
+use crate::note::expression::Dimension;
+use crate::project::Clipboard;
+use crate::project::DEFAULT_MARKER_NAME;
 use crate::project::DEFAULT_NOTES_DURATION;
 use crate::project::HistoryEntry;
 use crate::project::Track;
 use crate::project::track::Clip;
+use crate::project::track::ClipInsertionError;
 use crate::project::track::ClipInsertionErrorKind;
+use crate::project::track::Mixing;
+use crate::project::track::OverlapPolicy;
+use crate::project::track::automation::Point;
+use crate::project::track::automation::Target;
 use crate::project::track::clip;
+use crate::project::track::clip::ContentError;
 use crate::select::Selection;
+use crate::time::Tempo;
+use crate::ui::Colour;
 use arcstr::ArcStr;
 use mitsein::hash_set1::HashSet1;
 use mitsein::iter1::IteratorExt as _;
+use mitsein::vec1::Vec1;
+use non_zero::non_zero;
 use serde::Deserialize;
+use std::cmp::min;
 use std::ffi::OsStr;
 use std::mem::replace;
+use std::mem::take;
+use std::num::NonZeroU64;
 use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 use thiserror::Error;
 
+/// The stem of file names for clips whose names cannot be used as one.
+const FALLBACK_FILE_STEM: &str = "audio";
+
 /// An edit to a [project](super::Project).
 #[derive(Clone, Debug, Deserialize)]
 #[remain::sorted]
 #[serde(rename_all = "snake_case")]
 pub enum Edit {
+    /// Inserts an empty drum clip into the selected track at the cursor.
+    #[serde(skip)]
+    AddDrums {
+        /// The duration of a step in the drum grid.
+        step_duration: NonZeroDuration,
+    },
+    /// Adds a marker at the cursor.
+    AddMarker,
     /// Adds a note to the selected clip.
     #[serde(skip)]
     AddNote {
@@ -48,6 +88,14 @@ pub enum Edit {
     AddNoteGroup,
     /// Adds an empty track.
     AddTrack,
+    /// Removes all points from an expression curve of a note.
+    #[serde(skip)]
+    ClearCurve {
+        /// The note.
+        note: note::Path,
+        /// The dimension of the curve.
+        dimension: Dimension,
+    },
     /// Deletes the selected item(s).
     Delete,
     /// Deletes some clips.
@@ -59,12 +107,29 @@ pub enum Edit {
     /// Deletes some tracks.
     #[serde(skip)]
     DeleteTracks(HashSet1<Id<Track>>),
+    /// Inserts a copy of each selected clip directly after it.
+    ///
+    /// If no clips are selected, the selected tracks are [duplicated](Edit::DuplicateTrack) instead.
+    Duplicate,
+    /// Inserts a copy of each selected track, and of its clips, directly below it.
+    DuplicateTrack,
     /// Imports an audio file into the selected track at the cursor.
     #[serde(skip)]
     ImportAudio {
         /// The path to the file.
         file: Arc<Path>,
     },
+    /// Joins the selected clips, which have to be adjacent clips on the same track, into one.
+    JoinClips,
+    /// Moves the audio of the selected audio clip into a file next to the project
+    /// and links the clip to that file.
+    LinkAudio,
+    /// Inserts a copy of the selected clip at the cursor, which is linked to the selected clip.
+    LinkClip,
+    /// Sets the loop region to span the selected notes or, if no notes are selected, the selected clips.
+    LoopSelection,
+    /// Detaches the selected clip from the clips it is linked to.
+    MakeUnique,
     /// Moves a clip.
     #[serde(skip)]
     MoveClip {
@@ -75,33 +140,298 @@ pub enum Edit {
         /// The position in `track` that the clip should be moved to.
         position: Instant,
     },
+    /// Moves the selected notes.
+    MoveNotes {
+        /// How far the notes are moved in time.
+        by_time: Duration,
+        /// Whether the notes are moved earlier, rather than later.
+        earlier: bool,
+        /// How far the notes are moved in pitch.
+        by_pitch: Interval,
+    },
+    /// Moves a track to another place in the track list.
+    #[serde(skip)]
+    MoveTrack {
+        /// The track.
+        track: Id<Track>,
+        /// The index in the track list that the track should be moved to.
+        to_index: usize,
+    },
+    /// Pastes copies of the items on a clipboard.
+    ///
+    /// Notes are pasted into the selected clip and clips into the selected track,
+    /// both at the cursor.
+    /// Tracks are appended to the project.
+    #[serde(skip)]
+    Paste(Arc<Clipboard>),
+    /// Moves the selected notes, or the notes of the selected clip, towards a quantisation grid.
+    ///
+    /// If any of the notes could not be moved, since they would overlap other notes
+    /// or end up outside their clip, none of them are moved and those notes become the selection.
+    QuantiseNotes {
+        /// The duration of a grid cell.
+        cell_duration: NonZeroDuration,
+        /// How far, in percent, the notes are moved towards the grid.
+        strength: u8,
+        /// How far, in percent of a grid cell, every other line of the grid is delayed.
+        swing: u8,
+        /// Whether the ends of the notes are quantised as well as their starts.
+        include_ends: bool,
+    },
+    /// Removes the loop region.
+    RemoveLoopRegion,
+    /// Removes a marker.
+    #[serde(skip)]
+    RemoveMarker {
+        /// The position of the marker.
+        at: Instant,
+    },
+    /// Removes the tempo change in effect at the cursor.
+    RemoveTempoChange,
+    /// Removes the time-signature change in effect at the cursor.
+    RemoveTimeSignatureChange,
+    /// Renames a clip.
+    #[serde(skip)]
+    RenameClip {
+        /// The clip.
+        clip: clip::Path,
+        /// The new name.
+        name: ArcStr,
+    },
+    /// Renames a marker.
+    #[serde(skip)]
+    RenameMarker {
+        /// The position of the marker.
+        at: Instant,
+        /// The new name.
+        name: ArcStr,
+    },
+    /// Renames a track.
+    #[serde(skip)]
+    RenameTrack {
+        /// The track.
+        track: Id<Track>,
+        /// The new name.
+        name: ArcStr,
+    },
+    /// Inserts copies of each selected clip after it, one after the other.
+    Repeat {
+        /// The number of copies.
+        count: NonZeroU64,
+    },
+    /// Changes the duration of a clip, moving its end.
+    ///
+    /// Notes after the new end are dropped and notes that cross it are truncated.
+    #[serde(skip)]
+    ResizeClip {
+        /// The clip.
+        clip: clip::Path,
+        /// The new duration.
+        duration: NonZeroDuration,
+    },
+    /// Changes the durations of the selected notes, keeping their starts in place.
+    ResizeNotes {
+        /// How much the durations are changed.
+        by: Duration,
+        /// Whether the notes are shortened, rather than lengthened.
+        shorten: bool,
+    },
+    /// Sets the articulation of a note.
+    #[serde(skip)]
+    SetArticulation {
+        /// The note.
+        note: note::Path,
+        /// The new articulation, or `None` if the note should be played normally.
+        articulation: Option<Articulation>,
+    },
+    /// Sets or removes a point in an automation lane of a track.
+    #[serde(skip)]
+    SetAutomationPoint {
+        /// The track.
+        track: Id<Track>,
+        /// The automated parameter.
+        target: Target,
+        /// The position of the point.
+        at: Instant,
+        /// The point, or `None` if the point should be removed.
+        ///
+        /// The value of the point is clamped to the range of the parameter.
+        point: Option<Point>,
+    },
+    /// Sets the colour of a clip.
+    #[serde(skip)]
+    SetClipColour {
+        /// The clip.
+        clip: clip::Path,
+        /// The new colour.
+        colour: Colour,
+    },
+    /// Sets or removes a hit in a drum clip.
+    #[serde(skip)]
+    SetDrumHit {
+        /// The drum clip.
+        clip: clip::Path,
+        /// The index of the lane.
+        lane: usize,
+        /// The index of the step.
+        step: u64,
+        /// The velocity of the hit, or `None` if the hit should be removed.
+        velocity: Option<Velocity>,
+    },
+    /// Sets or removes a point on an expression curve of a note.
+    #[serde(skip)]
+    SetExpressionPoint {
+        /// The note.
+        note: note::Path,
+        /// The dimension of the curve.
+        dimension: Dimension,
+        /// The position of the point, relative to the start of the note.
+        at: relative::Instant,
+        /// The value of the point, or `None` if the point should be removed.
+        ///
+        /// The value is clamped to the range of the dimension.
+        value: Option<i16>,
+    },
     /// Sets the key at the cursor.
     #[serde(skip)]
     SetKey(Key),
+    /// Sets the loop region.
+    #[serde(skip)]
+    SetLoopRegion {
+        /// The start of the region.
+        start: Instant,
+        /// The end of the region.
+        end: Instant,
+    },
+    /// Sets how a track is mixed.
+    #[serde(skip)]
+    SetMixing {
+        /// The track.
+        track: Id<Track>,
+        /// The new mixing settings.
+        mixing: Mixing,
+    },
+    /// Sets the tempo at the cursor.
+    #[serde(skip)]
+    SetTempo {
+        /// The new tempo.
+        tempo: Tempo,
+        /// How the new tempo is approached, or `None` if it is set instantly.
+        ramp: Option<Ramp>,
+    },
+    /// Sets the time signature at the start of the measure containing the cursor.
+    #[serde(skip)]
+    SetTimeSignature(TimeSignature),
+    /// Sets the colour of a track.
+    #[serde(skip)]
+    SetTrackColour {
+        /// The track.
+        track: Id<Track>,
+        /// The new colour.
+        colour: Colour,
+    },
+    /// Sets the velocity of a note.
+    #[serde(skip)]
+    SetVelocity {
+        /// The note.
+        note: note::Path,
+        /// The new velocity.
+        velocity: Velocity,
+    },
+    /// Splits the selected clip(s) at the cursor.
+    SplitClip,
+    /// Transposes the selected notes, or the notes of the selected clips.
+    ///
+    /// Diatonic transpositions use the key in effect at each note.
+    Transpose(Transposition),
+    /// Moves the start of a clip, keeping its end in place.
+    ///
+    /// The content of the clip keeps its position in the track,
+    /// so moving the start later removes the beginning of the clip.
+    #[serde(skip)]
+    TrimClipStart {
+        /// The clip.
+        clip: clip::Path,
+        /// The new start of the clip.
+        start: Instant,
+    },
 }
 
 /// A error when trying to perform an [edit](Edit).
 #[derive(Debug, Error)]
 #[remain::sorted]
 pub enum Error {
+    /// Tried quantising notes that were already quantised.
+    #[error("the notes are already quantised")]
+    AlreadyQuantised,
     /// Tried inserting a note outside the selected clip.
     #[error("{0}")]
     ClipInsertion(#[from] ClipInsertionErrorKind),
+    /// Tried setting a loop region that ends before, or where, it starts.
+    #[error("the loop region would be empty")]
+    EmptyLoopRegion,
+    /// Tried adding a marker where there already is one.
+    #[error("there already is a marker there")]
+    ExistingMarker,
+    /// Failed to write audio to a file.
+    #[error("failed to write the audio file: {0}")]
+    ExportAudio(#[from] hound::Error),
+    /// Tried moving the start of a clip where it cannot be moved.
+    #[error("the start of the clip cannot be moved there")]
+    ImmovableClipStart,
     /// Failed to import audio from a file.
     #[error("{0}")]
     ImportAudio(#[from] ImportError),
+    /// An edit failed and what it had already done could not all be undone.
+    #[error("{error} (and the edit could not be fully rolled back: {rollback})")]
+    IncompleteRollback {
+        /// Why the edit failed.
+        error: Box<Error>,
+        /// Why the edit could not be rolled back.
+        rollback: Box<Error>,
+    },
+    /// Tried splitting a clip at an instant that is not inside it.
+    #[error("the clip cannot be split there")]
+    InvalidSplitPoint,
+    /// There was no change, other than the initial value, to remove.
+    #[error("there is no change to remove")]
+    NoChangeToRemove,
+    /// The action required a selected clip to be at the cursor.
+    #[error("no selected clip is at the cursor")]
+    NoClipAtCursor,
     /// The action required a clip to be selected.
     #[error("no clip is selected")]
     NoClipSelected,
     /// The action required a note to be selected.
     #[error("no note is selected")]
     NoNoteSelected,
+    /// The clips to join were not two or more adjacent clips on the same track.
+    #[error("the clips are not adjacent")]
+    NonAdjacentClips,
+    /// The action required an embedded audio clip to be selected.
+    #[error("the selected clip is not an embedded audio clip")]
+    NonAudioClip,
+    /// The action required a drum clip to be selected.
+    #[error("the selected clip is not a drum clip")]
+    NonDrumClip,
     /// Unable to resolve a clip id.
     #[error("the clip does not exist")]
     NonExistentClip,
+    /// Unable to resolve a drum lane.
+    #[error("the drum lane does not exist")]
+    NonExistentLane,
+    /// Unable to resolve a marker position.
+    #[error("the marker does not exist")]
+    NonExistentMarker,
+    /// Unable to resolve a note id.
+    #[error("the note does not exist")]
+    NonExistentNote,
     /// Unable to resolve a track id.
     #[error("the track does not exist")]
     NonExistentTrack,
+    /// The action required a linked clip to be selected.
+    #[error("the selected clip is not linked")]
+    NonLinkedClip,
     /// The action required a note clip to be selected.
     #[error("the selected clip is not a note clip")]
     NonNoteCLip,
@@ -111,9 +441,51 @@ pub enum Error {
     /// Tried inserting a note outside the selected clip.
     #[error("{0}")]
     NoteInsertion(#[from] note::InsertionError),
+    /// Tried shortening a note to nothing.
+    #[error("the note would be too short")]
+    NoteTooShort,
     /// The action required something to be selected.
     #[error("nothing is selected")]
     NothingSelected,
+    /// There were no undone edits to redo.
+    #[error("there is nothing to redo")]
+    NothingToRedo,
+    /// There were no edits to undo.
+    #[error("there is nothing to undo")]
+    NothingToUndo,
+    /// Tried moving a note outside the MIDI range.
+    #[error("the pitch is outside the MIDI range")]
+    PitchOutOfRange,
+    /// Tried joining clips whose contents cannot be joined.
+    #[error("the clips cannot be joined")]
+    UnjoinableClips,
+    /// Some of the notes to move could not be moved,
+    /// since they would have left their clips or overlapped other notes.
+    #[error(
+        "{count} of the notes could not be moved without leaving their clips or overlapping other notes"
+    )]
+    UnmovableNotes {
+        /// The number of notes that could not be moved.
+        count: usize,
+    },
+    /// The action required the project to have been saved.
+    #[error("the project has not been saved")]
+    UnsavedProject,
+    /// Tried splitting a clip whose content cannot be split.
+    #[error("the clip cannot be split")]
+    UnsplittableClip,
+}
+
+impl From<ContentError> for Error {
+    fn from(error: ContentError) -> Self {
+        match error {
+            ContentError::ImmovableStart => Error::ImmovableClipStart,
+            ContentError::InvalidSplitPoint => Error::InvalidSplitPoint,
+            ContentError::NoteInsertion(error) => Error::NoteInsertion(error),
+            ContentError::Unjoinable => Error::UnjoinableClips,
+            ContentError::Unsplittable => Error::UnsplittableClip,
+        }
+    }
 }
 
 impl Project {
@@ -123,205 +495,2550 @@ impl Project {
             .ok_or(Error::NoTrackSelected)
     }
 
-    /// Returns a mutable reference to the last selected clip.
-    fn selected_clip(&mut self, selection: &Selection) -> Result<(Instant, &mut Clip), Error> {
-        self.clip_mut(selection.top_clip().ok_or(Error::NoClipSelected)?)
-            .ok_or(Error::NoClipSelected)
+    /// Returns a mutable reference to a note.
+    pub(super) fn note_mut(&mut self, path: note::Path) -> Result<&mut Note, Error> {
+        let (_, content) = self.content_mut(path.clip).ok_or(Error::NonExistentClip)?;
+
+        content
+            .as_notes_mut()
+            .ok_or(Error::NonNoteCLip)?
+            .note_mut(path.note)
+            .ok_or(Error::NonExistentNote)
     }
 
-    /// Performs an edit on the project.
-    #[expect(clippy::too_many_lines, reason = "`Edit` is a large enum")]
-    #[remain::check]
-    pub(crate) fn edit(
+    /// Makes room for a clip in a track according to an overlap policy.
+    ///
+    /// Returns the entries that record the changes made to other clips.
+    fn make_room(
         &mut self,
-        edit: Edit,
-        cursor: Instant,
-        selection: &mut Selection,
-    ) -> Result<HistoryEntry, Error> {
-        #[sorted]
-        match edit {
-            Edit::AddNote {
-                position,
-                pitch,
-                mut duration,
-            } => {
-                let track = selection.top_track().ok_or(Error::NoTrackSelected)?;
-                let (clip_start, clip) = self.selected_clip(selection)?;
+        track: Id<Track>,
+        position: Instant,
+        duration: NonZeroDuration,
+        overlap_policy: OverlapPolicy,
+    ) -> Result<Vec<HistoryEntry>, Error> {
+        let track_id = track;
+        let track = self.track_mut(track).ok_or(Error::NonExistentTrack)?;
 
-                if position < clip_start {
-                    let difference = clip_start - position;
-                    let max_duration = NonZeroDuration::from_duration(duration.get() - difference)
-                        .ok_or(Error::NoteInsertion(note::InsertionError::OutsideClip))?;
+        let end = position + duration.get();
 
-                    duration = max_duration;
+        match overlap_policy {
+            OverlapPolicy::Push => {
+                if let Some((_, clip)) = track.clip_around(position) {
+                    return Err(ClipInsertionErrorKind::Overlap { clip }.into());
                 }
 
-                let position = position.relative_to(clip_start);
-
-                let note = Note::new(duration);
-
-                let entry = HistoryEntry::InsertNote(note::Path::new(track, clip.id(), note.id()));
+                let Some((next_start, _)) = track.first_clip_in(position, end) else {
+                    return Ok(Vec::new());
+                };
 
-                clip.content_mut()
-                    .as_notes_mut()
-                    .ok_or(Error::NonNoteCLip)?
-                    .try_insert(position, pitch, note)?;
+                let entries = track
+                    .shift_clips(position, end - next_start)
+                    .into_iter()
+                    .map(|(clip, original_position)| HistoryEntry::MoveClip {
+                        original_track: track_id,
+                        original_position,
+                        new_path: clip::Path::new(track_id, clip),
+                    })
+                    .collect();
 
-                Ok(entry)
+                Ok(entries)
             }
-            Edit::AddNoteGroup => {
-                let track = self.selected_track(selection)?;
+            OverlapPolicy::Reject => Ok(Vec::new()),
+            OverlapPolicy::Trim => {
+                if let Some((_, clip)) = track.first_clip_in(position, end) {
+                    return Err(ClipInsertionErrorKind::Overlap { clip }.into());
+                }
 
-                let clip = Clip::empty_notes(DEFAULT_NOTES_DURATION);
+                let Some((start, clip)) = track.clip_around(position) else {
+                    return Ok(Vec::new());
+                };
 
-                let path = clip::Path::new(track.id(), clip.id());
+                let duration = NonZeroDuration::from_duration(position - start)
+                    .ok_or(ClipInsertionErrorKind::Overlap { clip })?;
 
-                selection.clear();
-                selection.push_clip(clip::Path::new(track.id(), clip.id()));
+                let (_, original) = track.clip(clip).ok_or(Error::NonExistentClip)?;
+                let cropped = original.cropped(duration)?;
 
-                track
-                    .try_insert_clip(cursor, clip)
+                let (start, original) = track.remove_clip(clip).ok_or(Error::NonExistentClip)?;
+
+                // This should be infallible since the position was just vacated.
+                let path = track
+                    .try_insert_clip(start, cropped)
                     .map_err(|error| error.kind)?;
 
-                Ok(HistoryEntry::InsertClip(path))
+                Ok(vec![
+                    HistoryEntry::DeleteClip {
+                        track: track_id,
+                        start,
+                        clip: original,
+                    },
+                    HistoryEntry::InsertClip(path),
+                ])
             }
-            Edit::AddTrack => {
-                let track = Track::new();
-                let id = track.id();
+        }
+    }
 
-                selection.clear();
-                selection.push_track(id);
+    /// Inserts a clip into a track, making room for it according to an overlap policy.
+    ///
+    /// If the clip cannot be inserted, the room that was made is given back.
+    fn insert_clip(
+        &mut self,
+        track: Id<Track>,
+        position: Instant,
+        clip: Clip,
+        overlap_policy: OverlapPolicy,
+    ) -> Result<HistoryEntry, Error> {
+        let side_effects = self.make_room(track, position, clip.duration(), overlap_policy)?;
 
-                self.tracks.insert(id, track);
+        let result = self
+            .track_mut(track)
+            .ok_or(Error::NonExistentTrack)
+            .and_then(|track| {
+                track
+                    .try_insert_clip(position, clip)
+                    .map_err(|error| error.kind.into())
+            });
 
-                Ok(HistoryEntry::AddTrack(id))
+        match result {
+            Ok(path) => Ok(HistoryEntry::InsertClip(path).preceded_by(side_effects)),
+            Err(error) => Err(self.roll_back(side_effects, error)),
+        }
+    }
+
+    /// Moves a clip to a position in a track.
+    ///
+    /// If the clip cannot be inserted, it is put back where it was.
+    pub(super) fn move_clip(
+        &mut self,
+        clip: clip::Path,
+        track: Id<Track>,
+        position: Instant,
+        overlap_policy: OverlapPolicy,
+    ) -> Result<HistoryEntry, Error> {
+        let original_track = clip.track;
+
+        let (original_position, clip) = self.remove_clip(clip).ok_or(Error::NonExistentClip)?;
+
+        let side_effects = match self.make_room(track, position, clip.duration(), overlap_policy) {
+            Ok(side_effects) => side_effects,
+            Err(error) => {
+                // Put back the clip into the original track.
+                // This should be infallible.
+                self.track_mut(original_track)
+                    .ok_or(Error::NonExistentTrack)?
+                    .try_insert_clip(original_position, clip)
+                    .map_err(|error| error.kind)?;
+
+                return Err(error);
             }
-            Edit::Delete => {
-                let action = if let Some(notes) = selection.take_notes() {
-                    Edit::DeleteNotes(notes)
-                } else if let Some(clips) = selection.take_clips() {
-                    Edit::DeleteClips(clips)
-                } else if let Some(tracks) = selection.take_tracks() {
-                    Edit::DeleteTracks(tracks)
-                } else {
-                    return Err(Error::NothingSelected);
-                };
+        };
+
+        let Some(track) = self.track_mut(track) else {
+            return Err(Error::NonExistentTrack);
+        };
 
-                self.edit(action, cursor, selection)
+        match track.try_insert_clip(position, clip) {
+            Ok(new_path) => Ok(HistoryEntry::MoveClip {
+                original_track,
+                original_position,
+                new_path,
             }
-            Edit::DeleteClips(clips) => clips
-                .into_iter()
-                .filter_map(|path| {
-                    let track = self.track_mut(path.track)?;
-                    let (start, clip) = track.remove_clip(path.clip)?;
+            .preceded_by(side_effects)),
+            Err(ClipInsertionError { clip, kind }) => {
+                let error = self.roll_back(side_effects, kind.into());
 
-                    Some(HistoryEntry::DeleteClip { start, clip })
-                })
-                .try_collect1()
-                .map_err(|_empty| Error::NoClipSelected),
-            Edit::DeleteNotes(notes) => notes
-                .into_iter()
-                .filter_map(|path| {
-                    let (_, clip) = self.clip_mut(path.clip)?;
+                // Put back the clip into the original track.
+                // This should be infallible.
+                self.track_mut(original_track)
+                    .ok_or(Error::NonExistentTrack)?
+                    .try_insert_clip(original_position, *clip)
+                    .map_err(|error| error.kind)?;
+
+                Err(error)
+            }
+        }
+    }
 
-                    let (instant, pitch, note) =
-                        clip.content_mut().as_notes_mut()?.remove(path.note)?;
+    /// Moves notes towards a quantisation grid.
+    ///
+    /// See [`Edit::QuantiseNotes`].
+    fn quantise_notes(
+        &mut self,
+        selection: &mut Selection,
+        cell_duration: NonZeroDuration,
+        strength: Ratio,
+        swing: Ratio,
+        include_ends: bool,
+    ) -> Result<HistoryEntry, Error> {
+        let mut notes: Vec<_> = if let Some(notes) = selection.clone().take_notes() {
+            notes.into_iter().collect()
+        } else {
+            let clip = selection.top_clip().ok_or(Error::NoNoteSelected)?;
+            let (_, content) = self.content(clip).ok_or(Error::NonExistentClip)?;
 
-                    Some(HistoryEntry::DeleteNote {
-                        instant,
-                        pitch,
-                        note,
-                    })
+            content
+                .as_notes()
+                .ok_or(Error::NonNoteCLip)?
+                .notes()
+                .map(|(_, _, note)| note::Path {
+                    clip,
+                    note: note.id(),
                 })
-                .try_collect1()
-                .map_err(|_empty| Error::NoNoteSelected),
-            Edit::DeleteTracks(tracks) => tracks
-                .into_iter()
-                .filter_map(|track| {
-                    let index = self.tracks.get_index_of(&track)?;
-                    let track = self.tracks.shift_remove(&track)?;
+                .collect()
+        };
 
-                    Some(HistoryEntry::DeleteTrack { index, track })
-                })
-                .try_collect1()
-                .map_err(|_empty| Error::NoTrackSelected),
-            Edit::ImportAudio { file } => {
-                let time_context = self.time_context();
+        // The notes are quantised in order, so that the result does not depend on the selection.
+        notes.sort_by_key(|path| {
+            let (clip_start, content) = self.content(path.clip)?;
+            let (position, pitch, _) = content.as_notes()?.note(path.note)?;
 
-                let track = self.selected_track(selection)?;
+            Some((clip_start + position, pitch))
+        });
 
-                let audio = Audio::read_from_file(&file)?;
+        let time_signature = self.time_signature.clone();
+        let quantised = |instant: Instant| {
+            let line = time_signature.nearest_grid_line(instant, cell_duration, swing);
+            moved_towards(instant, line, strength)
+        };
 
-                let audio = FixedLength::from_audio(audio, cursor, &time_context);
+        let mut entries = Vec::new();
+        let mut unmoved = Vec::new();
 
-                let name = file
-                    .file_stem()
-                    .map(OsStr::to_string_lossy)
-                    .map(ArcStr::from)
-                    .unwrap_or_default();
+        for path in notes {
+            let Some((clip_start, content)) = self.content_mut(path.clip) else {
+                continue;
+            };
+            let Some(group) = content.as_notes_mut() else {
+                continue;
+            };
+            let Some((position, pitch, note)) = group.note(path.note) else {
+                continue;
+            };
 
-                let clip = Clip::from_audio(name, audio);
+            let from = Placement {
+                position,
+                pitch,
+                duration: note.duration(),
+            };
 
-                let entry = HistoryEntry::InsertClip(clip::Path::new(track.id(), clip.id()));
+            let start = clip_start + position;
+            let end = start + note.duration().get();
 
-                track
-                    .try_insert_clip(cursor, clip)
-                    .map_err(|error| error.kind)?;
+            let new_start = quantised(start);
+            let new_end = if include_ends {
+                quantised(end)
+            } else {
+                new_start + note.duration().get()
+            };
 
-                Ok(entry)
+            let to = Placement {
+                position: new_start.relative_to(clip_start),
+                pitch,
+                duration: NonZeroDuration::from_duration(new_end - new_start)
+                    .unwrap_or(from.duration),
+            };
+
+            if to == from {
+                continue;
             }
-            Edit::MoveClip {
-                clip,
-                track,
-                position,
-            } => {
-                let original_track = clip.track;
 
-                let (original_position, clip) =
-                    self.remove_clip(clip).ok_or(Error::NonExistentClip)?;
+            if new_start < clip_start {
+                unmoved.push(path);
+                continue;
+            }
 
-                let Some(track) = self.track_mut(track) else {
-                    // Put back the clip into the original track.
-                    // This should be infallible.
-                    self.track_mut(original_track)
-                        .ok_or(Error::NonExistentTrack)?
-                        .try_insert_clip(original_position, clip)
-                        .map_err(|error| error.kind)?;
+            match group.place(path.note, to) {
+                Ok(from) => entries.push(HistoryEntry::MoveNote {
+                    note: path,
+                    to,
+                    from,
+                }),
+                Err(_) => unmoved.push(path),
+            }
+        }
 
-                    return Err(Error::NonExistentTrack);
-                };
+        if !unmoved.is_empty() {
+            let error = Error::UnmovableNotes {
+                count: unmoved.len(),
+            };
 
-                match track.try_insert_clip(position, clip) {
-                    Ok(new_path) => Ok(HistoryEntry::MoveClip {
-                        original_track,
-                        original_position,
-                        new_path,
-                    }),
-                    Err(error) => {
-                        // Put back the clip into the original track.
-                        // This should be infallible.
-                        self.track_mut(original_track)
-                            .ok_or(Error::NonExistentTrack)?
-                            .try_insert_clip(original_position, *error.clip)
-                            .map_err(|error| error.kind)?;
+            // The notes that could not be moved are selected, so that the user can find them.
+            selection.clear();
 
-                        Err(error.kind.into())
-                    }
-                }
+            for path in unmoved {
+                selection.push(Selectable::Note(path));
             }
-            Edit::SetKey(key) => {
-                let old = if let Some(position) = NonZeroInstant::from_instant(cursor) {
-                    self.key.changes.insert(position, key)
-                } else {
-                    Some(replace(&mut self.key.start, key))
-                };
 
-                Ok(HistoryEntry::SetKey {
-                    at: cursor,
-                    to: key,
-                    from: old,
-                })
+            // The notes are either all quantised or not at all.
+            return Err(self.roll_back(entries, error));
+        }
+
+        entries
+            .into_iter()
+            .try_collect1()
+            .map_err(|_empty| Error::AlreadyQuantised)
+    }
+
+    /// Inserts a copy of each selected track directly below it.
+    ///
+    /// The copies become the selection.
+    fn duplicate_tracks(&mut self, selection: &mut Selection) -> Result<HistoryEntry, Error> {
+        let tracks = selection
+            .clone()
+            .take_tracks()
+            .ok_or(Error::NoTrackSelected)?;
+
+        let mut indices: Vec<_> = tracks
+            .into_iter()
+            .filter_map(|track| self.tracks.get_index_of(&track))
+            .collect();
+        indices.sort_unstable();
+
+        // The copies are inserted from the bottom up, so that the indices of the others stay valid.
+        let copies = indices
+            .into_iter()
+            .rev()
+            .map(|index| {
+                let (_, track) = self
+                    .tracks
+                    .get_index(index)
+                    .ok_or(Error::NonExistentTrack)?;
+
+                Ok((index, track.copied()))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        selection.clear();
+
+        copies
+            .into_iter()
+            .map(|(index, copy)| {
+                let id = copy.id();
+
+                self.tracks.shift_insert(index.saturating_add(1), id, copy);
+                selection.push_track(id);
+
+                HistoryEntry::AddTrack(id)
+            })
+            .try_collect1()
+            .map_err(|_empty| Error::NoTrackSelected)
+    }
+
+    /// Inserts `count` copies of each selected clip after it, one after the other.
+    ///
+    /// The copies become the selection.
+    fn repeat_clips(
+        &mut self,
+        count: NonZeroU64,
+        selection: &mut Selection,
+    ) -> Result<HistoryEntry, Error> {
+        let clips = selection
+            .clone()
+            .take_clips()
+            .ok_or(Error::NoClipSelected)?;
+
+        let mut entries = Vec::new();
+        let mut copies = Vec::new();
+
+        let result: Result<(), Error> = clips.into_iter().try_for_each(|path| {
+            let track = self.track_mut(path.track).ok_or(Error::NonExistentTrack)?;
+            let (mut position, clip) = track.clip(path.clip).ok_or(Error::NonExistentClip)?;
+            let duration = clip.duration();
+
+            for _ in 0..count.get() {
+                position += duration.get();
+
+                let (_, clip) = track.clip(path.clip).ok_or(Error::NonExistentClip)?;
+                let copy = clip.copied();
+
+                let copy_path = track
+                    .try_insert_clip(position, copy)
+                    .map_err(|error| error.kind)?;
+
+                entries.push(HistoryEntry::InsertClip(copy_path));
+                copies.push(copy_path);
             }
+
+            Ok(())
+        });
+
+        if let Err(error) = result {
+            // The copies that were inserted are removed again.
+            return Err(self.roll_back(entries, error));
+        }
+
+        selection.clear();
+
+        for copy in copies {
+            selection.push_clip(copy);
         }
+
+        entries
+            .into_iter()
+            .try_collect1()
+            .map_err(|_empty| Error::NoClipSelected)
+    }
+
+    /// Changes the duration of a clip.
+    fn resize_clip(
+        &mut self,
+        clip: clip::Path,
+        duration: NonZeroDuration,
+    ) -> Result<HistoryEntry, Error> {
+        let track = self.track_mut(clip.track).ok_or(Error::NonExistentTrack)?;
+        let (start, original) = track.clip(clip.clip).ok_or(Error::NonExistentClip)?;
+
+        let end = start + original.duration().get();
+        let new_end = start + duration.get();
+
+        if end < new_end
+            && let Some((_, overlapped)) = track.first_clip_in(end, new_end)
+        {
+            return Err(ClipInsertionErrorKind::Overlap { clip: overlapped }.into());
+        }
+
+        let content = original.content().cropped(duration)?;
+
+        let (_, clip_reference) = track.clip_mut(clip.clip).ok_or(Error::NonExistentClip)?;
+        let content = replace(clip_reference.content_mut(), content);
+
+        Ok(HistoryEntry::ReplaceContent { clip, content })
+    }
+
+    /// Returns the start of the clip containing a note and the placement of the note in it.
+    fn placement(&self, note: note::Path) -> Result<(Instant, Placement), Error> {
+        let (clip_start, content) = self.content(note.clip).ok_or(Error::NonExistentClip)?;
+        let (position, pitch, note) = content
+            .as_notes()
+            .ok_or(Error::NonNoteCLip)?
+            .note(note.note)
+            .ok_or(Error::NonExistentNote)?;
+
+        let placement = Placement {
+            position,
+            pitch,
+            duration: note.duration(),
+        };
+
+        Ok((clip_start, placement))
+    }
+
+    /// Returns the period spanned by the selected notes or, if no notes are selected, the selected clips.
+    fn selection_period(&self, selection: &Selection) -> Result<NonZeroPeriod, Error> {
+        let mut selection = selection.clone();
+
+        let periods = if let Some(notes) = selection.take_notes() {
+            notes
+                .into_iter()
+                .map(|note| {
+                    let (clip_start, placement) = self.placement(note)?;
+                    let start = clip_start + placement.position;
+
+                    Ok((start, start + placement.duration.get()))
+                })
+                .collect::<Result<Vec<_>, Error>>()?
+        } else if let Some(clips) = selection.take_clips() {
+            clips
+                .into_iter()
+                .map(|path| {
+                    let (start, clip) = self.clip(path).ok_or(Error::NonExistentClip)?;
+
+                    Ok((start, start + clip.duration().get()))
+                })
+                .collect::<Result<Vec<_>, Error>>()?
+        } else {
+            return Err(Error::NothingSelected);
+        };
+
+        let start = periods.iter().map(|(start, _)| *start).min();
+        let end = periods.iter().map(|(_, end)| *end).max();
+
+        start
+            .zip(end)
+            .and_then(|(start, end)| NonZeroPeriod::from_endpoints(start, end))
+            .ok_or(Error::EmptyLoopRegion)
+    }
+
+    /// Moves and resizes notes.
+    ///
+    /// Notes that are in the way of another one of the notes are moved first.
+    /// If the notes cannot all be placed, none of them are moved.
+    fn place_notes(
+        &mut self,
+        placements: Vec<(note::Path, Placement)>,
+    ) -> Result<HistoryEntry, Error> {
+        let mut entries = Vec::new();
+        let mut pending = placements;
+
+        while !pending.is_empty() {
+            let pending_count = pending.len();
+            let mut blocked = Vec::new();
+            let mut last_error = None;
+
+            for (note, to) in pending {
+                let result = self
+                    .content_mut(note.clip)
+                    .ok_or(Error::NonExistentClip)
+                    .and_then(|(_, content)| {
+                        let group = content.as_notes_mut().ok_or(Error::NonNoteCLip)?;
+                        group.place(note.note, to).map_err(Error::from)
+                    });
+
+                match result {
+                    Ok(from) => entries.push(HistoryEntry::MoveNote { note, to, from }),
+                    Err(error) => {
+                        blocked.push((note, to));
+                        last_error = Some(error);
+                    }
+                }
+            }
+
+            // No note could be placed, so the remaining notes never will be.
+            if let Some(error) = last_error
+                && blocked.len() == pending_count
+            {
+                return Err(self.roll_back(entries, error));
+            }
+
+            pending = blocked;
+        }
+
+        entries
+            .into_iter()
+            .try_collect1()
+            .map_err(|_empty| Error::NoNoteSelected)
+    }
+
+    /// Moves the selected notes.
+    ///
+    /// See [`Edit::MoveNotes`].
+    fn move_notes(
+        &mut self,
+        by_time: Duration,
+        earlier: bool,
+        by_pitch: Interval,
+        selection: &Selection,
+    ) -> Result<HistoryEntry, Error> {
+        let notes = selection
+            .clone()
+            .take_notes()
+            .ok_or(Error::NoNoteSelected)?;
+
+        let placements = notes
+            .into_iter()
+            .map(|path| {
+                let (_, from) = self.placement(path)?;
+
+                let position = if earlier {
+                    if from.position.since_start < by_time {
+                        return Err(note::InsertionError::OutsideClip.into());
+                    }
+
+                    relative::Instant {
+                        since_start: from.position.since_start - by_time,
+                    }
+                } else {
+                    from.position + by_time
+                };
+
+                let to = Placement {
+                    position,
+                    pitch: from
+                        .pitch
+                        .checked_add(by_pitch)
+                        .ok_or(Error::PitchOutOfRange)?,
+                    duration: from.duration,
+                };
+
+                Ok((path, to))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        self.place_notes(placements)
+    }
+
+    /// Changes the durations of the selected notes.
+    ///
+    /// See [`Edit::ResizeNotes`].
+    fn resize_notes(
+        &mut self,
+        by: Duration,
+        shorten: bool,
+        selection: &Selection,
+    ) -> Result<HistoryEntry, Error> {
+        let notes = selection
+            .clone()
+            .take_notes()
+            .ok_or(Error::NoNoteSelected)?;
+
+        let placements = notes
+            .into_iter()
+            .map(|path| {
+                let (_, from) = self.placement(path)?;
+
+                let duration = if shorten {
+                    from.duration.get() - by
+                } else {
+                    from.duration.get() + by
+                };
+
+                let to = Placement {
+                    duration: NonZeroDuration::from_duration(duration)
+                        .ok_or(Error::NoteTooShort)?,
+                    ..from
+                };
+
+                Ok((path, to))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        self.place_notes(placements)
+    }
+
+    /// Transposes the selected notes, or the notes of the selected clips.
+    ///
+    /// See [`Edit::Transpose`].
+    fn transpose_notes(
+        &mut self,
+        transposition: Transposition,
+        selection: &Selection,
+    ) -> Result<HistoryEntry, Error> {
+        let notes: Vec<_> = if let Some(notes) = selection.clone().take_notes() {
+            notes.into_iter().collect()
+        } else {
+            let clips = selection
+                .clone()
+                .take_clips()
+                .ok_or(Error::NothingSelected)?;
+
+            clips
+                .into_iter()
+                .map(|clip| {
+                    let (_, content) = self.content(clip).ok_or(Error::NonExistentClip)?;
+                    let group = content.as_notes().ok_or(Error::NonNoteCLip)?;
+
+                    Ok(group
+                        .notes()
+                        .map(|(_, _, note)| note::Path {
+                            clip,
+                            note: note.id(),
+                        })
+                        .collect::<Vec<_>>())
+                })
+                .collect::<Result<Vec<_>, Error>>()?
+                .into_iter()
+                .flatten()
+                .collect()
+        };
+
+        // All pitches are checked before any note is moved.
+        let placements = notes
+            .into_iter()
+            .map(|path| {
+                let (clip_start, from) = self.placement(path)?;
+                let key = self.key.get(clip_start + from.position);
+
+                let to = Placement {
+                    pitch: transposition
+                        .transpose(from.pitch, key)
+                        .ok_or(Error::PitchOutOfRange)?,
+                    ..from
+                };
+
+                Ok((path, to))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        self.place_notes(placements)
+    }
+
+    /// Moves the start of a clip, keeping its end in place.
+    fn trim_clip_start(&mut self, clip: clip::Path, start: Instant) -> Result<HistoryEntry, Error> {
+        let time_context = self.time_context();
+
+        let track = self.track_mut(clip.track).ok_or(Error::NonExistentTrack)?;
+        let (original_position, original) = track.clip(clip.clip).ok_or(Error::NonExistentClip)?;
+
+        let end = original_position + original.duration().get();
+        let duration =
+            NonZeroDuration::from_duration(end - start).ok_or(Error::ImmovableClipStart)?;
+
+        if start < original_position
+            && let Some((_, overlapped)) = track
+                .clip_around(start)
+                .or_else(|| track.first_clip_in(start, original_position))
+        {
+            return Err(ClipInsertionErrorKind::Overlap { clip: overlapped }.into());
+        }
+
+        let content =
+            original
+                .content()
+                .with_start(original_position, start, duration, &time_context)?;
+
+        let (_, mut moved) = track.remove_clip(clip.clip).ok_or(Error::NonExistentClip)?;
+        let content = replace(moved.content_mut(), content);
+
+        // This should be infallible since there is room for the clip.
+        let new_path = track
+            .try_insert_clip(start, moved)
+            .map_err(|error| error.kind)?;
+
+        let mut entries = Vec1::from_one(HistoryEntry::MoveClip {
+            original_track: clip.track,
+            original_position,
+            new_path,
+        });
+        entries.push(HistoryEntry::ReplaceContent {
+            clip: new_path,
+            content,
+        });
+
+        Ok(HistoryEntry::Cluster(entries))
+    }
+
+    /// Pastes copies of the items on a clipboard, which then become the selection.
+    ///
+    /// If any item cannot be pasted, nothing is.
+    fn paste(
+        &mut self,
+        clipboard: &Clipboard,
+        cursor: Instant,
+        selection: &mut Selection,
+        overlap_policy: OverlapPolicy,
+    ) -> Result<HistoryEntry, Error> {
+        let mut entries = Vec::new();
+        let mut pasted = Vec::new();
+
+        let result: Result<(), Error> = match clipboard {
+            Clipboard::Clips(clips) => {
+                let track = selection.top_track().ok_or(Error::NoTrackSelected)?;
+
+                clips.iter().try_for_each(|(offset, clip)| {
+                    let copy = clip.copied();
+                    let position = cursor + *offset;
+
+                    let side_effects =
+                        self.make_room(track, position, copy.duration(), overlap_policy)?;
+                    entries.extend(side_effects);
+
+                    let path = self
+                        .track_mut(track)
+                        .ok_or(Error::NonExistentTrack)?
+                        .try_insert_clip(position, copy)
+                        .map_err(|error| error.kind)?;
+
+                    entries.push(HistoryEntry::InsertClip(path));
+                    pasted.push(Selectable::Clip(path));
+
+                    Ok(())
+                })
+            }
+            Clipboard::Notes(notes) => {
+                let clip = selection.top_clip().ok_or(Error::NoClipSelected)?;
+                let (clip_start, content) = self.content_mut(clip).ok_or(Error::NoClipSelected)?;
+                let group = content.as_notes_mut().ok_or(Error::NonNoteCLip)?;
+
+                // Either all notes are pasted or none are.
+                notes.iter().try_for_each(|(offset, pitch, note)| {
+                    let position = cursor + *offset;
+
+                    if position < clip_start {
+                        return Err(Error::NoteInsertion(note::InsertionError::OutsideClip));
+                    }
+
+                    let copy = note.copy_with_duration(note.duration());
+                    let path = note::Path::new(clip.track, clip.clip, copy.id());
+
+                    group.try_insert(position.relative_to(clip_start), *pitch, copy)?;
+
+                    entries.push(HistoryEntry::InsertNote(path));
+                    pasted.push(Selectable::Note(path));
+
+                    Ok(())
+                })
+            }
+            Clipboard::Tracks(tracks) => tracks.iter().try_for_each(|track| {
+                let copy = track.copied();
+                let id = copy.id();
+
+                self.tracks.insert(id, copy);
+
+                entries.push(HistoryEntry::AddTrack(id));
+                pasted.push(Selectable::Track(id));
+
+                Ok(())
+            }),
+        };
+
+        if let Err(error) = result {
+            // The items that were pasted are removed again.
+            return Err(self.roll_back(entries, error));
+        }
+
+        selection.clear();
+
+        for item in pasted {
+            selection.push(item);
+        }
+
+        // Clipboards are never empty, and every item on them was pasted.
+        entries
+            .into_iter()
+            .try_collect1()
+            .map_err(|_empty| Error::NothingSelected)
+    }
+
+    /// Splits a clip at an instant.
+    ///
+    /// The first part keeps the id of the clip.
+    /// Returns the path to the second part along with the history entry.
+    fn split_clip(
+        &mut self,
+        clip: clip::Path,
+        at: Instant,
+    ) -> Result<(clip::Path, HistoryEntry), Error> {
+        let time_context = self.time_context();
+
+        let track = self.track_mut(clip.track).ok_or(Error::NonExistentTrack)?;
+        let (start, original) = track.clip(clip.clip).ok_or(Error::NonExistentClip)?;
+
+        let (first, second) = original.content().split_at(start, at, &time_context)?;
+        let second = original.with_content(second);
+
+        let (_, clip_reference) = track.clip_mut(clip.clip).ok_or(Error::NonExistentClip)?;
+        let content = replace(clip_reference.content_mut(), first);
+
+        // This should be infallible since the first part was shortened to make room.
+        let second_path = track
+            .try_insert_clip(at, second)
+            .map_err(|error| error.kind)?;
+
+        let entry = HistoryEntry::InsertClip(second_path)
+            .preceded_by(vec![HistoryEntry::ReplaceContent { clip, content }]);
+
+        Ok((second_path, entry))
+    }
+
+    /// Joins adjacent clips on the same track into one.
+    fn join_clips(
+        &mut self,
+        clips: HashSet1<clip::Path>,
+    ) -> Result<(clip::Path, HistoryEntry), Error> {
+        let time_context = self.time_context();
+
+        let mut clips: Vec<_> = clips.into_iter().collect();
+
+        let track_id = clips.first().ok_or(Error::NoClipSelected)?.track;
+
+        if clips.iter().any(|clip| clip.track != track_id) {
+            return Err(Error::NonAdjacentClips);
+        }
+
+        let track = self.track_mut(track_id).ok_or(Error::NonExistentTrack)?;
+
+        clips.sort_by_key(|clip| track.clip(clip.clip).map(|(start, _)| start));
+
+        let first = clips.first().ok_or(Error::NoClipSelected)?;
+        let (start, first) = track.clip(first.clip).ok_or(Error::NonExistentClip)?;
+
+        let mut end = start + first.duration().get();
+        let mut content = None;
+
+        for clip in clips.iter().skip(1) {
+            let (clip_start, clip) = track.clip(clip.clip).ok_or(Error::NonExistentClip)?;
+
+            if clip_start != end {
+                return Err(Error::NonAdjacentClips);
+            }
+
+            let previous = content.as_ref().unwrap_or(first.content());
+            content = Some(previous.joined(start, clip.content(), &time_context)?);
+
+            end = clip_start + clip.duration().get();
+        }
+
+        let content = content.ok_or(Error::NonAdjacentClips)?;
+        let joined = first.with_content(content);
+
+        let entries = clips
+            .iter()
+            .filter_map(|clip| {
+                let (start, clip) = track.remove_clip(clip.clip)?;
+
+                Some(HistoryEntry::DeleteClip {
+                    track: track_id,
+                    start,
+                    clip,
+                })
+            })
+            .collect();
+
+        // This should be infallible since the joined clips were just removed.
+        let path = track
+            .try_insert_clip(start, joined)
+            .map_err(|error| error.kind)?;
+
+        Ok((path, HistoryEntry::InsertClip(path).preceded_by(entries)))
+    }
+
+    /// Performs an edit on the project.
+    #[expect(clippy::too_many_lines, reason = "`Edit` is a large enum")]
+    #[remain::check]
+    pub(crate) fn edit(
+        &mut self,
+        edit: Edit,
+        cursor: Instant,
+        selection: &mut Selection,
+        overlap_policy: OverlapPolicy,
+    ) -> Result<HistoryEntry, Error> {
+        #[sorted]
+        match edit {
+            Edit::AddDrums { step_duration } => {
+                let track = self.selected_track(selection)?.id();
+
+                let clip = Clip::empty_drums(DEFAULT_NOTES_DURATION, step_duration);
+
+                let path = clip::Path::new(track, clip.id());
+
+                let entry = self.insert_clip(track, cursor, clip, overlap_policy)?;
+
+                selection.clear();
+                selection.push_clip(path);
+
+                Ok(entry)
+            }
+            Edit::AddMarker => {
+                if self.markers.contains_key(&cursor) {
+                    return Err(Error::ExistingMarker);
+                }
+
+                self.markers.insert(cursor, DEFAULT_MARKER_NAME);
+
+                Ok(HistoryEntry::SetMarker {
+                    at: cursor,
+                    to: Some(DEFAULT_MARKER_NAME),
+                    from: None,
+                })
+            }
+            Edit::AddNote {
+                position,
+                pitch,
+                mut duration,
+            } => {
+                let track = selection.top_track().ok_or(Error::NoTrackSelected)?;
+                let clip = selection.top_clip().ok_or(Error::NoClipSelected)?;
+                let (clip_start, content) = self.content_mut(clip).ok_or(Error::NoClipSelected)?;
+
+                if position < clip_start {
+                    let difference = clip_start - position;
+                    let max_duration = NonZeroDuration::from_duration(duration.get() - difference)
+                        .ok_or(Error::NoteInsertion(note::InsertionError::OutsideClip))?;
+
+                    duration = max_duration;
+                }
+
+                let position = position.relative_to(clip_start);
+
+                let note = Note::new(duration);
+
+                let entry = HistoryEntry::InsertNote(note::Path::new(track, clip.clip, note.id()));
+
+                content
+                    .as_notes_mut()
+                    .ok_or(Error::NonNoteCLip)?
+                    .try_insert(position, pitch, note)?;
+
+                Ok(entry)
+            }
+            Edit::AddNoteGroup => {
+                let track = self.selected_track(selection)?.id();
+
+                let clip = Clip::empty_notes(DEFAULT_NOTES_DURATION);
+
+                let path = clip::Path::new(track, clip.id());
+
+                let entry = self.insert_clip(track, cursor, clip, overlap_policy)?;
+
+                selection.clear();
+                selection.push_clip(path);
+
+                Ok(entry)
+            }
+            Edit::AddTrack => {
+                let track = Track::new();
+                let id = track.id();
+
+                selection.clear();
+                selection.push_track(id);
+
+                self.tracks.insert(id, track);
+
+                Ok(HistoryEntry::AddTrack(id))
+            }
+            Edit::ClearCurve { note, dimension } => {
+                let curve = take(self.note_mut(note)?.expression_mut().curve_mut(dimension));
+
+                Ok(HistoryEntry::ReplaceCurve {
+                    note,
+                    dimension,
+                    curve,
+                })
+            }
+            Edit::Delete => {
+                let action = if let Some(notes) = selection.take_notes() {
+                    Edit::DeleteNotes(notes)
+                } else if let Some(clips) = selection.take_clips() {
+                    Edit::DeleteClips(clips)
+                } else if let Some(tracks) = selection.take_tracks() {
+                    Edit::DeleteTracks(tracks)
+                } else {
+                    return Err(Error::NothingSelected);
+                };
+
+                self.edit(action, cursor, selection, overlap_policy)
+            }
+            Edit::DeleteClips(clips) => clips
+                .into_iter()
+                .filter_map(|path| {
+                    let track = self.track_mut(path.track)?;
+                    let (start, clip) = track.remove_clip(path.clip)?;
+
+                    Some(HistoryEntry::DeleteClip {
+                        track: path.track,
+                        start,
+                        clip,
+                    })
+                })
+                .try_collect1()
+                .map_err(|_empty| Error::NoClipSelected),
+            Edit::DeleteNotes(notes) => notes
+                .into_iter()
+                .filter_map(|path| {
+                    let (_, content) = self.content_mut(path.clip)?;
+
+                    let (instant, pitch, note) = content.as_notes_mut()?.remove(path.note)?;
+
+                    Some(HistoryEntry::DeleteNote {
+                        clip: path.clip,
+                        instant,
+                        pitch,
+                        note,
+                    })
+                })
+                .try_collect1()
+                .map_err(|_empty| Error::NoNoteSelected),
+            Edit::DeleteTracks(tracks) => tracks
+                .into_iter()
+                .filter_map(|track| {
+                    let index = self.tracks.get_index_of(&track)?;
+                    let track = self.tracks.shift_remove(&track)?;
+
+                    Some(HistoryEntry::DeleteTrack { index, track })
+                })
+                .try_collect1()
+                .map_err(|_empty| Error::NoTrackSelected),
+            Edit::Duplicate => {
+                if selection.clone().take_clips().is_some() {
+                    self.repeat_clips(NonZeroU64::MIN, selection)
+                } else {
+                    self.duplicate_tracks(selection)
+                }
+            }
+            Edit::DuplicateTrack => self.duplicate_tracks(selection),
+            Edit::ImportAudio { file } => {
+                let time_context = self.time_context();
+
+                let track = self.selected_track(selection)?.id();
+
+                let audio = Audio::read_from_file(&file)?;
+
+                let audio = FixedLength::from_audio(audio, cursor, &time_context);
+
+                let name = file
+                    .file_stem()
+                    .map(OsStr::to_string_lossy)
+                    .map(ArcStr::from)
+                    .unwrap_or_default();
+
+                let clip = Clip::from_audio(name, audio);
+
+                self.insert_clip(track, cursor, clip, overlap_policy)
+            }
+            Edit::JoinClips => {
+                let clips = selection.take_clips().ok_or(Error::NoClipSelected)?;
+
+                let (path, entry) = self.join_clips(clips)?;
+
+                selection.push_clip(path);
+
+                Ok(entry)
+            }
+            Edit::LinkAudio => {
+                let directory = self.directory.clone().ok_or(Error::UnsavedProject)?;
+
+                let path = selection.top_clip().ok_or(Error::NoClipSelected)?;
+                let (_, clip) = self.clip_mut(path).ok_or(Error::NonExistentClip)?;
+
+                let audio = clip.content().as_audio().ok_or(Error::NonAudioClip)?;
+
+                let file = unused_file_name(&directory, &file_stem(&clip.name()), "wav");
+
+                audio.audio.export(&directory.join(&file), &[])?;
+
+                let linked = audio::Linked::from_fixed_length(audio.clone(), file);
+
+                let content = replace(clip.content_mut(), clip::Content::LinkedAudio(linked));
+
+                Ok(HistoryEntry::ReplaceContent {
+                    clip: path,
+                    content,
+                })
+            }
+            Edit::LinkClip => {
+                let path = selection.top_clip().ok_or(Error::NoClipSelected)?;
+
+                let unused_link = self.unused_link();
+
+                let (_, clip) = self.clip_mut(path).ok_or(Error::NonExistentClip)?;
+
+                let link = clip.content().link();
+                let copy = clip.linked(link.unwrap_or(unused_link));
+                let copy_path = clip::Path::new(path.track, copy.id());
+                let duration = copy.duration();
+
+                // The content of the clip is moved into the linked contents, unless it already is.
+                let unlinked_content = link.is_none().then(|| {
+                    replace(
+                        clip.content_mut(),
+                        clip::Content::Linked {
+                            link: unused_link,
+                            duration,
+                        },
+                    )
+                });
+
+                let mut entries = Vec::new();
+
+                if let Some(content) = unlinked_content {
+                    self.linked_contents.insert(unused_link, content);
+
+                    entries.push(HistoryEntry::LinkContent {
+                        clip: path,
+                        link: unused_link,
+                    });
+                }
+
+                let result = self
+                    .make_room(path.track, cursor, duration, overlap_policy)
+                    .and_then(|side_effects| {
+                        entries.extend(side_effects);
+
+                        self.track_mut(path.track)
+                            .ok_or(Error::NonExistentTrack)?
+                            .try_insert_clip(cursor, copy)
+                            .map_err(|error| error.kind)?;
+
+                        Ok(())
+                    });
+
+                if let Err(error) = result {
+                    // The room that was made is given back and the clip is unlinked again.
+                    return Err(self.roll_back(entries, error));
+                }
+
+                Ok(HistoryEntry::InsertClip(copy_path).preceded_by(entries))
+            }
+            Edit::LoopSelection => {
+                let region = self.selection_period(selection)?;
+
+                let from = self.loop_region.replace(region);
+
+                Ok(HistoryEntry::SetLoopRegion {
+                    to: Some(region),
+                    from,
+                })
+            }
+            Edit::MakeUnique => {
+                let path = selection.top_clip().ok_or(Error::NoClipSelected)?;
+
+                let (_, clip) = self.clip(path).ok_or(Error::NonExistentClip)?;
+
+                let &clip::Content::Linked { link, duration } = clip.content() else {
+                    return Err(Error::NonLinkedClip);
+                };
+
+                let copy = self
+                    .linked_contents
+                    .get(&link)
+                    .ok_or(Error::NonExistentClip)?
+                    .cropped(duration)?;
+
+                let (_, clip) = self.clip_mut(path).ok_or(Error::NonExistentClip)?;
+
+                let content = replace(clip.content_mut(), copy);
+
+                Ok(HistoryEntry::ReplaceContent {
+                    clip: path,
+                    content,
+                })
+            }
+            Edit::MoveClip {
+                clip,
+                track,
+                position,
+            } => self.move_clip(clip, track, position, overlap_policy),
+            Edit::MoveNotes {
+                by_time,
+                earlier,
+                by_pitch,
+            } => self.move_notes(by_time, earlier, by_pitch, selection),
+            Edit::MoveTrack { track, to_index } => {
+                let from = self
+                    .tracks
+                    .get_index_of(&track)
+                    .ok_or(Error::NonExistentTrack)?;
+                let to = min(to_index, self.tracks.len().saturating_sub(1));
+
+                self.tracks.move_index(from, to);
+
+                Ok(HistoryEntry::MoveTrack { track, to, from })
+            }
+            Edit::Paste(clipboard) => self.paste(&clipboard, cursor, selection, overlap_policy),
+            Edit::QuantiseNotes {
+                cell_duration,
+                strength,
+                swing,
+                include_ends,
+            } => self.quantise_notes(
+                selection,
+                cell_duration,
+                percentage(strength),
+                percentage(swing),
+                include_ends,
+            ),
+            Edit::RemoveLoopRegion => {
+                let from = self.loop_region.take().ok_or(Error::NoChangeToRemove)?;
+
+                Ok(HistoryEntry::SetLoopRegion {
+                    to: None,
+                    from: Some(from),
+                })
+            }
+            Edit::RemoveMarker { at } => {
+                let from = self.markers.remove(&at).ok_or(Error::NonExistentMarker)?;
+
+                Ok(HistoryEntry::SetMarker {
+                    at,
+                    to: None,
+                    from: Some(from),
+                })
+            }
+            Edit::RemoveTempoChange => {
+                let at = self
+                    .tempo
+                    .last_change(cursor)
+                    .ok_or(Error::NoChangeToRemove)?;
+                let from = self.tempo.remove(at);
+                let from_ramp = self.tempo.set_ramp(at, None);
+
+                let mut entries = Vec1::from_one(HistoryEntry::SetTempo { at, to: None, from });
+                entries.push(HistoryEntry::SetTempoRamp {
+                    at,
+                    to: None,
+                    from: from_ramp,
+                });
+
+                Ok(HistoryEntry::Cluster(entries))
+            }
+            Edit::RemoveTimeSignatureChange => {
+                let at = self
+                    .time_signature
+                    .last_change(cursor)
+                    .ok_or(Error::NoChangeToRemove)?;
+                let from = self.time_signature.remove(at);
+
+                Ok(HistoryEntry::SetTimeSignature { at, to: None, from })
+            }
+            Edit::RenameClip { clip, name } => {
+                let (_, clip_reference) = self.clip_mut(clip).ok_or(Error::NonExistentClip)?;
+
+                let from = clip_reference.name();
+                clip_reference.set_name(name.clone());
+
+                Ok(HistoryEntry::RenameClip {
+                    clip,
+                    to: name,
+                    from,
+                })
+            }
+            Edit::RenameMarker { at, name } => {
+                let marker = self.markers.get_mut(&at).ok_or(Error::NonExistentMarker)?;
+
+                let from = replace(marker, name.clone());
+
+                Ok(HistoryEntry::SetMarker {
+                    at,
+                    to: Some(name),
+                    from: Some(from),
+                })
+            }
+            Edit::RenameTrack { track, name } => {
+                let track_reference = self.track_mut(track).ok_or(Error::NonExistentTrack)?;
+
+                let from = track_reference.name();
+                track_reference.set_name(name.clone());
+
+                Ok(HistoryEntry::RenameTrack {
+                    track,
+                    to: name,
+                    from,
+                })
+            }
+            Edit::Repeat { count } => self.repeat_clips(count, selection),
+            Edit::ResizeClip { clip, duration } => self.resize_clip(clip, duration),
+            Edit::ResizeNotes { by, shorten } => self.resize_notes(by, shorten, selection),
+            Edit::SetArticulation { note, articulation } => {
+                let note_reference = self.note_mut(note)?;
+
+                let from = note_reference.articulation();
+                note_reference.set_articulation(articulation);
+
+                Ok(HistoryEntry::SetArticulation {
+                    note,
+                    to: articulation,
+                    from,
+                })
+            }
+            Edit::SetAutomationPoint {
+                track,
+                target,
+                at,
+                point,
+            } => {
+                let point = point.map(|point| Point {
+                    value: target.clamp_value(point.value),
+                    ..point
+                });
+
+                let from = self
+                    .track_mut(track)
+                    .ok_or(Error::NonExistentTrack)?
+                    .automation_mut()
+                    .set_point(target, at, point);
+
+                Ok(HistoryEntry::SetAutomationPoint {
+                    track,
+                    target,
+                    at,
+                    to: point,
+                    from,
+                })
+            }
+            Edit::SetClipColour { clip, colour } => {
+                let (_, clip_reference) = self.clip_mut(clip).ok_or(Error::NonExistentClip)?;
+
+                let from = clip_reference.colour();
+                clip_reference.set_colour(colour);
+
+                Ok(HistoryEntry::SetClipColour {
+                    clip,
+                    to: colour,
+                    from,
+                })
+            }
+            Edit::SetDrumHit {
+                clip,
+                lane,
+                step,
+                velocity,
+            } => {
+                let (_, content) = self.content_mut(clip).ok_or(Error::NonExistentClip)?;
+                let drums = content.as_drums_mut().ok_or(Error::NonDrumClip)?;
+
+                if drums.step_count().get() <= step {
+                    return Err(note::InsertionError::OutsideClip.into());
+                }
+
+                let from = drums
+                    .lane_mut(lane)
+                    .ok_or(Error::NonExistentLane)?
+                    .set_hit(step, velocity);
+
+                Ok(HistoryEntry::SetDrumHit {
+                    clip,
+                    lane,
+                    step,
+                    to: velocity,
+                    from,
+                })
+            }
+            Edit::SetExpressionPoint {
+                note,
+                dimension,
+                at,
+                value,
+            } => {
+                let value = value.map(|value| dimension.clamp_value(value));
+
+                let from = self
+                    .note_mut(note)?
+                    .expression_mut()
+                    .curve_mut(dimension)
+                    .set_point(at, value);
+
+                Ok(HistoryEntry::SetExpressionPoint {
+                    note,
+                    dimension,
+                    at,
+                    to: value,
+                    from,
+                })
+            }
+            Edit::SetKey(key) => {
+                let from = self.key.set(cursor, key);
+
+                Ok(HistoryEntry::SetKey {
+                    at: cursor,
+                    to: Some(key),
+                    from,
+                })
+            }
+            Edit::SetLoopRegion { start, end } => {
+                let region =
+                    NonZeroPeriod::from_endpoints(start, end).ok_or(Error::EmptyLoopRegion)?;
+
+                let from = self.loop_region.replace(region);
+
+                Ok(HistoryEntry::SetLoopRegion {
+                    to: Some(region),
+                    from,
+                })
+            }
+            Edit::SetMixing { track, mixing } => {
+                let track = self.track_mut(track).ok_or(Error::NonExistentTrack)?;
+
+                let from = replace(track.mixing_mut(), mixing);
+
+                Ok(HistoryEntry::SetMixing {
+                    track: track.id(),
+                    to: mixing,
+                    from,
+                })
+            }
+            Edit::SetTempo { tempo, ramp } => {
+                let from = self.tempo.set(cursor, tempo);
+                let from_ramp = self.tempo.set_ramp(cursor, ramp);
+
+                let mut entries = Vec1::from_one(HistoryEntry::SetTempo {
+                    at: cursor,
+                    to: Some(tempo),
+                    from,
+                });
+                entries.push(HistoryEntry::SetTempoRamp {
+                    at: cursor,
+                    to: ramp,
+                    from: from_ramp,
+                });
+
+                Ok(HistoryEntry::Cluster(entries))
+            }
+            Edit::SetTimeSignature(time_signature) => {
+                // Changing the time signature in the middle of a measure would misalign the bars.
+                let at = self.time_signature.measure_at(cursor).start;
+                let from = self.time_signature.set(at, time_signature);
+
+                Ok(HistoryEntry::SetTimeSignature {
+                    at,
+                    to: Some(time_signature),
+                    from,
+                })
+            }
+            Edit::SetTrackColour { track, colour } => {
+                let track_reference = self.track_mut(track).ok_or(Error::NonExistentTrack)?;
+
+                let from = track_reference.colour();
+                track_reference.set_colour(colour);
+
+                Ok(HistoryEntry::SetTrackColour {
+                    track,
+                    to: colour,
+                    from,
+                })
+            }
+            Edit::SetVelocity { note, velocity } => {
+                let note_reference = self.note_mut(note)?;
+
+                let from = note_reference.velocity();
+                note_reference.set_velocity(velocity);
+
+                Ok(HistoryEntry::SetVelocity {
+                    note,
+                    to: velocity,
+                    from,
+                })
+            }
+            Edit::SplitClip => {
+                let clips: Vec<_> = selection
+                    .take_clips()
+                    .ok_or(Error::NoClipSelected)?
+                    .into_iter()
+                    .collect();
+
+                let mut entries = Vec::new();
+
+                for clip in clips {
+                    selection.push_clip(clip);
+
+                    let Some((start, reference)) = self.clip(clip) else {
+                        continue;
+                    };
+
+                    let end = start + reference.duration().get();
+
+                    if cursor <= start || end <= cursor {
+                        continue;
+                    }
+
+                    match self.split_clip(clip, cursor) {
+                        Ok((second, entry)) => {
+                            selection.push_clip(second);
+                            entries.push(entry);
+                        }
+                        Err(error) => {
+                            // The clips that were split are joined again.
+                            return Err(self.roll_back(entries, error));
+                        }
+                    }
+                }
+
+                entries
+                    .into_iter()
+                    .try_collect1()
+                    .map_err(|_empty| Error::NoClipAtCursor)
+            }
+            Edit::Transpose(transposition) => self.transpose_notes(transposition, selection),
+            Edit::TrimClipStart { clip, start } => self.trim_clip_start(clip, start),
+        }
+    }
+}
+
+/// Converts a percentage to a ratio, clamping it to at most 100 %.
+fn percentage(percent: u8) -> Ratio {
+    Ratio::new(u64::from(min(percent, 100)), non_zero!(100))
+}
+
+/// Moves an instant a fraction of the way towards another one.
+fn moved_towards(from: Instant, to: Instant, fraction: Ratio) -> Instant {
+    if from <= to {
+        from + (to - from) * fraction
+    } else {
+        from - (from - to) * fraction
+    }
+}
+
+/// Turns a name into a file stem that is safe to use on any platform.
+///
+/// Control characters and characters that are reserved in paths are replaced by underscores,
+/// surrounding dots and whitespace are removed, and the names of Windows devices are prefixed.
+fn file_stem(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|character| {
+            if character.is_control()
+                || matches!(
+                    character,
+                    '/' | '\\' | '<' | '>' | ':' | '"' | '|' | '?' | '*'
+                )
+            {
+                '_'
+            } else {
+                character
+            }
+        })
+        .collect();
+
+    let stem =
+        replaced.trim_matches(|character: char| character == '.' || character.is_whitespace());
+
+    if stem.is_empty() {
+        return FALLBACK_FILE_STEM.to_owned();
+    }
+
+    let upper = stem.to_ascii_uppercase();
+    let is_device = matches!(upper.as_str(), "AUX" | "CON" | "NUL" | "PRN")
+        || ["COM", "LPT"].iter().any(|prefix| {
+            upper.strip_prefix(prefix).is_some_and(|number| {
+                matches!(number, "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9")
+            })
+        });
+
+    if is_device {
+        format!("_{stem}")
+    } else {
+        stem.to_owned()
+    }
+}
+
+/// Returns the name of a file in `directory` that does not yet exist.
+///
+/// If `{stem}.{extension}` is taken, a number is appended to the stem.
+fn unused_file_name(directory: &Path, stem: &str, extension: &str) -> PathBuf {
+    let mut file = PathBuf::from(format!("{stem}.{extension}"));
+    let mut number: u32 = 1;
+
+    while directory.join(&file).exists() {
+        number = number.saturating_add(1);
+        file = PathBuf::from(format!("{stem} ({number}).{extension}"));
+    }
+
+    file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::NonZeroRatio;
+    use crate::audio::Sample;
+    use crate::audio::sample;
+    use crate::note::NonUnisonSimpleInterval;
+    use crate::note::PitchClass;
+    use crate::note::Sign;
+    use crate::time;
+    use anyhow::Context as _;
+    use anyhow::bail;
+    use anyhow::ensure;
+    use enum_iterator::all;
+
+    /// Returns the instant a number of whole notes after the start.
+    fn whole_notes(count: u64) -> Instant {
+        Instant {
+            since_start: Duration {
+                whole_notes: Ratio::integer(count),
+            },
+        }
+    }
+
+    /// Creates a project with a track that has an empty note clip starting at each instant.
+    ///
+    /// The clips last four whole notes each.
+    fn track_with_clips(
+        starts: &[Instant],
+    ) -> anyhow::Result<(Project, Id<Track>, Vec<clip::Path>)> {
+        let mut project = Project::default();
+        let mut selection = Selection::default();
+
+        project.edit(
+            Edit::AddTrack,
+            Instant::START,
+            &mut selection,
+            OverlapPolicy::Reject,
+        )?;
+        let track = selection.top_track().context("the track was not added")?;
+
+        let mut clips = Vec::new();
+
+        for start in starts {
+            selection.clear();
+            selection.push_track(track);
+
+            project.edit(
+                Edit::AddNoteGroup,
+                *start,
+                &mut selection,
+                OverlapPolicy::Reject,
+            )?;
+            clips.push(selection.top_clip().context("the clip was not added")?);
+        }
+
+        Ok((project, track, clips))
+    }
+
+    /// Returns the start and duration of a clip.
+    fn extent(project: &Project, clip: clip::Path) -> anyhow::Result<(Instant, Duration)> {
+        let (start, clip) = project.clip(clip).context("the clip does not exist")?;
+
+        Ok((start, clip.duration().get()))
+    }
+
+    /// Checks that the clips start at the given instants and last the given numbers of whole notes.
+    fn check_extents(
+        project: &Project,
+        clips: &[clip::Path],
+        expected: &[(u64, u64)],
+    ) -> anyhow::Result<()> {
+        for (clip, (start, duration)) in clips.iter().zip(expected) {
+            let expected = (whole_notes(*start), whole_notes(*duration).since_start);
+            let actual = extent(project, *clip)?;
+
+            ensure!(
+                actual == expected,
+                "a clip spans {actual:?} instead of {expected:?}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_overlap() -> anyhow::Result<()> {
+        let (mut project, track, clips) = track_with_clips(&[whole_notes(0), whole_notes(4)])?;
+
+        let Some(&[first, second]) = clips.get(..2) else {
+            bail!("the clips were not added");
+        };
+
+        let result = project.move_clip(second, track, whole_notes(2), OverlapPolicy::Reject);
+
+        ensure!(
+            matches!(
+                result,
+                Err(Error::ClipInsertion(ClipInsertionErrorKind::Overlap { clip })) if clip == first.clip
+            ),
+            "the overlap was not rejected: {result:?}"
+        );
+
+        check_extents(&project, &clips, &[(0, 4), (4, 4)])
+    }
+
+    #[test]
+    fn push_clips_into_further_clips() -> anyhow::Result<()> {
+        let (mut project, track, clips) = track_with_clips(&[
+            whole_notes(0),
+            whole_notes(6),
+            whole_notes(10),
+            whole_notes(20),
+        ])?;
+
+        let Some(&last) = clips.last() else {
+            bail!("the clips were not added");
+        };
+
+        // The clip at 6 is pushed to 8, which pushes the clip at 10 to 12.
+        let entry = project.move_clip(last, track, whole_notes(4), OverlapPolicy::Push)?;
+
+        check_extents(&project, &clips, &[(0, 4), (8, 4), (12, 4), (4, 4)])?;
+
+        project.undo(entry).map_err(|failure| failure.error)?;
+
+        check_extents(&project, &clips, &[(0, 4), (6, 4), (10, 4), (20, 4)])
+    }
+
+    #[test]
+    fn push_rejects_starting_inside_a_clip() -> anyhow::Result<()> {
+        let (mut project, track, clips) = track_with_clips(&[whole_notes(0), whole_notes(6)])?;
+
+        let Some(&[first, second]) = clips.get(..2) else {
+            bail!("the clips were not added");
+        };
+
+        let result = project.move_clip(second, track, whole_notes(2), OverlapPolicy::Push);
+
+        ensure!(
+            matches!(
+                result,
+                Err(Error::ClipInsertion(ClipInsertionErrorKind::Overlap { clip })) if clip == first.clip
+            ),
+            "the clip was placed inside another one: {result:?}"
+        );
+
+        check_extents(&project, &clips, &[(0, 4), (6, 4)])
+    }
+
+    #[test]
+    fn trim_the_clip_that_is_started_inside() -> anyhow::Result<()> {
+        let (mut project, track, clips) = track_with_clips(&[whole_notes(0), whole_notes(8)])?;
+
+        let Some(&second) = clips.get(1) else {
+            bail!("the clips were not added");
+        };
+
+        let entry = project.move_clip(second, track, whole_notes(2), OverlapPolicy::Trim)?;
+
+        // The first clip is replaced by a shorter copy.
+        let (start, trimmed) = project
+            .track(track)
+            .and_then(|track| track.first_clip_in(Instant::START, whole_notes(1)))
+            .context("the first clip was removed")?;
+
+        ensure!(start == Instant::START, "the first clip was moved");
+        check_extents(
+            &project,
+            &[clip::Path::new(track, trimmed), second],
+            &[(0, 2), (2, 4)],
+        )?;
+
+        project.undo(entry).map_err(|failure| failure.error)?;
+
+        check_extents(&project, &clips, &[(0, 4), (8, 4)])
+    }
+
+    #[test]
+    fn trim_rejects_overlapping_later_clips() -> anyhow::Result<()> {
+        let (mut project, track, clips) =
+            track_with_clips(&[whole_notes(0), whole_notes(5), whole_notes(20)])?;
+
+        let Some(&[_, second, third]) = clips.get(..3) else {
+            bail!("the clips were not added");
+        };
+
+        let result = project.move_clip(third, track, whole_notes(2), OverlapPolicy::Trim);
+
+        ensure!(
+            matches!(
+                result,
+                Err(Error::ClipInsertion(ClipInsertionErrorKind::Overlap { clip })) if clip == second.clip
+            ),
+            "the clip was placed over a later clip: {result:?}"
+        );
+
+        check_extents(&project, &clips, &[(0, 4), (5, 4), (20, 4)])
+    }
+
+    #[test]
+    fn trim_rejects_leaving_nothing() -> anyhow::Result<()> {
+        let (mut project, track, clips) = track_with_clips(&[whole_notes(0), whole_notes(8)])?;
+
+        let Some(&[first, second]) = clips.get(..2) else {
+            bail!("the clips were not added");
+        };
+
+        // Trimming the first clip to make room would leave it without any duration.
+        let result = project.move_clip(second, track, whole_notes(0), OverlapPolicy::Trim);
+
+        ensure!(
+            matches!(
+                result,
+                Err(Error::ClipInsertion(ClipInsertionErrorKind::Overlap { clip })) if clip == first.clip
+            ),
+            "the clip was trimmed to nothing: {result:?}"
+        );
+
+        check_extents(&project, &clips, &[(0, 4), (8, 4)])
+    }
+
+    #[test]
+    fn make_room_without_overlap() -> anyhow::Result<()> {
+        let (mut project, track, clips) = track_with_clips(&[whole_notes(0), whole_notes(8)])?;
+
+        for policy in all::<OverlapPolicy>() {
+            let entries =
+                project.make_room(track, whole_notes(4), NonZeroDuration::QUARTER, policy)?;
+
+            ensure!(
+                entries.is_empty(),
+                "{policy:?} changed other clips despite there being room"
+            );
+        }
+
+        check_extents(&project, &clips, &[(0, 4), (8, 4)])
+    }
+
+    #[test]
+    fn sanitise_file_stems() -> anyhow::Result<()> {
+        for (name, expected) in [
+            ("vocals", "vocals"),
+            ("../../etc/passwd", "_.._etc_passwd"),
+            ("a/b\\c", "a_b_c"),
+            ("nul\0byte", "nul_byte"),
+            ("what? <yes>: \"no\" | *", "what_ _yes__ _no_ _ _"),
+            ("", FALLBACK_FILE_STEM),
+            (" .. ", FALLBACK_FILE_STEM),
+            (".hidden.", "hidden"),
+            ("con", "_con"),
+            ("LPT1", "_LPT1"),
+            ("COM10", "COM10"),
+        ] {
+            let stem = file_stem(name);
+
+            ensure!(
+                stem == expected,
+                "{name:?} became {stem:?} instead of {expected:?}"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Returns a non-zero duration of a number of whole notes.
+    fn whole_note_duration(count: NonZeroU64) -> NonZeroDuration {
+        NonZeroDuration {
+            whole_notes: NonZeroRatio::integer(count),
+        }
+    }
+
+    /// Returns the position a number of whole notes after the start of a clip.
+    fn relative_whole_notes(count: u64) -> relative::Instant {
+        relative::Instant {
+            since_start: whole_notes(count).since_start,
+        }
+    }
+
+    /// Adds a note at middle C to a note clip, at and for the given numbers of whole notes.
+    fn add_note(
+        project: &mut Project,
+        clip: clip::Path,
+        position: u64,
+        duration: NonZeroU64,
+    ) -> anyhow::Result<()> {
+        insert_note(
+            project,
+            clip,
+            whole_notes(position),
+            middle_c(),
+            whole_note_duration(duration),
+        )
+    }
+
+    /// Adds a note to a note clip.
+    fn insert_note(
+        project: &mut Project,
+        clip: clip::Path,
+        position: Instant,
+        pitch: Pitch,
+        duration: NonZeroDuration,
+    ) -> anyhow::Result<()> {
+        let mut selection = Selection::default();
+        selection.push_clip(clip);
+
+        project.edit(
+            Edit::AddNote {
+                position,
+                pitch,
+                duration,
+            },
+            position,
+            &mut selection,
+            OverlapPolicy::Reject,
+        )?;
+
+        Ok(())
+    }
+
+    /// Returns the placements of the notes in a note clip, in order.
+    fn placements(project: &Project, clip: clip::Path) -> anyhow::Result<Vec<Placement>> {
+        let (_, content) = project.content(clip).context("the clip does not exist")?;
+        let notes = content.as_notes().context("the clip is not a note clip")?;
+
+        let mut placements: Vec<_> = notes
+            .notes()
+            .map(|(position, pitch, note)| Placement {
+                position,
+                pitch,
+                duration: note.duration(),
+            })
+            .collect();
+
+        placements.sort_unstable_by_key(|placement| (placement.position, placement.pitch));
+
+        Ok(placements)
+    }
+
+    /// Returns middle C.
+    fn middle_c() -> Pitch {
+        midi_pitch(60)
+    }
+
+    /// Returns the pitch with a MIDI number.
+    fn midi_pitch(number: i8) -> Pitch {
+        Pitch::LOWEST + Interval::from_semitones(number)
+    }
+
+    /// Returns the placement of a note at middle C.
+    fn note_at(position: u64, duration: NonZeroU64) -> Placement {
+        Placement {
+            position: relative_whole_notes(position),
+            pitch: middle_c(),
+            duration: whole_note_duration(duration),
+        }
+    }
+
+    /// Returns the audio of an embedded audio clip.
+    fn embedded_audio(project: &Project, clip: clip::Path) -> anyhow::Result<&FixedLength> {
+        let (_, content) = project.content(clip).context("the clip does not exist")?;
+
+        content
+            .as_audio()
+            .context("the clip is not an embedded audio clip")
+    }
+
+    #[test]
+    fn split_audio_with_an_offset() -> anyhow::Result<()> {
+        let (mut project, track, _) = track_with_clips(&[])?;
+
+        // A whole note lasts two seconds, or eight samples.
+        project.tempo.set(
+            Instant::START,
+            Tempo::new(NonZeroRatio::integer(non_zero!(120))),
+        );
+
+        let sample_rate = sample::Rate {
+            samples_per_second: non_zero!(4),
+        };
+        let samples: Vec<_> = (0..16_u16)
+            .map(|index| Sample::new(f32::from(index) / 16.0))
+            .collect();
+
+        let clip = Clip::from_audio(
+            ArcStr::from("audio"),
+            FixedLength {
+                audio: Audio {
+                    sample_rate,
+                    channels: [samples.clone(), samples.clone()],
+                },
+                duration: whole_note_duration(non_zero!(1)),
+                offset: time::Duration::SECOND,
+            },
+        );
+
+        let clip = project
+            .track_mut(track)
+            .context("the track does not exist")?
+            .try_insert_clip(Instant::START, clip)
+            .map_err(|error| error.kind)?;
+
+        let half = Instant {
+            since_start: Duration {
+                whole_notes: Ratio::HALF,
+            },
+        };
+
+        let (second, _) = project.split_clip(clip, half)?;
+
+        // The split is one second into the clip, which is two seconds, or eight samples, into the audio.
+        let first = embedded_audio(&project, clip)?;
+        let [first_samples, _] = &first.audio.channels;
+
+        ensure!(
+            first.offset == time::Duration::SECOND
+                && Some(first_samples.as_slice()) == samples.get(..8),
+            "the first part does not keep the offset and the audio up to the split: {first:?}"
+        );
+
+        let second = embedded_audio(&project, second)?;
+        let [second_samples, _] = &second.audio.channels;
+
+        ensure!(
+            second.offset == time::Duration::ZERO
+                && Some(second_samples.as_slice()) == samples.get(8..),
+            "the second part does not start with the audio at the split: {second:?}"
+        );
+
+        ensure!(
+            first.duration.get() + second.duration.get() == whole_notes(1).since_start,
+            "the parts are not as long as the clip"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn split_notes_that_cross_the_instant() -> anyhow::Result<()> {
+        let (mut project, _, clips) = track_with_clips(&[Instant::START])?;
+
+        let Some(&clip) = clips.first() else {
+            bail!("the clip was not added");
+        };
+
+        add_note(&mut project, clip, 0, non_zero!(2))?;
+        add_note(&mut project, clip, 3, non_zero!(1))?;
+
+        let (second, _) = project.split_clip(clip, whole_notes(1))?;
+
+        let first = placements(&project, clip)?;
+        let second = placements(&project, second)?;
+
+        ensure!(
+            first == [note_at(0, non_zero!(1))],
+            "the start of the crossing note is not kept in the first part: {first:?}"
+        );
+        ensure!(
+            second == [note_at(0, non_zero!(1)), note_at(2, non_zero!(1))],
+            "the end of the crossing note is not moved to the second part: {second:?}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_split_points_outside_the_clip() -> anyhow::Result<()> {
+        let (mut project, _, clips) = track_with_clips(&[whole_notes(4)])?;
+
+        let Some(&clip) = clips.first() else {
+            bail!("the clip was not added");
+        };
+
+        for at in [0, 4, 8, 9] {
+            let at = whole_notes(at);
+            let result = project.split_clip(clip, at);
+
+            ensure!(
+                matches!(result, Err(Error::InvalidSplitPoint)),
+                "splitting at {at:?} gave {result:?}"
+            );
+        }
+
+        check_extents(&project, &clips, &[(4, 4)])
+    }
+
+    #[test]
+    fn join_and_split_round_trip() -> anyhow::Result<()> {
+        let (mut project, _, clips) = track_with_clips(&[whole_notes(0), whole_notes(4)])?;
+
+        let Some(&[first, second]) = clips.get(..2) else {
+            bail!("the clips were not added");
+        };
+
+        add_note(&mut project, first, 1, non_zero!(2))?;
+        add_note(&mut project, second, 4, non_zero!(1))?;
+        add_note(&mut project, second, 6, non_zero!(2))?;
+
+        let first_notes = placements(&project, first)?;
+        let second_notes = placements(&project, second)?;
+
+        let mut selection = Selection::default();
+        selection.push_clip(first);
+        selection.push_clip(second);
+
+        project.edit(
+            Edit::JoinClips,
+            Instant::START,
+            &mut selection,
+            OverlapPolicy::Reject,
+        )?;
+        let joined = selection
+            .top_clip()
+            .context("the joined clip was not selected")?;
+
+        let (split, _) = project.split_clip(joined, whole_notes(4))?;
+
+        check_extents(&project, &[joined, split], &[(0, 4), (4, 4)])?;
+
+        ensure!(
+            placements(&project, joined)? == first_notes,
+            "the notes of the first clip changed"
+        );
+        ensure!(
+            placements(&project, split)? == second_notes,
+            "the notes of the second clip changed"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn paste_all_notes_or_none() -> anyhow::Result<()> {
+        let (mut project, _, clips) = track_with_clips(&[Instant::START])?;
+
+        let Some(&clip) = clips.first() else {
+            bail!("the clip was not added");
+        };
+
+        add_note(&mut project, clip, 0, non_zero!(1))?;
+        add_note(&mut project, clip, 2, non_zero!(2))?;
+
+        let original = placements(&project, clip)?;
+
+        let mut notes = Vec1::from_one((
+            Duration::ZERO,
+            middle_c(),
+            Note::new(whole_note_duration(non_zero!(1))),
+        ));
+        notes.push((
+            whole_notes(2).since_start,
+            middle_c(),
+            Note::new(whole_note_duration(non_zero!(1))),
+        ));
+
+        let mut selection = Selection::default();
+        selection.push_clip(clip);
+
+        // The first note fits before the second original one, but the second lands inside it.
+        let result = project.edit(
+            Edit::Paste(Arc::new(Clipboard::Notes(notes))),
+            whole_notes(1),
+            &mut selection,
+            OverlapPolicy::Reject,
+        );
+
+        ensure!(
+            matches!(
+                result,
+                Err(Error::NoteInsertion(note::InsertionError::InsideOther))
+            ),
+            "pasting gave {result:?}"
+        );
+        ensure!(
+            placements(&project, clip)? == original,
+            "the notes that could be pasted were not removed again"
+        );
+
+        Ok(())
+    }
+
+    /// Returns a number of sixteenth notes.
+    fn sixteenths(count: u64) -> Duration {
+        Duration {
+            whole_notes: Ratio::new(count, non_zero!(16)),
+        }
+    }
+
+    /// Returns a non-zero number of sixteenth notes.
+    fn non_zero_sixteenths(count: NonZeroU64) -> NonZeroDuration {
+        NonZeroDuration {
+            whole_notes: NonZeroRatio::new(count, non_zero!(16)),
+        }
+    }
+
+    /// Adds a note at middle C to a note clip, at and for the given numbers of sixteenth notes.
+    fn add_sixteenth_notes(
+        project: &mut Project,
+        clip: clip::Path,
+        position: u64,
+        duration: NonZeroU64,
+    ) -> anyhow::Result<()> {
+        let position = Instant {
+            since_start: sixteenths(position),
+        };
+
+        insert_note(
+            project,
+            clip,
+            position,
+            middle_c(),
+            non_zero_sixteenths(duration),
+        )
+    }
+
+    /// Returns the placement of a note at middle C, at and for the given numbers of sixteenth notes.
+    fn sixteenth_notes_at(position: u64, duration: NonZeroU64) -> Placement {
+        Placement {
+            position: relative::Instant {
+                since_start: sixteenths(position),
+            },
+            pitch: middle_c(),
+            duration: non_zero_sixteenths(duration),
+        }
+    }
+
+    /// Quantises the notes of a clip at full strength and without swing.
+    fn quantise(
+        project: &mut Project,
+        clip: clip::Path,
+        cell_duration: NonZeroDuration,
+        include_ends: bool,
+    ) -> Result<(HistoryEntry, Selection), Error> {
+        let mut selection = Selection::default();
+        selection.push_clip(clip);
+
+        let entry = project.edit(
+            Edit::QuantiseNotes {
+                cell_duration,
+                strength: 100,
+                swing: 0,
+                include_ends,
+            },
+            Instant::START,
+            &mut selection,
+            OverlapPolicy::Reject,
+        )?;
+
+        Ok((entry, selection))
+    }
+
+    #[test]
+    fn quantise_to_the_grid() -> anyhow::Result<()> {
+        let (mut project, _, clips) = track_with_clips(&[Instant::START])?;
+
+        let Some(&clip) = clips.first() else {
+            bail!("the clip was not added");
+        };
+
+        add_sixteenth_notes(&mut project, clip, 3, non_zero!(2))?;
+        add_sixteenth_notes(&mut project, clip, 11, non_zero!(4))?;
+        add_sixteenth_notes(&mut project, clip, 17, non_zero!(3))?;
+
+        let original = placements(&project, clip)?;
+
+        let (entry, _) = quantise(&mut project, clip, NonZeroDuration::QUARTER, false)?;
+
+        let quantised = placements(&project, clip)?;
+
+        ensure!(
+            quantised
+                == [
+                    sixteenth_notes_at(4, non_zero!(2)),
+                    sixteenth_notes_at(12, non_zero!(4)),
+                    sixteenth_notes_at(16, non_zero!(3)),
+                ],
+            "the notes were not moved to the nearest grid lines: {quantised:?}"
+        );
+
+        let result = quantise(&mut project, clip, NonZeroDuration::QUARTER, false);
+
+        ensure!(
+            matches!(result, Err(Error::AlreadyQuantised)),
+            "quantising again gave {result:?}"
+        );
+
+        project.undo(entry).map_err(|failure| failure.error)?;
+
+        ensure!(
+            placements(&project, clip)? == original,
+            "undoing did not restore the notes"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn keep_the_durations_of_notes_that_would_collapse() -> anyhow::Result<()> {
+        let (mut project, _, clips) = track_with_clips(&[Instant::START])?;
+
+        let Some(&clip) = clips.first() else {
+            bail!("the clip was not added");
+        };
+
+        add_sixteenth_notes(&mut project, clip, 1, non_zero!(1))?;
+        add_sixteenth_notes(&mut project, clip, 5, non_zero!(9))?;
+
+        let half = NonZeroDuration {
+            whole_notes: NonZeroRatio::new(non_zero!(1), non_zero!(2)),
+        };
+
+        quantise(&mut project, clip, half, true)?;
+
+        let quantised = placements(&project, clip)?;
+
+        // The start and end of the first note are both nearest to the first grid line.
+        ensure!(
+            quantised
+                == [
+                    sixteenth_notes_at(0, non_zero!(1)),
+                    sixteenth_notes_at(8, non_zero!(8)),
+                ],
+            "the collapsing note was not moved without being resized: {quantised:?}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn report_notes_that_would_leave_the_clip() -> anyhow::Result<()> {
+        let (mut project, _, clips) = track_with_clips(&[Instant::START])?;
+
+        let Some(&clip) = clips.first() else {
+            bail!("the clip was not added");
+        };
+
+        add_sixteenth_notes(&mut project, clip, 3, non_zero!(1))?;
+        add_sixteenth_notes(&mut project, clip, 63, non_zero!(1))?;
+
+        let Some((_, _, last)) = project
+            .content(clip)
+            .and_then(|(_, content)| content.as_notes())
+            .and_then(|notes| notes.notes().max_by_key(|(position, _, _)| *position))
+        else {
+            bail!("the notes were not added");
+        };
+        let last = note::Path::new(clip.track, clip.clip, last.id());
+
+        let original = placements(&project, clip)?;
+
+        let mut selection = Selection::default();
+        selection.push_clip(clip);
+
+        // The last note would start where the clip ends.
+        let result = project.edit(
+            Edit::QuantiseNotes {
+                cell_duration: NonZeroDuration::QUARTER,
+                strength: 100,
+                swing: 0,
+                include_ends: false,
+            },
+            Instant::START,
+            &mut selection,
+            OverlapPolicy::Reject,
+        );
+
+        ensure!(
+            matches!(result, Err(Error::UnmovableNotes { count: 1 })),
+            "quantising a note that would leave the clip gave {result:?}"
+        );
+
+        ensure!(
+            placements(&project, clip)? == original,
+            "no notes should be moved when one of them cannot be"
+        );
+
+        let unmoved: Vec<_> = selection
+            .clone()
+            .take_notes()
+            .into_iter()
+            .flatten()
+            .collect();
+
+        ensure!(
+            unmoved == [last],
+            "the note that could not be moved is not the selection: {unmoved:?}"
+        );
+
+        Ok(())
+    }
+
+    /// Returns a major key.
+    fn major(tonic: PitchClass) -> Key {
+        Key {
+            tonic,
+            sign: Sign::default(),
+            intervals: NonUnisonSimpleInterval::M2
+                | NonUnisonSimpleInterval::M3
+                | NonUnisonSimpleInterval::P4
+                | NonUnisonSimpleInterval::P5
+                | NonUnisonSimpleInterval::M6
+                | NonUnisonSimpleInterval::M7,
+        }
+    }
+
+    /// Adds a whole note with each of the given MIDI numbers at each of the given whole notes.
+    fn add_chords(
+        project: &mut Project,
+        clip: clip::Path,
+        chords: &[(u64, &[i8])],
+    ) -> anyhow::Result<()> {
+        for (position, numbers) in chords {
+            for number in *numbers {
+                insert_note(
+                    project,
+                    clip,
+                    whole_notes(*position),
+                    midi_pitch(*number),
+                    whole_note_duration(non_zero!(1)),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Transposes the notes of a clip.
+    fn transpose(
+        project: &mut Project,
+        clip: clip::Path,
+        transposition: Transposition,
+    ) -> Result<HistoryEntry, Error> {
+        let mut selection = Selection::default();
+        selection.push_clip(clip);
+
+        project.edit(
+            Edit::Transpose(transposition),
+            Instant::START,
+            &mut selection,
+            OverlapPolicy::Reject,
+        )
+    }
+
+    /// Returns the MIDI numbers of the notes of a clip, in order.
+    fn midi_numbers(project: &Project, clip: clip::Path) -> anyhow::Result<Vec<i8>> {
+        Ok(placements(project, clip)?
+            .into_iter()
+            .map(|placement| (placement.pitch - Pitch::LOWEST).semitones())
+            .collect())
+    }
+
+    #[test]
+    fn transpose_diatonically_in_the_key_at_each_note() -> anyhow::Result<()> {
+        let (mut project, _, clips) = track_with_clips(&[Instant::START])?;
+
+        let Some(&clip) = clips.first() else {
+            bail!("the clip was not added");
+        };
+
+        project.key.set(Instant::START, major(PitchClass::D));
+        project.key.set(whole_notes(2), major(PitchClass::Bb));
+
+        // D4 and F♯4 in D major, then A4 in B♭ major.
+        add_chords(&mut project, clip, &[(0, &[62, 66]), (2, &[69])])?;
+
+        let entry = transpose(&mut project, clip, Transposition::Diatonic(1))?;
+
+        let transposed = midi_numbers(&project, clip)?;
+
+        ensure!(
+            transposed == [64, 67, 70],
+            "the notes were not moved up a step in their keys: {transposed:?}"
+        );
+
+        project.undo(entry).map_err(|failure| failure.error)?;
+
+        transpose(&mut project, clip, Transposition::Diatonic(-8))?;
+
+        let transposed = midi_numbers(&project, clip)?;
+
+        ensure!(
+            transposed == [49, 52, 55],
+            "the notes were not moved down an octave and a step in their keys: {transposed:?}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn transpose_pitches_outside_the_key() -> anyhow::Result<()> {
+        let (mut project, _, clips) = track_with_clips(&[Instant::START])?;
+
+        let Some(&clip) = clips.first() else {
+            bail!("the clip was not added");
+        };
+
+        project.key.set(Instant::START, major(PitchClass::Eb));
+
+        // E4 and B4 are a semitone above E♭4 and B♭4, which are in E♭ major.
+        add_chords(&mut project, clip, &[(0, &[64, 71])])?;
+
+        transpose(&mut project, clip, Transposition::Diatonic(2))?;
+
+        let transposed = midi_numbers(&project, clip)?;
+
+        ensure!(
+            transposed == [68, 75],
+            "the notes did not keep their distances to the key: {transposed:?}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_transposing_out_of_range() -> anyhow::Result<()> {
+        let (mut project, _, clips) = track_with_clips(&[Instant::START])?;
+
+        let Some(&clip) = clips.first() else {
+            bail!("the clip was not added");
+        };
+
+        add_chords(&mut project, clip, &[(0, &[5, 60, 120])])?;
+
+        let original = placements(&project, clip)?;
+
+        for transposition in [
+            Transposition::Chromatic(Interval::from_semitones(12)),
+            Transposition::Chromatic(Interval::from_semitones(-12)),
+            Transposition::Diatonic(8),
+            Transposition::Diatonic(-4),
+        ] {
+            let result = transpose(&mut project, clip, transposition);
+
+            ensure!(
+                matches!(result, Err(Error::PitchOutOfRange)),
+                "transposing by {transposition:?} gave {result:?}"
+            );
+            ensure!(
+                placements(&project, clip)? == original,
+                "transposing by {transposition:?} moved notes"
+            );
+        }
+
+        Ok(())
     }
 }
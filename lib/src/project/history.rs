@@ -2,30 +2,49 @@
 
 use crate::Id;
 use crate::Note;
+use crate::Project;
 use crate::metre::Instant;
+use crate::metre::NonZeroPeriod;
+use crate::metre::Ramp;
+use crate::metre::TimeSignature;
 use crate::metre::relative;
 use crate::note;
+use crate::note::Articulation;
 use crate::note::Key;
 use crate::note::Pitch;
+use crate::note::Placement;
+use crate::note::Velocity;
+use crate::note::expression::Curve;
+use crate::note::expression::Dimension;
 use crate::project::Track;
+use crate::project::edit::Error;
 use crate::project::track::Clip;
+use crate::project::track::Mixing;
+use crate::project::track::OverlapPolicy;
+use crate::project::track::automation::Point;
+use crate::project::track::automation::Target;
 use crate::project::track::clip;
+use crate::time::Tempo;
+use crate::ui::Colour;
+use arcstr::ArcStr;
 use mitsein::iter1::FromIterator1;
 use mitsein::iter1::IntoIterator1;
 use mitsein::vec1::Vec1;
+use std::cmp::min;
+use std::mem::replace;
 
-// TODO: Add a method for constructing an undoing action
 /// A performed [edit](Edit).
-#[expect(dead_code, reason = "see TODO")]
 #[derive(Debug)]
 #[remain::sorted]
 pub enum HistoryEntry {
-    /// The addition of a track at the bottom.
+    /// The addition of a track.
     AddTrack(Id<Track>),
     /// A collection of actions that were taken at once.
     Cluster(Vec1<HistoryEntry>),
     /// The deletion of a clip.
     DeleteClip {
+        /// The track that contained the clip.
+        track: Id<Track>,
         /// The position of the clip.
         start: Instant,
         /// The deleted clip.
@@ -33,6 +52,8 @@ pub enum HistoryEntry {
     },
     /// The deletion of a note.
     DeleteNote {
+        /// The clip that contained the note.
+        clip: clip::Path,
         /// The position of the note.
         instant: relative::Instant,
         /// The pitch of the note.
@@ -51,6 +72,13 @@ pub enum HistoryEntry {
     InsertClip(clip::Path),
     /// The insertion of a note.
     InsertNote(note::Path),
+    /// The moving of the content of a clip into the linked contents.
+    LinkContent {
+        /// The clip.
+        clip: clip::Path,
+        /// The link to the content.
+        link: clip::Link,
+    },
     /// The relocation of a clip.
     MoveClip {
         /// The track from which the clip was moved.
@@ -60,15 +88,201 @@ pub enum HistoryEntry {
         /// The path to the clip after the move.
         new_path: clip::Path,
     },
+    /// The moving or resizing of a note within its clip.
+    MoveNote {
+        /// The note.
+        note: note::Path,
+        /// Where the note was placed.
+        to: Placement,
+        /// Where the note was placed before.
+        from: Placement,
+    },
+    /// The moving of a track to another place in the track list.
+    MoveTrack {
+        /// The track.
+        track: Id<Track>,
+        /// The index that the track was moved to.
+        to: usize,
+        /// The index that the track was moved from.
+        from: usize,
+    },
+    /// The renaming of a clip.
+    RenameClip {
+        /// The clip.
+        clip: clip::Path,
+        /// The name that was set.
+        to: ArcStr,
+        /// The name that was overwritten.
+        from: ArcStr,
+    },
+    /// The renaming of a track.
+    RenameTrack {
+        /// The track.
+        track: Id<Track>,
+        /// The name that was set.
+        to: ArcStr,
+        /// The name that was overwritten.
+        from: ArcStr,
+    },
+    /// The replacement of the content of a clip.
+    ReplaceContent {
+        /// The clip.
+        clip: clip::Path,
+        /// The content that was replaced.
+        content: clip::Content,
+    },
+    /// The replacement of an expression curve of a note.
+    ReplaceCurve {
+        /// The note.
+        note: note::Path,
+        /// The dimension of the curve.
+        dimension: Dimension,
+        /// The curve that was replaced.
+        curve: Curve,
+    },
+    /// The setting of the articulation of a note.
+    SetArticulation {
+        /// The note.
+        note: note::Path,
+        /// The articulation that was set.
+        to: Option<Articulation>,
+        /// The articulation that was overwritten.
+        from: Option<Articulation>,
+    },
+    /// The setting or removal of a point in an automation lane of a track.
+    SetAutomationPoint {
+        /// The track.
+        track: Id<Track>,
+        /// The automated parameter.
+        target: Target,
+        /// The position of the point.
+        at: Instant,
+        /// The point that was set, or `None` if the point was removed.
+        to: Option<Point>,
+        /// The point that was overwritten.
+        from: Option<Point>,
+    },
+    /// The setting of the colour of a clip.
+    SetClipColour {
+        /// The clip.
+        clip: clip::Path,
+        /// The colour that was set.
+        to: Colour,
+        /// The colour that was overwritten.
+        from: Colour,
+    },
+    /// The setting or removal of a hit in a drum clip.
+    SetDrumHit {
+        /// The drum clip.
+        clip: clip::Path,
+        /// The index of the lane.
+        lane: usize,
+        /// The index of the step.
+        step: u64,
+        /// The velocity of the hit that was set, or `None` if the hit was removed.
+        to: Option<Velocity>,
+        /// The velocity of the hit that was overwritten.
+        from: Option<Velocity>,
+    },
+    /// The setting or removal of a point on an expression curve of a note.
+    SetExpressionPoint {
+        /// The note.
+        note: note::Path,
+        /// The dimension of the curve.
+        dimension: Dimension,
+        /// The position of the point, relative to the start of the note.
+        at: relative::Instant,
+        /// The value that was set, or `None` if the point was removed.
+        to: Option<i16>,
+        /// The value that was overwritten.
+        from: Option<i16>,
+    },
     /// The setting of the key.
     SetKey {
         /// The position at which the key was set.
         at: Instant,
-        /// The key that was set.
-        to: Key,
+        /// The key that was set, or `None` if a key change was removed.
+        to: Option<Key>,
         /// The key that was overwritten.
         from: Option<Key>,
     },
+    /// The setting or removal of the loop region.
+    SetLoopRegion {
+        /// The region that was set, or `None` if the region was removed.
+        to: Option<NonZeroPeriod>,
+        /// The region that was overwritten.
+        from: Option<NonZeroPeriod>,
+    },
+    /// The setting, renaming or removal of a marker.
+    SetMarker {
+        /// The position of the marker.
+        at: Instant,
+        /// The name that was set, or `None` if the marker was removed.
+        to: Option<ArcStr>,
+        /// The name that was overwritten, or `None` if the marker was added.
+        from: Option<ArcStr>,
+    },
+    /// The setting of how a track is mixed.
+    SetMixing {
+        /// The track.
+        track: Id<Track>,
+        /// The mixing settings that were set.
+        to: Mixing,
+        /// The mixing settings that were overwritten.
+        from: Mixing,
+    },
+    /// The setting of the tempo.
+    SetTempo {
+        /// The position at which the tempo was set.
+        at: Instant,
+        /// The tempo that was set, or `None` if a tempo change was removed.
+        to: Option<Tempo>,
+        /// The tempo that was overwritten.
+        from: Option<Tempo>,
+    },
+    /// The setting of how a tempo change is approached.
+    SetTempoRamp {
+        /// The position of the tempo change.
+        at: Instant,
+        /// The ramp that was set, or `None` if the change was made instant.
+        to: Option<Ramp>,
+        /// The ramp that was overwritten.
+        from: Option<Ramp>,
+    },
+    /// The setting of the time signature.
+    SetTimeSignature {
+        /// The position at which the time signature was set.
+        at: Instant,
+        /// The time signature that was set, or `None` if a time-signature change was removed.
+        to: Option<TimeSignature>,
+        /// The time signature that was overwritten.
+        from: Option<TimeSignature>,
+    },
+    /// The setting of the colour of a track.
+    SetTrackColour {
+        /// The track.
+        track: Id<Track>,
+        /// The colour that was set.
+        to: Colour,
+        /// The colour that was overwritten.
+        from: Colour,
+    },
+    /// The setting of the velocity of a note.
+    SetVelocity {
+        /// The note.
+        note: note::Path,
+        /// The velocity that was set.
+        to: Velocity,
+        /// The velocity that was overwritten.
+        from: Velocity,
+    },
+    /// The moving of linked content back into the clip it was moved from.
+    UnlinkContent {
+        /// The clip.
+        clip: clip::Path,
+        /// The link to the content.
+        link: clip::Link,
+    },
 }
 
 impl FromIterator1<HistoryEntry> for HistoryEntry {
@@ -79,3 +293,1307 @@ impl FromIterator1<HistoryEntry> for HistoryEntry {
         HistoryEntry::Cluster(items.into_iter1().collect1())
     }
 }
+
+impl HistoryEntry {
+    /// Returns a cluster of some entries followed by this one.
+    ///
+    /// If there are no preceding entries, the entry itself is returned.
+    pub(super) fn preceded_by(self, entries: Vec<HistoryEntry>) -> HistoryEntry {
+        let mut entries = entries.into_iter();
+
+        let Some(first) = entries.next() else {
+            return self;
+        };
+
+        let mut cluster = Vec1::from_one(first);
+
+        for entry in entries {
+            cluster.push(entry);
+        }
+
+        cluster.push(self);
+
+        HistoryEntry::Cluster(cluster)
+    }
+}
+
+/// A failed reversion of a [history entry](HistoryEntry).
+///
+/// The project is left as it was before the reversion was attempted,
+/// unless the error is an [incomplete rollback](Error::IncompleteRollback),
+/// in which case the entry only describes what is left to revert.
+#[derive(Debug)]
+pub(crate) struct Failure {
+    /// Why the entry could not be reverted.
+    pub error: Error,
+    /// The entry that could not be reverted.
+    ///
+    /// Boxed due to `clippy::result_large_err`.
+    pub entry: Box<HistoryEntry>,
+}
+
+/// What happened to an entry of a [cluster](HistoryEntry::Cluster) when reverting the cluster.
+#[derive(Debug)]
+enum ClusterOutcome {
+    /// The entry was reverted into the contained entry.
+    Reverted(HistoryEntry),
+    /// The entry could not be reverted.
+    Failed(HistoryEntry),
+    /// The entry was not reverted since an earlier entry could not be.
+    Skipped(HistoryEntry),
+}
+
+impl Project {
+    /// Reverts a performed edit.
+    ///
+    /// Returns the entry that reverts the reversion, i.e. the one that redoes the edit.
+    /// If the edit could not be reverted, the project is left unchanged.
+    #[remain::check]
+    #[expect(clippy::too_many_lines, reason = "`HistoryEntry` is a large enum")]
+    pub(super) fn undo(&mut self, entry: HistoryEntry) -> Result<HistoryEntry, Failure> {
+        #[sorted]
+        match entry {
+            HistoryEntry::AddTrack(id) => {
+                let Some((index, _, track)) = self.tracks.shift_remove_full(&id) else {
+                    return Err(Failure {
+                        error: Error::NonExistentTrack,
+                        entry: Box::new(HistoryEntry::AddTrack(id)),
+                    });
+                };
+
+                Ok(HistoryEntry::DeleteTrack { index, track })
+            }
+            HistoryEntry::Cluster(entries) => {
+                // The entries are reverted in the opposite order that they were performed in.
+                // Hence, the reversions will be reverted in the original order.
+                let mut error = None;
+
+                let outcomes = entries
+                    .into_iter1()
+                    .rev()
+                    .map(|entry| {
+                        if error.is_some() {
+                            return ClusterOutcome::Skipped(entry);
+                        }
+
+                        match self.undo(entry) {
+                            Ok(reversion) => ClusterOutcome::Reverted(reversion),
+                            Err(failure) => {
+                                error = Some(failure.error);
+                                ClusterOutcome::Failed(*failure.entry)
+                            }
+                        }
+                    })
+                    .collect1::<Vec1<_>>();
+
+                let Some(error) = error else {
+                    return Ok(outcomes
+                        .into_iter1()
+                        .map(ClusterOutcome::into_entry)
+                        .collect1());
+                };
+
+                // The entries that were reverted are performed again, in the original order,
+                // so that the cluster is reverted either fully or not at all.
+                // In the original order, the skipped entries come first, then the failed one,
+                // and then the reverted ones, so the first entry was not reverted.
+                let (reverted, first) = outcomes.into_rtail_and_head();
+                let mut entries = Vec1::from_one(first.into_entry());
+                let mut rollback = None;
+
+                for outcome in reverted.into_iter().rev() {
+                    match outcome {
+                        ClusterOutcome::Reverted(reversion) => match self.undo(reversion) {
+                            Ok(entry) => entries.push(entry),
+                            // The entry stays reverted, so it is no longer part of the cluster.
+                            Err(failure) => {
+                                rollback.get_or_insert(failure.error);
+                            }
+                        },
+                        ClusterOutcome::Failed(entry) | ClusterOutcome::Skipped(entry) => {
+                            entries.push(entry);
+                        }
+                    }
+                }
+
+                Err(Failure {
+                    error: incomplete_rollback(error, rollback),
+                    entry: Box::new(HistoryEntry::Cluster(entries)),
+                })
+            }
+            HistoryEntry::DeleteClip { track, start, clip } => {
+                let Some(track_reference) = self.track_mut(track) else {
+                    return Err(Failure {
+                        error: Error::NonExistentTrack,
+                        entry: Box::new(HistoryEntry::DeleteClip { track, start, clip }),
+                    });
+                };
+
+                match track_reference.try_insert_clip(start, clip) {
+                    Ok(path) => Ok(HistoryEntry::InsertClip(path)),
+                    Err(error) => Err(Failure {
+                        error: error.kind.into(),
+                        entry: Box::new(HistoryEntry::DeleteClip {
+                            track,
+                            start,
+                            clip: *error.clip,
+                        }),
+                    }),
+                }
+            }
+            HistoryEntry::DeleteNote {
+                clip,
+                instant,
+                pitch,
+                note,
+            } => {
+                let path = note::Path {
+                    clip,
+                    note: note.id(),
+                };
+
+                let group = self
+                    .content_mut(clip)
+                    .ok_or(Error::NonExistentClip)
+                    .and_then(|(_, content)| content.as_notes_mut().ok_or(Error::NonNoteCLip));
+
+                let result = match group {
+                    Ok(group) => group
+                        .try_insert_or_return(instant, pitch, note)
+                        .map_err(|(error, note)| (Error::from(error), *note)),
+                    Err(error) => Err((error, note)),
+                };
+
+                match result {
+                    Ok(()) => Ok(HistoryEntry::InsertNote(path)),
+                    Err((error, note)) => Err(Failure {
+                        error,
+                        entry: Box::new(HistoryEntry::DeleteNote {
+                            clip,
+                            instant,
+                            pitch,
+                            note,
+                        }),
+                    }),
+                }
+            }
+            HistoryEntry::DeleteTrack { index, track } => {
+                let id = track.id();
+                let index = min(index, self.tracks.len());
+
+                self.tracks.shift_insert(index, id, track);
+
+                Ok(HistoryEntry::AddTrack(id))
+            }
+            HistoryEntry::InsertClip(path) => {
+                let Some((start, clip)) = self.remove_clip(path) else {
+                    return Err(Failure {
+                        error: Error::NonExistentClip,
+                        entry: Box::new(HistoryEntry::InsertClip(path)),
+                    });
+                };
+
+                Ok(HistoryEntry::DeleteClip {
+                    track: path.track,
+                    start,
+                    clip,
+                })
+            }
+            HistoryEntry::InsertNote(path) => {
+                let removed = self
+                    .content_mut(path.clip)
+                    .ok_or(Error::NonExistentClip)
+                    .and_then(|(_, content)| content.as_notes_mut().ok_or(Error::NonNoteCLip))
+                    .and_then(|group| group.remove(path.note).ok_or(Error::NonExistentNote));
+
+                match removed {
+                    Ok((instant, pitch, note)) => Ok(HistoryEntry::DeleteNote {
+                        clip: path.clip,
+                        instant,
+                        pitch,
+                        note,
+                    }),
+                    Err(error) => Err(Failure {
+                        error,
+                        entry: Box::new(HistoryEntry::InsertNote(path)),
+                    }),
+                }
+            }
+            HistoryEntry::LinkContent { clip, link } => {
+                let Some(content) = self.linked_contents.remove(&link) else {
+                    return Err(Failure {
+                        error: Error::NonExistentClip,
+                        entry: Box::new(HistoryEntry::LinkContent { clip, link }),
+                    });
+                };
+
+                let Some((_, clip_reference)) = self.clip_mut(clip) else {
+                    self.linked_contents.insert(link, content);
+
+                    return Err(Failure {
+                        error: Error::NonExistentClip,
+                        entry: Box::new(HistoryEntry::LinkContent { clip, link }),
+                    });
+                };
+
+                *clip_reference.content_mut() = content;
+
+                Ok(HistoryEntry::UnlinkContent { clip, link })
+            }
+            HistoryEntry::MoveClip {
+                original_track,
+                original_position,
+                new_path,
+            } => {
+                // A failed move puts the clip back where it was.
+                self.move_clip(
+                    new_path,
+                    original_track,
+                    original_position,
+                    OverlapPolicy::Reject,
+                )
+                .map_err(|error| Failure {
+                    error,
+                    entry: Box::new(HistoryEntry::MoveClip {
+                        original_track,
+                        original_position,
+                        new_path,
+                    }),
+                })
+            }
+            HistoryEntry::MoveNote { note, to, from } => {
+                let placed = self
+                    .content_mut(note.clip)
+                    .ok_or(Error::NonExistentClip)
+                    .and_then(|(_, content)| content.as_notes_mut().ok_or(Error::NonNoteCLip))
+                    .and_then(|group| group.place(note.note, from).map_err(Error::from));
+
+                match placed {
+                    Ok(_) => Ok(HistoryEntry::MoveNote {
+                        note,
+                        to: from,
+                        from: to,
+                    }),
+                    Err(error) => Err(Failure {
+                        error,
+                        entry: Box::new(HistoryEntry::MoveNote { note, to, from }),
+                    }),
+                }
+            }
+            HistoryEntry::MoveTrack { track, to, from } => {
+                let Some(index) = self.tracks.get_index_of(&track) else {
+                    return Err(Failure {
+                        error: Error::NonExistentTrack,
+                        entry: Box::new(HistoryEntry::MoveTrack { track, to, from }),
+                    });
+                };
+                let from = min(from, self.tracks.len().saturating_sub(1));
+
+                self.tracks.move_index(index, from);
+
+                Ok(HistoryEntry::MoveTrack {
+                    track,
+                    to: from,
+                    from: to,
+                })
+            }
+            HistoryEntry::RenameClip { clip, to, from } => {
+                let Some((_, clip_reference)) = self.clip_mut(clip) else {
+                    return Err(Failure {
+                        error: Error::NonExistentClip,
+                        entry: Box::new(HistoryEntry::RenameClip { clip, to, from }),
+                    });
+                };
+
+                clip_reference.set_name(from.clone());
+
+                Ok(HistoryEntry::RenameClip {
+                    clip,
+                    to: from,
+                    from: to,
+                })
+            }
+            HistoryEntry::RenameTrack { track, to, from } => {
+                let Some(track_reference) = self.track_mut(track) else {
+                    return Err(Failure {
+                        error: Error::NonExistentTrack,
+                        entry: Box::new(HistoryEntry::RenameTrack { track, to, from }),
+                    });
+                };
+
+                track_reference.set_name(from.clone());
+
+                Ok(HistoryEntry::RenameTrack {
+                    track,
+                    to: from,
+                    from: to,
+                })
+            }
+            HistoryEntry::ReplaceContent { clip, content } => {
+                let Some((_, clip_reference)) = self.clip_mut(clip) else {
+                    return Err(Failure {
+                        error: Error::NonExistentClip,
+                        entry: Box::new(HistoryEntry::ReplaceContent { clip, content }),
+                    });
+                };
+
+                let content = replace(clip_reference.content_mut(), content);
+
+                Ok(HistoryEntry::ReplaceContent { clip, content })
+            }
+            HistoryEntry::ReplaceCurve {
+                note,
+                dimension,
+                curve,
+            } => {
+                let note_reference = match self.note_mut(note) {
+                    Ok(note_reference) => note_reference,
+                    Err(error) => {
+                        return Err(Failure {
+                            error,
+                            entry: Box::new(HistoryEntry::ReplaceCurve {
+                                note,
+                                dimension,
+                                curve,
+                            }),
+                        });
+                    }
+                };
+
+                let curve = replace(note_reference.expression_mut().curve_mut(dimension), curve);
+
+                Ok(HistoryEntry::ReplaceCurve {
+                    note,
+                    dimension,
+                    curve,
+                })
+            }
+            HistoryEntry::SetArticulation { note, to, from } => {
+                let note_reference = match self.note_mut(note) {
+                    Ok(note_reference) => note_reference,
+                    Err(error) => {
+                        return Err(Failure {
+                            error,
+                            entry: Box::new(HistoryEntry::SetArticulation { note, to, from }),
+                        });
+                    }
+                };
+
+                note_reference.set_articulation(from);
+
+                Ok(HistoryEntry::SetArticulation {
+                    note,
+                    to: from,
+                    from: to,
+                })
+            }
+            HistoryEntry::SetAutomationPoint {
+                track,
+                target,
+                at,
+                to,
+                from,
+            } => {
+                let Some(track_reference) = self.track_mut(track) else {
+                    return Err(Failure {
+                        error: Error::NonExistentTrack,
+                        entry: Box::new(HistoryEntry::SetAutomationPoint {
+                            track,
+                            target,
+                            at,
+                            to,
+                            from,
+                        }),
+                    });
+                };
+
+                track_reference.automation_mut().set_point(target, at, from);
+
+                Ok(HistoryEntry::SetAutomationPoint {
+                    track,
+                    target,
+                    at,
+                    to: from,
+                    from: to,
+                })
+            }
+            HistoryEntry::SetClipColour { clip, to, from } => {
+                let Some((_, clip_reference)) = self.clip_mut(clip) else {
+                    return Err(Failure {
+                        error: Error::NonExistentClip,
+                        entry: Box::new(HistoryEntry::SetClipColour { clip, to, from }),
+                    });
+                };
+
+                clip_reference.set_colour(from);
+
+                Ok(HistoryEntry::SetClipColour {
+                    clip,
+                    to: from,
+                    from: to,
+                })
+            }
+            HistoryEntry::SetDrumHit {
+                clip,
+                lane,
+                step,
+                to,
+                from,
+            } => {
+                let lane_reference = self
+                    .content_mut(clip)
+                    .ok_or(Error::NonExistentClip)
+                    .and_then(|(_, content)| content.as_drums_mut().ok_or(Error::NonDrumClip))
+                    .and_then(|drums| drums.lane_mut(lane).ok_or(Error::NonExistentLane));
+
+                match lane_reference {
+                    Ok(lane_reference) => {
+                        lane_reference.set_hit(step, from);
+                    }
+                    Err(error) => {
+                        return Err(Failure {
+                            error,
+                            entry: Box::new(HistoryEntry::SetDrumHit {
+                                clip,
+                                lane,
+                                step,
+                                to,
+                                from,
+                            }),
+                        });
+                    }
+                }
+
+                Ok(HistoryEntry::SetDrumHit {
+                    clip,
+                    lane,
+                    step,
+                    to: from,
+                    from: to,
+                })
+            }
+            HistoryEntry::SetExpressionPoint {
+                note,
+                dimension,
+                at,
+                to,
+                from,
+            } => {
+                let note_reference = match self.note_mut(note) {
+                    Ok(note_reference) => note_reference,
+                    Err(error) => {
+                        return Err(Failure {
+                            error,
+                            entry: Box::new(HistoryEntry::SetExpressionPoint {
+                                note,
+                                dimension,
+                                at,
+                                to,
+                                from,
+                            }),
+                        });
+                    }
+                };
+
+                note_reference
+                    .expression_mut()
+                    .curve_mut(dimension)
+                    .set_point(at, from);
+
+                Ok(HistoryEntry::SetExpressionPoint {
+                    note,
+                    dimension,
+                    at,
+                    to: from,
+                    from: to,
+                })
+            }
+            HistoryEntry::SetKey { at, to, from } => {
+                self.key.set_or_remove(at, from);
+
+                Ok(HistoryEntry::SetKey {
+                    at,
+                    to: from,
+                    from: to,
+                })
+            }
+            HistoryEntry::SetLoopRegion { to, from } => {
+                self.loop_region = from;
+
+                Ok(HistoryEntry::SetLoopRegion { to: from, from: to })
+            }
+            HistoryEntry::SetMarker { at, to, from } => {
+                if let Some(name) = from.clone() {
+                    self.markers.insert(at, name);
+                } else {
+                    self.markers.remove(&at);
+                }
+
+                Ok(HistoryEntry::SetMarker {
+                    at,
+                    to: from,
+                    from: to,
+                })
+            }
+            HistoryEntry::SetMixing { track, to, from } => {
+                let Some(track_reference) = self.track_mut(track) else {
+                    return Err(Failure {
+                        error: Error::NonExistentTrack,
+                        entry: Box::new(HistoryEntry::SetMixing { track, to, from }),
+                    });
+                };
+
+                *track_reference.mixing_mut() = from;
+
+                Ok(HistoryEntry::SetMixing {
+                    track,
+                    to: from,
+                    from: to,
+                })
+            }
+            HistoryEntry::SetTempo { at, to, from } => {
+                self.tempo.set_or_remove(at, from);
+
+                Ok(HistoryEntry::SetTempo {
+                    at,
+                    to: from,
+                    from: to,
+                })
+            }
+            HistoryEntry::SetTempoRamp { at, to, from } => {
+                self.tempo.set_ramp(at, from);
+
+                Ok(HistoryEntry::SetTempoRamp {
+                    at,
+                    to: from,
+                    from: to,
+                })
+            }
+            HistoryEntry::SetTimeSignature { at, to, from } => {
+                self.time_signature.set_or_remove(at, from);
+
+                Ok(HistoryEntry::SetTimeSignature {
+                    at,
+                    to: from,
+                    from: to,
+                })
+            }
+            HistoryEntry::SetTrackColour { track, to, from } => {
+                let Some(track_reference) = self.track_mut(track) else {
+                    return Err(Failure {
+                        error: Error::NonExistentTrack,
+                        entry: Box::new(HistoryEntry::SetTrackColour { track, to, from }),
+                    });
+                };
+
+                track_reference.set_colour(from);
+
+                Ok(HistoryEntry::SetTrackColour {
+                    track,
+                    to: from,
+                    from: to,
+                })
+            }
+            HistoryEntry::SetVelocity { note, to, from } => {
+                let note_reference = match self.note_mut(note) {
+                    Ok(note_reference) => note_reference,
+                    Err(error) => {
+                        return Err(Failure {
+                            error,
+                            entry: Box::new(HistoryEntry::SetVelocity { note, to, from }),
+                        });
+                    }
+                };
+
+                note_reference.set_velocity(from);
+
+                Ok(HistoryEntry::SetVelocity {
+                    note,
+                    to: from,
+                    from: to,
+                })
+            }
+            HistoryEntry::UnlinkContent { clip, link } => {
+                let Some((_, clip_reference)) = self.clip_mut(clip) else {
+                    return Err(Failure {
+                        error: Error::NonExistentClip,
+                        entry: Box::new(HistoryEntry::UnlinkContent { clip, link }),
+                    });
+                };
+
+                let duration = clip_reference.duration();
+                let content = replace(
+                    clip_reference.content_mut(),
+                    clip::Content::Linked { link, duration },
+                );
+
+                self.linked_contents.insert(link, content);
+
+                Ok(HistoryEntry::LinkContent { clip, link })
+            }
+        }
+    }
+
+    /// Reverts entries that were just performed, in the opposite order,
+    /// since the edit that they are part of failed with `error`.
+    ///
+    /// Returns the error to report for the edit.
+    pub(super) fn roll_back(&mut self, entries: Vec<HistoryEntry>, error: Error) -> Error {
+        let mut rollback = None;
+
+        for entry in entries.into_iter().rev() {
+            if let Err(failure) = self.undo(entry) {
+                rollback.get_or_insert(failure.error);
+            }
+        }
+
+        incomplete_rollback(error, rollback)
+    }
+}
+
+impl ClusterOutcome {
+    /// Returns the entry, whatever happened to it.
+    fn into_entry(self) -> HistoryEntry {
+        match self {
+            ClusterOutcome::Reverted(entry)
+            | ClusterOutcome::Failed(entry)
+            | ClusterOutcome::Skipped(entry) => entry,
+        }
+    }
+}
+
+/// Adds the error of a failed rollback, if any, to the error that caused the rollback.
+fn incomplete_rollback(error: Error, rollback: Option<Error>) -> Error {
+    match rollback {
+        Some(rollback) => Error::IncompleteRollback {
+            error: Box::new(error),
+            rollback: Box::new(rollback),
+        },
+        None => error,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::NonZeroRatio;
+    use crate::Ratio;
+    use crate::Selectable;
+    use crate::metre::Duration;
+    use crate::metre::NonZeroDuration;
+    use crate::note::Interval;
+    use crate::note::PitchClass;
+    use crate::project::Edit;
+    use crate::project::track::automation::Shape;
+    use crate::select::Selection;
+    use anyhow::Context as _;
+    use anyhow::bail;
+    use anyhow::ensure;
+    use mitsein::hash_set1::HashSet1;
+    use non_zero::non_zero;
+
+    /// Returns the instant a number of whole notes after the start.
+    fn whole_notes(count: u64) -> Instant {
+        Instant {
+            since_start: Duration {
+                whole_notes: Ratio::integer(count),
+            },
+        }
+    }
+
+    /// Returns a selection of a single item.
+    fn selecting(item: Selectable) -> Selection {
+        let mut selection = Selection::default();
+        selection.push(item);
+        selection
+    }
+
+    /// Adds a track to the project.
+    fn add_track(project: &mut Project) -> anyhow::Result<Id<Track>> {
+        let mut selection = Selection::default();
+
+        project.edit(
+            Edit::AddTrack,
+            Instant::START,
+            &mut selection,
+            OverlapPolicy::Reject,
+        )?;
+
+        selection.top_track().context("the track was not selected")
+    }
+
+    /// Adds a note clip to a track.
+    fn add_note_group(
+        project: &mut Project,
+        track: Id<Track>,
+        position: Instant,
+    ) -> anyhow::Result<clip::Path> {
+        let mut selection = selecting(Selectable::Track(track));
+
+        project.edit(
+            Edit::AddNoteGroup,
+            position,
+            &mut selection,
+            OverlapPolicy::Reject,
+        )?;
+
+        selection.top_clip().context("the clip was not selected")
+    }
+
+    /// Adds a note at the start of a note clip.
+    fn add_note(project: &mut Project, clip: clip::Path) -> anyhow::Result<note::Path> {
+        let entry = project.edit(
+            Edit::AddNote {
+                position: Instant::START,
+                pitch: Pitch::LOWEST + Interval::from_semitones(60),
+                duration: NonZeroDuration::QUARTER,
+            },
+            Instant::START,
+            &mut selecting(Selectable::Clip(clip)),
+            OverlapPolicy::Reject,
+        )?;
+
+        let HistoryEntry::InsertNote(note) = entry else {
+            bail!("the note was not inserted");
+        };
+
+        Ok(note)
+    }
+
+    /// Performs an edit and checks that undoing and then redoing it restores the project exactly.
+    fn check_undo_redo(
+        project: &mut Project,
+        edit: Edit,
+        cursor: Instant,
+        mut selection: Selection,
+    ) -> anyhow::Result<()> {
+        let before = toml::to_string(&*project)?;
+
+        let entry = project.edit(edit, cursor, &mut selection, OverlapPolicy::Reject)?;
+
+        let after = toml::to_string(&*project)?;
+
+        ensure!(before != after, "the edit did not change the project");
+
+        let reversion = project.undo(entry).map_err(|failure| failure.error)?;
+        let undone = toml::to_string(&*project)?;
+
+        ensure!(
+            undone == before,
+            "undoing did not restore the project: ```\n{before}```\nbut gave: ```\n{undone}```"
+        );
+
+        project.undo(reversion).map_err(|failure| failure.error)?;
+        let redone = toml::to_string(&*project)?;
+
+        ensure!(
+            redone == after,
+            "redoing did not restore the project: ```\n{after}```\nbut gave: ```\n{redone}```"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn undo_redo_adding_a_track() -> anyhow::Result<()> {
+        let mut project = Project::default();
+
+        check_undo_redo(
+            &mut project,
+            Edit::AddTrack,
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_deleting_a_track() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        add_track(&mut project)?;
+        let track = add_track(&mut project)?;
+        add_note_group(&mut project, track, Instant::START)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::DeleteTracks(HashSet1::from_one(track)),
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_adding_a_clip() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::AddNoteGroup,
+            whole_notes(1),
+            selecting(Selectable::Track(track)),
+        )
+    }
+
+    #[test]
+    fn undo_redo_moving_a_clip() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+        let other_track = add_track(&mut project)?;
+        let clip = add_note_group(&mut project, track, Instant::START)?;
+        add_note(&mut project, clip)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::MoveClip {
+                clip,
+                track: other_track,
+                position: whole_notes(2),
+            },
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_deleting_a_clip() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+        let clip = add_note_group(&mut project, track, Instant::START)?;
+        add_note(&mut project, clip)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::DeleteClips(HashSet1::from_one(clip)),
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_adding_a_note() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+        let clip = add_note_group(&mut project, track, Instant::START)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::AddNote {
+                position: Instant::START,
+                pitch: Pitch::LOWEST + Interval::from_semitones(62),
+                duration: NonZeroDuration::QUARTER,
+            },
+            Instant::START,
+            selecting(Selectable::Clip(clip)),
+        )
+    }
+
+    #[test]
+    fn undo_redo_deleting_a_note() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+        let clip = add_note_group(&mut project, track, Instant::START)?;
+        let note = add_note(&mut project, clip)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::DeleteNotes(HashSet1::from_one(note)),
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_setting_the_key() -> anyhow::Result<()> {
+        let mut project = Project::default();
+
+        check_undo_redo(
+            &mut project,
+            Edit::SetKey(Key {
+                tonic: PitchClass::C,
+                ..Key::default()
+            }),
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn keep_a_cluster_that_cannot_be_undone() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+        let missing_track = add_track(&mut Project::default())?;
+
+        let before = toml::to_string(&project)?;
+
+        // The track is removed first, after which the missing track cannot be.
+        let mut entries = Vec1::from_one(HistoryEntry::AddTrack(missing_track));
+        entries.push(HistoryEntry::AddTrack(track));
+
+        let Err(failure) = project.undo(HistoryEntry::Cluster(entries)) else {
+            bail!("a cluster with a missing track was undone");
+        };
+
+        let after = toml::to_string(&project)?;
+
+        ensure!(
+            before == after,
+            "the failed undo changed the project to: ```\n{after}```"
+        );
+        ensure!(
+            matches!(failure.error, Error::NonExistentTrack),
+            "the wrong error was reported: {}",
+            failure.error
+        );
+
+        let HistoryEntry::Cluster(entries) = *failure.entry else {
+            bail!("the cluster was not given back");
+        };
+
+        ensure!(
+            entries.len().get() == 2,
+            "the cluster was not given back intact"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn undo_redo_setting_the_mixing() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::SetMixing {
+                track,
+                mixing: Mixing {
+                    muted: true,
+                    ..Mixing::default()
+                },
+            },
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_setting_the_time_signature() -> anyhow::Result<()> {
+        let mut project = Project::default();
+
+        check_undo_redo(
+            &mut project,
+            Edit::SetTimeSignature(TimeSignature::from([non_zero!(3), non_zero!(4)])),
+            whole_notes(4),
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_setting_a_ramped_tempo() -> anyhow::Result<()> {
+        let mut project = Project::default();
+
+        check_undo_redo(
+            &mut project,
+            Edit::SetTempo {
+                tempo: Tempo::new(NonZeroRatio::integer(non_zero!(90))),
+                ramp: Some(Ramp::Linear),
+            },
+            whole_notes(4),
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_linking_a_clip() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+        let clip = add_note_group(&mut project, track, Instant::START)?;
+        add_note(&mut project, clip)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::LinkClip,
+            whole_notes(16),
+            selecting(Selectable::Clip(clip)),
+        )
+    }
+
+    #[test]
+    fn undo_redo_setting_a_drum_hit() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+
+        let mut selection = selecting(Selectable::Track(track));
+        project.edit(
+            Edit::AddDrums {
+                step_duration: NonZeroDuration::QUARTER,
+            },
+            Instant::START,
+            &mut selection,
+            OverlapPolicy::Reject,
+        )?;
+        let clip = selection.top_clip().context("the clip was not selected")?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::SetDrumHit {
+                clip,
+                lane: 0,
+                step: 2,
+                velocity: Some(Velocity::MAX),
+            },
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_setting_a_velocity() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+        let clip = add_note_group(&mut project, track, Instant::START)?;
+        let note = add_note(&mut project, clip)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::SetVelocity {
+                note,
+                velocity: Velocity::MIN,
+            },
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_setting_an_articulation() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+        let clip = add_note_group(&mut project, track, Instant::START)?;
+        let note = add_note(&mut project, clip)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::SetArticulation {
+                note,
+                articulation: Some(Articulation::Staccato),
+            },
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_setting_an_expression_point() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+        let clip = add_note_group(&mut project, track, Instant::START)?;
+        let note = add_note(&mut project, clip)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::SetExpressionPoint {
+                note,
+                dimension: Dimension::PitchBend,
+                at: relative::Instant::default(),
+                value: Some(100),
+            },
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_setting_an_automation_point() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::SetAutomationPoint {
+                track,
+                target: Target::Gain,
+                at: whole_notes(1),
+                point: Some(Point {
+                    value: -6.0,
+                    shape: Shape::default(),
+                }),
+            },
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_resizing_a_clip() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+        let clip = add_note_group(&mut project, track, Instant::START)?;
+        add_note(&mut project, clip)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::ResizeClip {
+                clip,
+                duration: NonZeroDuration::QUARTER,
+            },
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_repeating_a_clip() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+        let clip = add_note_group(&mut project, track, Instant::START)?;
+        add_note(&mut project, clip)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::Repeat {
+                count: non_zero!(2),
+            },
+            Instant::START,
+            selecting(Selectable::Clip(clip)),
+        )
+    }
+
+    #[test]
+    fn undo_redo_moving_a_note() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+        let clip = add_note_group(&mut project, track, Instant::START)?;
+        let note = add_note(&mut project, clip)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::MoveNotes {
+                by_time: whole_notes(1).since_start,
+                earlier: false,
+                by_pitch: Interval::from_semitones(2),
+            },
+            Instant::START,
+            selecting(Selectable::Note(note)),
+        )
+    }
+
+    #[test]
+    fn undo_redo_renaming_a_track() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::RenameTrack {
+                track,
+                name: ArcStr::from("bass"),
+            },
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_recolouring_a_track() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::SetTrackColour {
+                track,
+                colour: Colour::LIME,
+            },
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_moving_a_track() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+        add_track(&mut project)?;
+
+        // The tracks are told apart by their names, since their ids are not serialised.
+        project.edit(
+            Edit::RenameTrack {
+                track,
+                name: ArcStr::from("bass"),
+            },
+            Instant::START,
+            &mut Selection::default(),
+            OverlapPolicy::Reject,
+        )?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::MoveTrack { track, to_index: 1 },
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_renaming_a_clip() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+        let clip = add_note_group(&mut project, track, Instant::START)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::RenameClip {
+                clip,
+                name: ArcStr::from("chorus"),
+            },
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_recolouring_a_clip() -> anyhow::Result<()> {
+        let mut project = Project::default();
+        let track = add_track(&mut project)?;
+        let clip = add_note_group(&mut project, track, Instant::START)?;
+
+        check_undo_redo(
+            &mut project,
+            Edit::SetClipColour {
+                clip,
+                colour: Colour::CYAN,
+            },
+            Instant::START,
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_adding_a_marker() -> anyhow::Result<()> {
+        let mut project = Project::default();
+
+        check_undo_redo(
+            &mut project,
+            Edit::AddMarker,
+            whole_notes(2),
+            Selection::default(),
+        )
+    }
+
+    #[test]
+    fn undo_redo_setting_the_loop_region() -> anyhow::Result<()> {
+        let mut project = Project::default();
+
+        check_undo_redo(
+            &mut project,
+            Edit::SetLoopRegion {
+                start: whole_notes(1),
+                end: whole_notes(3),
+            },
+            Instant::START,
+            Selection::default(),
+        )
+    }
+}
@@ -5,9 +5,15 @@ use crate::metre::Instant;
 use crate::popup;
 use crate::project::Edit;
 use crate::project::HistoryEntry;
+use crate::project::edit::Error;
+use crate::project::history::Failure;
+use crate::project::track::OverlapPolicy;
 use crate::select::Selection;
 use anyhow::Context as _;
 use getset::Getters;
+use std::fs::canonicalize;
+use std::fs::copy;
+use std::fs::create_dir_all;
 use std::fs::read_to_string;
 use std::fs::write;
 use std::path::Path;
@@ -20,9 +26,10 @@ pub struct Manager {
     #[get = "pub"]
     project: Project,
 
-    // TODO: undoing
     /// The history of edits.
     history: Vec<HistoryEntry>,
+    /// The reversions of undone edits, the most recently undone last.
+    undone: Vec<HistoryEntry>,
 
     // TODO: Add a format field.
     /// Where to save the project to.
@@ -36,24 +43,67 @@ impl Manager {
         action: Edit,
         cursor: Instant,
         selection: &mut Selection,
+        overlap_policy: OverlapPolicy,
     ) -> anyhow::Result<()> {
-        let entry = self.project.edit(action, cursor, selection)?;
+        let entry = self
+            .project
+            .edit(action, cursor, selection, overlap_policy)?;
         self.history.push(entry);
+        self.undone.clear();
 
         Ok(())
     }
 
+    /// Undoes the last edit.
+    ///
+    /// If the edit cannot be undone, it is kept in the history.
+    pub(crate) fn undo(&mut self) -> anyhow::Result<()> {
+        let entry = self.history.pop().ok_or(Error::NothingToUndo)?;
+
+        match self.project.undo(entry) {
+            Ok(reversion) => {
+                self.undone.push(reversion);
+                Ok(())
+            }
+            Err(Failure { error, entry }) => {
+                self.history.push(*entry);
+                Err(error.into())
+            }
+        }
+    }
+
+    /// Redoes the last undone edit.
+    ///
+    /// If the edit cannot be redone, it is kept among the undone edits.
+    pub(crate) fn redo(&mut self) -> anyhow::Result<()> {
+        let reversion = self.undone.pop().ok_or(Error::NothingToRedo)?;
+
+        match self.project.undo(reversion) {
+            Ok(entry) => {
+                self.history.push(entry);
+                Ok(())
+            }
+            Err(Failure { error, entry }) => {
+                self.undone.push(*entry);
+                Err(error.into())
+            }
+        }
+    }
+
     /// Read a project from a file.
     pub(crate) fn open(path: Arc<Path>) -> anyhow::Result<Manager> {
         let content =
             read_to_string(&path).with_context(|| format!("reading from {}", path.display()))?;
 
-        let project: Project =
+        let mut project: Project =
             toml::from_str(&content).with_context(|| format!("parsing {}", path.display()))?;
 
+        project.directory = path.parent().map(Arc::from);
+
         Ok(Manager {
             project,
             history: Vec::new(),
+            undone: Vec::new(),
             save_location: Some(path),
         })
     }
@@ -71,6 +121,8 @@ impl Manager {
     }
 
     /// Save the project to a given save location.
+    ///
+    /// If the project moves to another directory, the files of linked audio clips are copied along.
     pub(crate) fn save_as(&mut self, mut path: Arc<Path>) -> anyhow::Result<()> {
         let string = toml::to_string(&self.project)?;
 
@@ -80,10 +132,44 @@ impl Manager {
             path = path.join(file_name).into();
         }
 
+        let directory: Option<Arc<Path>> = path.parent().map(Arc::from);
+        let new_directory = directory.as_deref().unwrap_or(Path::new(""));
+
+        for file in self.project.linked_files() {
+            copy_linked_file(
+                &self.project.directory().join(file),
+                &new_directory.join(file),
+            )?;
+        }
+
         write(&path, string).with_context(|| format!("writing to {}", path.display()))?;
 
-        self.save_location.get_or_insert(path);
+        self.project.directory = directory;
+        self.save_location = Some(path);
 
         Ok(())
     }
 }
+
+/// Copies the file of a linked audio clip to a new location.
+///
+/// Files that are missing are skipped, as they are reported when the project is rendered.
+fn copy_linked_file(from: &Path, to: &Path) -> anyhow::Result<()> {
+    let Ok(source) = canonicalize(from) else {
+        return Ok(());
+    };
+
+    // Copying a file onto itself would truncate it.
+    if canonicalize(to).is_ok_and(|destination| destination == source) {
+        return Ok(());
+    }
+
+    if let Some(parent) = to.parent() {
+        create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+    }
+
+    copy(&source, to)
+        .with_context(|| format!("copying {} to {}", source.display(), to.display()))?;
+
+    Ok(())
+}
@@ -3,6 +3,7 @@
 pub mod track;
 
 mod bar;
+mod clipboard;
 mod edit;
 mod history;
 mod manager;
@@ -10,6 +11,7 @@ mod renderer;
 mod serial;
 mod workspace;
 
+pub use clipboard::Clipboard;
 pub use edit::Edit;
 pub use manager::Manager;
 
@@ -23,24 +25,31 @@ pub(crate) use workspace::workspace;
 
 use crate::Id;
 use crate::NonZeroRatio;
+use crate::audio::Cue;
 use crate::metre::Changing;
+use crate::metre::ChangingTempo;
 use crate::metre::Instant;
 use crate::metre::NonZeroDuration;
+use crate::metre::NonZeroPeriod;
 use crate::metre::TimeContext;
 use crate::metre::TimeSignature;
 use crate::note::Key;
 use crate::project::track::Clip;
 use crate::project::track::clip;
-use crate::time::Tempo;
+use crate::ui::Colour;
 use arcstr::ArcStr;
 use arcstr::literal;
 use getset::CloneGetters;
+use getset::CopyGetters;
 use getset::Getters;
 use indexmap::IndexMap;
 use non_zero::non_zero;
 use serde::Deserialize;
 use serde::Serialize;
 use serial::Serial;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
 
 /// The label for the button to add new tracks.
 const ADD_TRACK_LABEL: ArcStr = literal!("+");
@@ -48,6 +57,14 @@ const ADD_TRACK_LABEL: ArcStr = literal!("+");
 const ADD_TRACK_DESCRIPTION: ArcStr = literal!("add track");
 /// The default name for tracks.
 const DEFAULT_TRACK_NAME: ArcStr = literal!("a track");
+/// The default colour for tracks.
+const DEFAULT_TRACK_COLOUR: Colour = Colour::SILVER;
+/// The label for the button to add new markers.
+const ADD_MARKER_LABEL: ArcStr = literal!("+");
+/// The description for the button to add new markers.
+const ADD_MARKER_DESCRIPTION: ArcStr = literal!("add marker");
+/// The default name for markers.
+const DEFAULT_MARKER_NAME: ArcStr = literal!("a marker");
 
 /// The default duration for empty note groups.
 const DEFAULT_NOTES_DURATION: NonZeroDuration = NonZeroDuration {
@@ -56,25 +73,38 @@ const DEFAULT_NOTES_DURATION: NonZeroDuration = NonZeroDuration {
 
 // TODO: Test that this isn't `Clone` (bc. id).
 /// A musical piece consisting of multiple [tracks](Track).
-#[derive(Debug, Default, Getters, CloneGetters, Deserialize)]
-#[serde(from = "Serial")]
+#[derive(Debug, Default, Getters, CopyGetters, CloneGetters, Deserialize)]
+#[serde(try_from = "Serial")]
 pub struct Project {
     /// The name of the project.
     #[get_clone = "pub"]
     name: ArcStr,
 
-    // TODO: continuous change
     /// The tempo of the project.
-    tempo: Changing<Tempo>,
+    tempo: ChangingTempo,
     /// The time signature of the project.
     #[get = "pub(crate)"]
     time_signature: Changing<TimeSignature>,
     /// The key of the project.
     #[get = "pub(crate)"]
     key: Changing<Key>,
+    /// The named markers of the project (e.g. the start of a verse or chorus).
+    #[get = "pub(crate)"]
+    markers: BTreeMap<Instant, ArcStr>,
+    /// The region that is repeated during playback, if looping is enabled.
+    #[get_copy = "pub(crate)"]
+    loop_region: Option<NonZeroPeriod>,
 
     /// The tracks in the project.
     tracks: IndexMap<Id<Track>, Track>,
+    /// The contents that are shared between linked clips.
+    #[get = "pub(crate)"]
+    linked_contents: BTreeMap<clip::Link, clip::Content>,
+
+    /// The directory that the project is saved in.
+    ///
+    /// Linked files are stored relative to this.
+    directory: Option<Arc<Path>>,
 }
 
 impl Project {
@@ -102,12 +132,77 @@ impl Project {
         self.track_mut(path.track)?.clip_mut(path.clip)
     }
 
+    /// Returns a reference to the content of a clip.
+    ///
+    /// If the clip is linked, the shared content is returned.
+    fn content(&self, path: clip::Path) -> Option<(Instant, &clip::Content)> {
+        let (start, clip) = self.clip(path)?;
+
+        Some((start, clip.content().resolve(&self.linked_contents)?))
+    }
+
+    /// Returns a mutable reference to the content of a clip.
+    ///
+    /// If the clip is linked, the shared content is returned.
+    fn content_mut(&mut self, path: clip::Path) -> Option<(Instant, &mut clip::Content)> {
+        let (start, clip) = self.tracks.get_mut(&path.track)?.clip_mut(path.clip)?;
+
+        let content = clip.content_mut();
+
+        if let Some(link) = content.link() {
+            return Some((start, self.linked_contents.get_mut(&link)?));
+        }
+
+        Some((start, content))
+    }
+
+    /// Returns an unused link.
+    fn unused_link(&self) -> clip::Link {
+        self.linked_contents
+            .last_key_value()
+            .map_or(clip::Link::FIRST, |(link, _)| link.next())
+    }
+
     /// Removes a clip from a track.
     #[must_use]
     fn remove_clip(&mut self, path: clip::Path) -> Option<(Instant, Clip)> {
         self.track_mut(path.track)?.remove_clip(path.clip)
     }
 
+    /// Returns the directory against which linked files are resolved.
+    ///
+    /// If the project has not been saved, this is the working directory.
+    pub(crate) fn directory(&self) -> &Path {
+        self.directory.as_deref().unwrap_or(Path::new(""))
+    }
+
+    /// Returns the paths of the files of all linked audio clips in the project,
+    /// relative to its [directory](Project::directory).
+    pub(super) fn linked_files(&self) -> impl Iterator<Item = &Path> {
+        let linked_contents = self
+            .linked_contents
+            .values()
+            .filter_map(|content| Some(content.as_linked_audio()?.path().as_path()));
+
+        self.tracks
+            .values()
+            .flat_map(Track::linked_files)
+            .chain(linked_contents)
+    }
+
+    /// Returns the markers as [cues](Cue) for exported audio.
+    pub(crate) fn cues(&self) -> Vec<Cue> {
+        let time_context = self.time_context();
+
+        self.markers
+            .iter()
+            .map(|(at, name)| Cue {
+                position: *at * &time_context,
+                label: name.clone(),
+            })
+            .collect()
+    }
+
     /// Returns the [time context](TimeContext).
     pub(crate) fn time_context(&self) -> Changing<TimeContext> {
         &self.time_signature / &self.tempo
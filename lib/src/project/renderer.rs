@@ -3,12 +3,19 @@
 use crate::Audio;
 use crate::Project;
 use crate::UserInterface;
+use crate::audio::ClickTrack;
+use crate::audio::Cue;
 use crate::audio::Player;
 use crate::audio::sample;
 use crate::audio::sample::Instant;
 use crate::node::Chain;
 use crate::note::event::Sequence;
 use crate::popup;
+use crate::project::Track;
+use crate::project::track::Automation;
+use crate::project::track::Mixing;
+use crate::project::track::automation;
+use crate::project::track::automation::Target;
 use crate::sync::Cell;
 use crate::time;
 use executors::Executor as _;
@@ -20,6 +27,7 @@ use saturating_cast::SaturatingCast as _;
 use std::cmp::max;
 use std::mem::replace;
 use std::mem::take;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -53,6 +61,18 @@ struct Play {
     from: time::Instant,
     /// The audio player in which to start playback.
     player: Player,
+    /// The region to repeat, if playback should loop.
+    loop_region: Option<time::Period>,
+    /// The metronome clicks to mix into playback.
+    click_track: ClickTrack,
+}
+
+/// Where to export the render.
+struct Export {
+    /// The file to export the render to.
+    to: PathBuf,
+    /// The cues to write into the file.
+    cues: Vec<Cue>,
 }
 
 /// The state of the master.
@@ -65,7 +85,7 @@ enum Master {
         /// Where and when to start playing the render if it is set.
         should_play: Option<Play>,
         /// Where to export the render if it is set.
-        should_export: Option<PathBuf>,
+        should_export: Option<Export>,
     },
 }
 
@@ -86,25 +106,39 @@ impl Renderer {
     }
 
     /// Play the rendered audio from the given position in the given player when rendering is finished.
-    pub(crate) fn play_when_finished(&self, from: time::Instant, player: Player) {
+    ///
+    /// If a loop region is given, it is repeated.
+    /// The click track is only mixed into the played audio, not into the render.
+    pub(crate) fn play_when_finished(
+        &self,
+        from: time::Instant,
+        player: Player,
+        loop_region: Option<time::Period>,
+        click_track: ClickTrack,
+    ) {
         match &mut *self.progress.master.lock() {
             Master::Finished(audio) => {
-                player.play(audio.clone(), from);
+                player.play(audio.clone(), from, loop_region, &click_track);
             }
             Master::OnFinish { should_play, .. } => {
-                *should_play = Some(Play { from, player });
+                *should_play = Some(Play {
+                    from,
+                    player,
+                    loop_region,
+                    click_track,
+                });
             }
         }
     }
 
-    /// Exports the project to a file when rendering is finished.
-    pub(crate) fn export_when_finished(&self, to: PathBuf) -> anyhow::Result<()> {
+    /// Exports the project to a file, with the given cues, when rendering is finished.
+    pub(crate) fn export_when_finished(&self, to: PathBuf, cues: Vec<Cue>) -> anyhow::Result<()> {
         match &mut *self.progress.master.lock() {
             Master::Finished(audio) => {
-                audio.export(&to)?;
+                audio.export(&to, &cues)?;
             }
             Master::OnFinish { should_export, .. } => {
-                *should_export = Some(to);
+                *should_export = Some(Export { to, cues });
             }
         }
 
@@ -144,31 +178,52 @@ impl Renderer {
             },
         };
 
+        let solo_active = project.tracks.values().any(|track| track.mixing().soloed);
+
+        let audible_tracks: Vec<&Track> = project
+            .tracks
+            .values()
+            .filter(|track| track.mixing().is_audible(solo_active))
+            .collect();
+
         self.progress = Arc::new(Progress {
             should_stop: Cell::new(false),
-            unmastered_tracks: Mutex::new(Vec::with_capacity(project.tracks.len())),
+            unmastered_tracks: Mutex::new(Vec::with_capacity(audible_tracks.len())),
             master: Mutex::new(new_master),
         });
 
         let time_context = project.time_context();
+        let directory: Arc<Path> = Arc::from(project.directory());
 
-        for track in project.tracks.values() {
-            let audio = track.audio_superposition(&time_context, sample_rate);
-            let events = track.events(&time_context, sample_rate);
+        for track in &audible_tracks {
+            let mut audio =
+                track.audio_superposition(&time_context, sample_rate, &project.linked_contents);
+            let linked_audio =
+                track.linked_audio(&time_context, sample_rate, &project.linked_contents);
+            let events = track.events(&time_context, sample_rate, &project.linked_contents);
+            let mixing = track.mixing();
+            let automation = track.automation().sample(&time_context, sample_rate);
 
             // TODO: take from the track
             let chain = Chain::default();
 
+            let directory = Arc::clone(&directory);
             let progress = Arc::clone(&self.progress);
             let popups = Arc::clone(&self.popups);
 
             self.thread_pool.execute(move || {
-                try_render(&audio, &events, &chain, &progress)
+                // A clip whose file cannot be read is left silent.
+                for clip in &linked_audio {
+                    clip.superpose_onto(&mut audio, &directory)
+                        .unwrap_or_else(|error| popups.open(&error.into(), ui));
+                }
+
+                try_render(&audio, &events, &chain, mixing, &automation, &progress)
                     .unwrap_or_else(|error| popups.open(&error.into(), ui));
             });
         }
 
-        if project.tracks.is_empty() {
+        if audible_tracks.is_empty() {
             master(Vec::new(), sample_rate, &self.progress)?;
         }
 
@@ -177,10 +232,15 @@ impl Renderer {
 }
 
 /// Tries to render a track.
+///
+/// The `automation` of the parameters of the chain and of the mixing is applied sample by sample,
+/// and the rendered audio is mixed according to `mixing` before it is mastered.
 fn try_render(
     input_audio: &Audio,
     events: &Sequence,
     chain: &Chain,
+    mixing: Mixing,
+    automation: &[(Target, automation::Sampled)],
     progress: &Progress,
 ) -> anyhow::Result<()> {
     let sample_rate = input_audio.sample_rate;
@@ -214,7 +274,7 @@ fn try_render(
         let audio = input_audio.subsection(period);
         let events = events.subsequence(period);
 
-        let result = instance.process(batch_duration, audio, events);
+        let result = instance.process(batch_duration, audio, events, automation);
 
         output_audio.superpose_with_offset(&result.audio, position.since_start);
         position += batch_duration;
@@ -222,6 +282,7 @@ fn try_render(
     }
 
     output_audio.truncate_silence(input_audio.duration());
+    output_audio.amplify(|instant| Automation::mixing_at(automation, mixing, instant).amplitudes());
 
     let mut tracks = progress.unmastered_tracks.lock();
     tracks.push(output_audio);
@@ -258,12 +319,18 @@ fn master(
         should_export,
     } = &*audio_progress
     {
-        if let Some(Play { from, player }) = should_play {
-            player.play(audio.clone(), *from);
+        if let Some(Play {
+            from,
+            player,
+            loop_region,
+            click_track,
+        }) = should_play
+        {
+            player.play(audio.clone(), *from, *loop_region, click_track);
         }
 
-        if let Some(file) = should_export {
-            audio.export(file)?;
+        if let Some(Export { to, cues }) = should_export {
+            audio.export(to, cues)?;
         }
     }
 
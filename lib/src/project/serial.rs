@@ -2,15 +2,25 @@
 
 use crate::Project;
 use crate::metre::Changing;
+use crate::metre::ChangingTempo;
+use crate::metre::Instant;
+use crate::metre::NonZeroPeriod;
 use crate::metre::TimeSignature;
 use crate::note::Key;
+use crate::project::Track;
 use crate::project::track;
-use crate::time::Tempo;
+use crate::project::track::clip;
 use arcstr::ArcStr;
+use arcstr::literal;
 use indexmap::IndexMap;
 use serde::Deserialize;
 use serde::Serialize;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+
+/// The name used in place of a track name in errors about linked contents.
+const LINKED_CONTENTS: ArcStr = literal!("linked contents");
 
 /// The serial representation of a [project](Project).
 #[derive(Serialize, Deserialize)]
@@ -19,14 +29,50 @@ pub(super) struct Serial<'data> {
     pub name: Cow<'data, str>,
 
     /// The tempo.
-    pub tempo: Changing<Tempo>,
+    pub tempo: ChangingTempo,
     /// The time signature.
     pub time_signature: Changing<TimeSignature>,
     /// The key.
     pub key: Changing<Key>,
+    /// The markers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub markers: Vec<Marker<'data>>,
+    /// The loop region.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loop_region: Option<LoopRegion>,
 
     /// The tracks.
     pub tracks: Vec<track::Serial<'data>>,
+    /// The contents that are shared between linked clips.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub linked_contents: Vec<LinkedContent<'data>>,
+}
+
+/// The serial representation of a marker.
+#[derive(Serialize, Deserialize)]
+pub(super) struct Marker<'data> {
+    /// The position of the marker.
+    pub position: Instant,
+    /// The name of the marker.
+    pub name: Cow<'data, str>,
+}
+
+/// The serial representation of a loop region.
+#[derive(Serialize, Deserialize)]
+pub(super) struct LoopRegion {
+    /// The start of the region.
+    pub start: Instant,
+    /// The end of the region.
+    pub end: Instant,
+}
+
+/// The serial representation of content that is shared between linked clips.
+#[derive(Serialize, Deserialize)]
+pub(super) struct LinkedContent<'data> {
+    /// The link to the content.
+    pub link: clip::Link,
+    /// The content.
+    pub content: clip::SerialContent<'data>,
 }
 
 impl<'data> From<&'data Project> for Serial<'data> {
@@ -36,39 +82,108 @@ impl<'data> From<&'data Project> for Serial<'data> {
             tempo,
             time_signature,
             key,
+            markers,
+            loop_region,
             tracks,
+            linked_contents,
+            directory: _,
         } = project;
 
+        // Contents that are no longer linked to by any clip are not saved.
+        let links: HashSet<clip::Link> = tracks.values().flat_map(Track::links).collect();
+
         Serial {
             name: Cow::Borrowed(name),
             tempo: tempo.clone(),
             time_signature: time_signature.clone(),
             key: key.clone(),
+            markers: markers
+                .iter()
+                .map(|(position, name)| Marker {
+                    position: *position,
+                    name: Cow::Borrowed(name),
+                })
+                .collect(),
+            loop_region: loop_region.map(|region| LoopRegion {
+                start: region.start,
+                end: region.get().end(),
+            }),
             tracks: tracks.values().map(track::Serial::from).collect(),
+            linked_contents: linked_contents
+                .iter()
+                .filter(|(link, _)| links.contains(link))
+                .map(|(link, content)| LinkedContent {
+                    link: *link,
+                    content: clip::SerialContent::from(content),
+                })
+                .collect(),
         }
     }
 }
 
-impl<'data> From<Serial<'data>> for Project {
-    fn from(serial: Serial<'data>) -> Self {
+impl<'data> TryFrom<Serial<'data>> for Project {
+    type Error = track::DeserialisationError;
+
+    fn try_from(serial: Serial<'data>) -> Result<Self, Self::Error> {
         let Serial {
             name,
             tempo,
             time_signature,
             key,
+            markers,
+            loop_region,
             tracks,
+            linked_contents,
         } = serial;
 
-        // TODO: convert the tracks
-        drop(tracks);
-
-        Project {
+        let mut project = Project {
             name: ArcStr::from(name),
             tempo,
             time_signature,
             key,
+            markers: markers
+                .into_iter()
+                .map(|Marker { position, name }| (position, ArcStr::from(name)))
+                .collect(),
+            loop_region: loop_region
+                .and_then(|LoopRegion { start, end }| NonZeroPeriod::from_endpoints(start, end)),
             tracks: IndexMap::new(),
+            linked_contents: BTreeMap::new(),
+            directory: None,
+        };
+
+        for LinkedContent { link, content } in linked_contents {
+            let content =
+                clip::Content::try_from(content).map_err(|kind| track::DeserialisationError {
+                    track: LINKED_CONTENTS,
+                    clip: arcstr::format!("{link}"),
+                    kind: track::DeserialisationErrorKind::from(kind),
+                })?;
+
+            project.linked_contents.insert(link, content);
         }
+
+        for track in tracks {
+            for clip in &track.clips {
+                let clip::SerialContent::Linked { link, .. } = clip.content else {
+                    continue;
+                };
+
+                if !project.linked_contents.contains_key(&link) {
+                    return Err(track::DeserialisationError {
+                        track: ArcStr::from(&*track.name),
+                        clip: ArcStr::from(&*clip.name),
+                        kind: track::DeserialisationErrorKind::DanglingLink { link },
+                    });
+                }
+            }
+
+            let track = Track::try_from(track)?;
+
+            project.tracks.insert(track.id(), track);
+        }
+
+        Ok(project)
     }
 }
 
@@ -76,8 +191,14 @@ impl<'data> From<Serial<'data>> for Project {
 mod test {
     use super::*;
 
+    use crate::NonZeroRatio;
+    use crate::project::track::automation::Parameter;
+    use crate::project::track::automation::Target;
     use anyhow::Context as _;
+    use anyhow::bail;
     use anyhow::ensure;
+    use enum_iterator::all;
+    use non_zero::non_zero;
     use std::fs::read_dir;
     use std::fs::read_to_string;
     use std::path::Path;
@@ -112,6 +233,263 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn round_trip_toml_example_projects() -> anyhow::Result<()> {
+        let lib = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let root = lib.parent().context("invalid CARGO_MANIFEST_DIR")?;
+        let examples = root.join("examples").join("toml");
+
+        let directory_context = || format!("reading {}", examples.display());
+
+        for entry in read_dir(&examples).with_context(directory_context)? {
+            let entry = entry?;
+            let path = entry.path();
+
+            let file_context = || format!("reading {}", path.display());
+
+            let content = read_to_string(&path).with_context(file_context)?;
+
+            let project: Project = toml::from_str(&content).with_context(file_context)?;
+
+            let string = toml::to_string(&project).with_context(file_context)?;
+
+            ensure!(
+                content == string,
+                "comparing {} with the output of serializing the parsed project",
+                path.display()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn report_overlapping_notes() -> anyhow::Result<()> {
+        let content = r#"
+            name = ""
+            time_signature = [4, 4]
+
+            [tempo]
+            bpm = 120
+
+            [key]
+            tonic = "A"
+            sign = "sharp"
+            intervals = []
+
+            [[tracks]]
+            name = "drums"
+
+            [[tracks.clips]]
+            name = "beat"
+            position = { since_start = { whole_notes = 0 } }
+            colour = { red = 0, green = 0, blue = 0 }
+
+            [tracks.clips.content.notes]
+            duration = { whole_notes = 1 }
+            notes = [
+                { position = { since_start = { whole_notes = 0 } }, pitch = 36, duration = { whole_notes = 1 } },
+                { position = { since_start = { whole_notes = 0.5 } }, pitch = 36, duration = { whole_notes = 0.5 } },
+            ]
+        "#;
+
+        let Err(error) = toml::from_str::<Project>(content) else {
+            bail!("overlapping notes were accepted");
+        };
+
+        let message = error.to_string();
+
+        ensure!(
+            message.contains("\"beat\"") && message.contains("\"drums\""),
+            "the error does not name the offending clip and track: {message}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn load_overlapping_clips() -> anyhow::Result<()> {
+        let content = r#"
+            name = ""
+            time_signature = [4, 4]
+
+            [tempo]
+            bpm = 120
+
+            [key]
+            tonic = "A"
+            sign = "sharp"
+            intervals = []
+
+            [[tracks]]
+            name = "piano"
+
+            [[tracks.clips]]
+            name = "verse"
+            position = { since_start = { whole_notes = 0 } }
+            colour = { red = 0, green = 0, blue = 0 }
+
+            [tracks.clips.content.notes]
+            duration = { whole_notes = 2 }
+            notes = []
+
+            [[tracks.clips]]
+            name = "chorus"
+            position = { since_start = { whole_notes = 1 } }
+            colour = { red = 0, green = 0, blue = 0 }
+
+            [tracks.clips.content.notes]
+            duration = { whole_notes = 2 }
+            notes = []
+        "#;
+
+        // Projects saved before overlapping clips were rejected should still load.
+        let project: Project = toml::from_str(content)?;
+
+        let string = toml::to_string(&project)?;
+
+        ensure!(
+            string.contains("\"verse\"") && string.contains("\"chorus\""),
+            "not both overlapping clips were loaded: ```\n{string}```"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn report_dangling_links() -> anyhow::Result<()> {
+        let content = r#"
+            name = ""
+            time_signature = [4, 4]
+
+            [tempo]
+            bpm = 120
+
+            [key]
+            tonic = "A"
+            sign = "sharp"
+            intervals = []
+
+            [[tracks]]
+            name = "bass"
+
+            [[tracks.clips]]
+            name = "riff"
+            position = { since_start = { whole_notes = 0 } }
+            colour = { red = 0, green = 0, blue = 0 }
+
+            [tracks.clips.content.linked]
+            link = 3
+            duration = { whole_notes = 1 }
+        "#;
+
+        let Err(error) = toml::from_str::<Project>(content) else {
+            bail!("a link to non-existent content was accepted");
+        };
+
+        let message = error.to_string();
+
+        ensure!(
+            message.contains("\"riff\"")
+                && message.contains("\"bass\"")
+                && message.contains("link 3"),
+            "the error does not name the offending clip, track and link: {message}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_node_parameter_automation() -> anyhow::Result<()> {
+        let content = r#"
+            name = ""
+            time_signature = [4, 4]
+
+            [tempo]
+            bpm = 120
+
+            [key]
+            tonic = "A"
+            sign = "sharp"
+            intervals = []
+
+            [[tracks]]
+            name = "lead"
+            clips = []
+
+            [[tracks.automation.node_brightness]]
+            at = { since_start = { whole_notes = 0 } }
+            value = 25.0
+            shape = "linear"
+
+            [[tracks.automation.node_level]]
+            at = { since_start = { whole_notes = 1 } }
+            value = -6.0
+        "#;
+
+        let project: Project = toml::from_str(content)?;
+
+        let track = project
+            .tracks
+            .values()
+            .next()
+            .context("the track was not parsed")?;
+
+        for parameter in all::<Parameter>() {
+            ensure!(
+                track.automation().lane(Target::Node(parameter)).is_some(),
+                "the automation of {parameter} was not parsed"
+            );
+        }
+
+        let string = toml::to_string(&project)?;
+        let reparsed: Project = toml::from_str(&string)?;
+
+        ensure!(
+            toml::to_string(&reparsed)? == string,
+            "the automation changed when serialized as: ```\n{string}```"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_non_integral_tempo() -> anyhow::Result<()> {
+        let content = r#"
+            name = ""
+            time_signature = [4, 4]
+            tracks = []
+
+            [tempo]
+            bpm = 127.5
+
+            [key]
+            tonic = "A"
+            sign = "sharp"
+            intervals = []
+        "#;
+
+        let project: Project = toml::from_str(content)?;
+
+        let bpm = project.tempo.values.start.bpm();
+
+        ensure!(
+            bpm == NonZeroRatio::new(non_zero!(255), non_zero!(2)),
+            "127.5 was parsed as {}",
+            bpm.get()
+        );
+
+        let string = toml::to_string(&project)?;
+        let reparsed: Project = toml::from_str(&string)?;
+
+        ensure!(
+            reparsed.tempo.values.start == project.tempo.values.start,
+            "the tempo changed when serialized as: ```\n{string}```"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn serialize_project_default() -> anyhow::Result<()> {
         let lib = Path::new(env!("CARGO_MANIFEST_DIR"));
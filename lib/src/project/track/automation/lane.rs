@@ -0,0 +1,114 @@
+//! Items pertaining to [`Lane`].
+
+use crate::audio::sample;
+use crate::metre::Changing;
+use crate::metre::Instant;
+use crate::metre::TimeContext;
+use crate::project::track::automation::Sampled;
+use crate::project::track::automation::Shape;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A breakpoint curve of an automated parameter.
+///
+/// Before the first point, the parameter has the value of the first point.
+/// After the last point, the parameter keeps its value.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Lane {
+    /// A map from positions to points.
+    points: BTreeMap<Instant, Point>,
+}
+
+/// A point on an automation [lane](Lane).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Point {
+    /// The value of the parameter at the point.
+    pub value: f32,
+    /// How the value moves from the point to the next one.
+    pub shape: Shape,
+}
+
+/// The serial representation of a [point](Point).
+#[derive(Serialize, Deserialize)]
+struct SerialPoint {
+    /// The position.
+    at: Instant,
+    /// The value.
+    value: f32,
+    /// The shape.
+    #[serde(default)]
+    shape: Shape,
+}
+
+impl Lane {
+    /// Returns whether the lane has no points.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns an iterator over the points of the lane.
+    pub fn points(&self) -> impl Iterator<Item = (Instant, Point)> {
+        self.points.iter().map(|(at, point)| (*at, *point))
+    }
+
+    /// Returns the point at a position, if any.
+    #[must_use]
+    pub fn point(&self, at: Instant) -> Option<Point> {
+        self.points.get(&at).copied()
+    }
+
+    /// Sets or removes the point at a position.
+    ///
+    /// Returns the point that was overwritten, if any.
+    pub(crate) fn set_point(&mut self, at: Instant, point: Option<Point>) -> Option<Point> {
+        match point {
+            Some(point) => self.points.insert(at, point),
+            None => self.points.remove(&at),
+        }
+    }
+
+    /// Positions the points of the lane at samples.
+    pub(super) fn sample(
+        &self,
+        time_context: &Changing<TimeContext>,
+        sample_rate: sample::Rate,
+    ) -> Sampled {
+        Sampled::new(
+            self.points
+                .iter()
+                .map(|(at, point)| (*at * time_context * sample_rate, *point))
+                .collect(),
+        )
+    }
+}
+
+impl Serialize for Lane {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.points.iter().map(|(at, point)| SerialPoint {
+            at: *at,
+            value: point.value,
+            shape: point.shape,
+        }))
+    }
+}
+
+impl<'de> Deserialize<'de> for Lane {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let points = Vec::<SerialPoint>::deserialize(deserializer)?;
+
+        Ok(Lane {
+            points: points
+                .into_iter()
+                .map(|SerialPoint { at, value, shape }| (at, Point { value, shape }))
+                .collect(),
+        })
+    }
+}
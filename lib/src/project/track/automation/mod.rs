@@ -0,0 +1,103 @@
+//! Items pertaining to [`Automation`].
+
+mod lane;
+mod overview;
+mod sampled;
+mod shape;
+mod target;
+
+pub use crate::node::Parameter;
+pub use lane::Lane;
+pub use lane::Point;
+pub use shape::Shape;
+pub use target::Target;
+
+pub(crate) use overview::overview;
+pub(crate) use sampled::Sampled;
+
+use crate::audio::sample;
+use crate::metre::Changing;
+use crate::metre::Instant;
+use crate::metre::TimeContext;
+use crate::project::track::Mixing;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// The automation of the parameters of a [track](super::Track).
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Automation {
+    /// The lanes of the automated parameters.
+    lanes: BTreeMap<Target, Lane>,
+}
+
+impl Automation {
+    /// Returns whether no parameters are automated.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lanes.values().all(Lane::is_empty)
+    }
+
+    /// Returns the lane of a parameter, or `None` if it is not automated.
+    #[must_use]
+    pub fn lane(&self, target: Target) -> Option<&Lane> {
+        self.lanes.get(&target)
+    }
+
+    /// Sets or removes the point at a position in the lane of a parameter.
+    ///
+    /// Returns the point that was overwritten, if any.
+    pub(crate) fn set_point(
+        &mut self,
+        target: Target,
+        at: Instant,
+        point: Option<Point>,
+    ) -> Option<Point> {
+        let lane = self.lanes.entry(target).or_default();
+        let overwritten = lane.set_point(at, point);
+
+        if lane.is_empty() {
+            self.lanes.remove(&target);
+        }
+
+        overwritten
+    }
+
+    /// Positions the points of all lanes at samples.
+    pub(crate) fn sample(
+        &self,
+        time_context: &Changing<TimeContext>,
+        sample_rate: sample::Rate,
+    ) -> Vec<(Target, Sampled)> {
+        self.lanes
+            .iter()
+            .map(|(target, lane)| (*target, lane.sample(time_context, sample_rate)))
+            .collect()
+    }
+
+    /// Returns the value of a parameter at an instant, given the sampled lanes,
+    /// or `None` if the parameter is not automated.
+    pub(crate) fn value_at(
+        lanes: &[(Target, Sampled)],
+        target: Target,
+        instant: sample::Instant,
+    ) -> Option<f32> {
+        lanes
+            .iter()
+            .find(|(lane_target, _)| *lane_target == target)
+            .and_then(|(_, lane)| lane.value(instant))
+    }
+
+    /// Returns the mixing settings at an instant, given the sampled lanes and the unautomated settings.
+    pub(crate) fn mixing_at(
+        lanes: &[(Target, Sampled)],
+        mixing: Mixing,
+        instant: sample::Instant,
+    ) -> Mixing {
+        lanes.iter().fold(mixing, |mixing, (target, lane)| {
+            lane.value(instant)
+                .map_or(mixing, |value| target.apply(mixing, value))
+        })
+    }
+}
@@ -0,0 +1,113 @@
+//! File for the [`overview`] function.
+
+use crate::UserInterface;
+use crate::View;
+use crate::app::Action;
+use crate::metre::OffsetMapping;
+use crate::project::Edit;
+use crate::project::Track;
+use crate::project::track::automation::Point;
+use crate::project::track::automation::Shape;
+use crate::project::track::automation::Target;
+use crate::string::ToArcStr as _;
+use crate::ui::Length;
+use crate::ui::NonZeroLength;
+use crate::ui::Size;
+use crate::ui::relative;
+use crate::view::OnClick;
+use bon::builder;
+use std::sync::Arc;
+
+/// Returns the overview of an automation lane of a track, which is shown below the track.
+///
+/// Clicking the lane sets a point at the (quantised) time and height of the click.
+/// Clicking a point cycles its shape until it is removed.
+#[builder]
+pub(crate) fn overview<Ui: UserInterface>(
+    track: &Track,
+    target: Target,
+    offset_mapping: OffsetMapping,
+    negative_overview_offset: Length,
+) -> View {
+    let id = track.id();
+    let lane = track.automation().lane(target).cloned().unwrap_or_default();
+
+    let point_size = Size {
+        width: Ui::KEY_WIDTH.get(),
+        height: Ui::KEY_WIDTH.get(),
+    };
+
+    let points: Vec<_> = lane
+        .points()
+        .map(|(at, point)| {
+            let x = offset_mapping.offset(at) - negative_overview_offset;
+
+            let button = View::simple_button(
+                point.shape.to_arc_str(),
+                OnClick::from(Edit::SetAutomationPoint {
+                    track: id,
+                    target,
+                    at,
+                    point: next_point(point),
+                }),
+            );
+
+            (x, point.value, Arc::new(button))
+        })
+        .collect();
+
+    let points = View::reactive(move |render_area| {
+        let height = render_area.area.size.height - point_size.height;
+
+        View::Layers(
+            points
+                .iter()
+                .map(|(x, value, button)| {
+                    let position = relative::Point {
+                        x: *x,
+                        y: height - height * target.fraction(*value),
+                    };
+
+                    View::Shared(Arc::clone(button))
+                        .quoted_2d(point_size)
+                        .positioned(position)
+                })
+                .collect(),
+        )
+    });
+
+    let background = View::Empty.on_click(OnClick::new(move |render_area, actions| {
+        let Some(mouse_position) = render_area.relative_mouse_position() else {
+            return;
+        };
+        let Some(height) = NonZeroLength::from_length(render_area.area.size.height) else {
+            return;
+        };
+
+        let at = offset_mapping.quantised_instant(mouse_position.x + negative_overview_offset);
+        let shape = lane.point(at).map(|point| point.shape).unwrap_or_default();
+        let value = target.value_at((height.get() - mouse_position.y) / height);
+
+        actions.push(Action::Edit(Edit::SetAutomationPoint {
+            track: id,
+            target,
+            at,
+            point: Some(Point { value, shape }),
+        }));
+    }));
+
+    View::Layers(vec![background, points]).scrollable(Action::MoveOverview)
+}
+
+/// Returns the point that a point is replaced with when it is clicked.
+///
+/// Linear points become curved, curved points become steps and steps are removed.
+fn next_point(point: Point) -> Option<Point> {
+    let shape = match point.shape {
+        Shape::Curved => Shape::Step,
+        Shape::Linear => Shape::Curved,
+        Shape::Step => return None,
+    };
+
+    Some(Point { shape, ..point })
+}
@@ -0,0 +1,43 @@
+//! Items pertaining to [`Sampled`].
+
+use crate::audio::sample;
+use crate::project::track::automation::Point;
+
+/// An automation [lane](super::Lane) with its points positioned at samples.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct Sampled {
+    /// The points, in order.
+    points: Vec<(sample::Instant, Point)>,
+}
+
+impl Sampled {
+    /// Constructs a new sampled lane from points in order.
+    pub(super) fn new(points: Vec<(sample::Instant, Point)>) -> Sampled {
+        Sampled { points }
+    }
+
+    /// Returns the value of the lane at an instant, or `None` if the lane has no points.
+    pub(crate) fn value(&self, instant: sample::Instant) -> Option<f32> {
+        #![expect(
+            clippy::cast_precision_loss,
+            reason = "segments are short enough for the progress to be accurate"
+        )]
+
+        let next_index = self.points.partition_point(|(at, _)| *at <= instant);
+
+        let Some(index) = next_index.checked_sub(1) else {
+            return self.points.first().map(|(_, point)| point.value);
+        };
+
+        let (from, point) = self.points.get(index)?;
+
+        let Some((to, next)) = self.points.get(next_index) else {
+            return Some(point.value);
+        };
+
+        let length = (*to - *from).samples as f32;
+        let offset = (instant - *from).samples as f32;
+
+        Some(point.shape.value(point.value, next.value, offset / length))
+    }
+}
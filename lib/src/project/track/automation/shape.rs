@@ -0,0 +1,46 @@
+//! Items pertaining to [`Shape`].
+
+use enum_iterator::Sequence;
+use serde::Deserialize;
+use serde::Serialize;
+use std::f32::consts::PI;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// How an automated value moves from a [point](super::Point) to the next one.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default, Sequence, Serialize, Deserialize)]
+#[remain::sorted]
+#[serde(rename_all = "snake_case")]
+pub enum Shape {
+    /// The value eases out of the point and into the next one.
+    Curved,
+    /// The value changes by a constant amount per unit of time.
+    #[default]
+    Linear,
+    /// The value is held until the next point.
+    Step,
+}
+
+impl Shape {
+    /// Returns the value at some progress, from 0 to 1, through a segment from `start` to `end`.
+    pub(crate) fn value(self, start: f32, end: f32, progress: f32) -> f32 {
+        let progress = match self {
+            Shape::Curved => (1.0 - (progress * PI).cos()) / 2.0,
+            Shape::Linear => progress,
+            Shape::Step => 0.0,
+        };
+
+        start + (end - start) * progress
+    }
+}
+
+impl Display for Shape {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Shape::Curved => write!(f, "~"),
+            Shape::Linear => write!(f, "/"),
+            Shape::Step => write!(f, "_"),
+        }
+    }
+}
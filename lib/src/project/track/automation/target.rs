@@ -0,0 +1,143 @@
+//! Items pertaining to [`Target`].
+
+use crate::Ratio;
+use crate::audio::Gain;
+use crate::audio::Pan;
+use crate::project::track::Mixing;
+use crate::project::track::automation::Parameter;
+use enum_iterator::Sequence;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::ops::RangeInclusive;
+
+/// A parameter of a [track](crate::project::Track) that can be automated.
+#[derive(
+    Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Sequence, Serialize, Deserialize,
+)]
+#[remain::sorted]
+#[serde(from = "Serial", into = "Serial")]
+pub enum Target {
+    /// The gain of the track, in decibels.
+    Gain,
+    /// A parameter of the nodes in the track's chain.
+    Node(Parameter),
+    /// The stereo position of the track, in percent.
+    Pan,
+}
+
+/// The serial representation of a [target](Target).
+///
+/// Targets are used as keys, so the parameters of nodes are part of the name.
+#[derive(Copy, Clone, Serialize, Deserialize)]
+#[remain::sorted]
+#[serde(rename_all = "snake_case")]
+enum Serial {
+    /// [`Target::Gain`].
+    Gain,
+    /// [`Target::Node`] with [`Parameter::Brightness`].
+    NodeBrightness,
+    /// [`Target::Node`] with [`Parameter::Level`].
+    NodeLevel,
+    /// [`Target::Pan`].
+    Pan,
+}
+
+impl Target {
+    /// Returns the range of values that the parameter can be automated to.
+    #[must_use]
+    pub fn range(self) -> RangeInclusive<f32> {
+        match self {
+            Target::Gain | Target::Node(Parameter::Level) => -60.0..=12.0,
+            Target::Node(Parameter::Brightness) => 0.0..=100.0,
+            Target::Pan => -100.0..=100.0,
+        }
+    }
+
+    /// Clamps a value to the range of the parameter.
+    #[must_use]
+    pub fn clamp_value(self, value: f32) -> f32 {
+        let range = self.range();
+        value.clamp(*range.start(), *range.end())
+    }
+
+    /// Returns where a value lies in the range of the parameter, from zero to one.
+    pub(crate) fn fraction(self, value: f32) -> Ratio {
+        let range = self.range();
+        let fraction = (self.clamp_value(value) - range.start()) / (range.end() - range.start());
+
+        Ratio::approximate(f64::from(fraction))
+    }
+
+    /// Returns the value that lies at a fraction, from zero to one, of the range of the parameter.
+    pub(crate) fn value_at(self, fraction: Ratio) -> f32 {
+        #![expect(
+            clippy::cast_possible_truncation,
+            reason = "the fraction is small enough to fit in a float"
+        )]
+
+        let range = self.range();
+        let span = range.end() - range.start();
+
+        self.clamp_value(range.start() + span * fraction.to_float() as f32)
+    }
+
+    /// Returns the mixing settings with the parameter set to a value.
+    ///
+    /// The parameters of nodes are not part of the mixing settings, which are then returned as is.
+    #[must_use]
+    pub fn apply(self, mixing: Mixing, value: f32) -> Mixing {
+        #![expect(
+            clippy::cast_possible_truncation,
+            reason = "the value is rounded and clamped to the range of a pan"
+        )]
+
+        let value = self.clamp_value(value);
+
+        match self {
+            Target::Gain => Mixing {
+                gain: Gain { decibels: value },
+                ..mixing
+            },
+            Target::Node(_) => mixing,
+            Target::Pan => Mixing {
+                pan: Pan::new(value.round() as i8),
+                ..mixing
+            },
+        }
+    }
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::Gain => write!(f, "gain"),
+            Target::Node(parameter) => write!(f, "{parameter}"),
+            Target::Pan => write!(f, "pan"),
+        }
+    }
+}
+
+impl From<Target> for Serial {
+    fn from(target: Target) -> Self {
+        match target {
+            Target::Gain => Serial::Gain,
+            Target::Node(Parameter::Brightness) => Serial::NodeBrightness,
+            Target::Node(Parameter::Level) => Serial::NodeLevel,
+            Target::Pan => Serial::Pan,
+        }
+    }
+}
+
+impl From<Serial> for Target {
+    fn from(serial: Serial) -> Self {
+        match serial {
+            Serial::Gain => Target::Gain,
+            Serial::NodeBrightness => Target::Node(Parameter::Brightness),
+            Serial::NodeLevel => Target::Node(Parameter::Level),
+            Serial::Pan => Target::Pan,
+        }
+    }
+}
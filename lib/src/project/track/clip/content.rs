@@ -1,23 +1,63 @@
 //! Items pertaining to [`Content`].
 
 use crate::audio;
+use crate::metre::Changing;
+use crate::metre::Instant;
 use crate::metre::NonZeroDuration;
 use crate::metre::OffsetMapping;
+use crate::metre::TimeContext;
 use crate::note;
+use crate::note::InsertionError;
+use crate::project::track::clip::Link;
+use crate::time;
 use crate::ui::Length;
 use crate::view::Painter;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// The [content](Content) of a clip could not be changed as requested.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Error)]
+#[remain::sorted]
+pub enum ContentError {
+    /// The start of the content cannot be moved to where it was requested.
+    #[error("the start of the clip cannot be moved there")]
+    ImmovableStart,
+    /// The instant to split the content at is not inside it.
+    #[error("the clip cannot be split there")]
+    InvalidSplitPoint,
+    /// The notes did not fit in the changed content.
+    #[error("{0}")]
+    NoteInsertion(#[from] InsertionError),
+    /// The contents are not of kinds that can be joined.
+    #[error("the clips cannot be joined")]
+    Unjoinable,
+    /// The content is not of a kind that can be split.
+    #[error("the clip cannot be split")]
+    Unsplittable,
+}
 
 /// The content of a [clip](super::Clip).
 #[derive(Eq, PartialEq, Debug)]
 pub enum Content {
     /// An audio clip.
     Audio(audio::FixedLength),
+    /// A [drum pattern](note::Drums).
+    Drums(note::Drums),
+    /// Content that is shared between linked clips.
+    ///
+    /// Edits to the content through any of the clips show up in all of them.
+    Linked {
+        /// The link to the shared content.
+        link: Link,
+        /// The duration of the clip.
+        ///
+        /// The shared content is cropped or extended with silence to fit this.
+        duration: NonZeroDuration,
+    },
+    /// An audio clip whose audio is stored in a separate file.
+    LinkedAudio(audio::Linked),
     /// A [note group](note::Group).
     Notes(note::Group),
-    // TODO:
-    //  - linked audio file
-    //  - linked clip
-    //  - drums
 }
 
 impl Content {
@@ -25,16 +65,214 @@ impl Content {
     pub(crate) fn duration(&self) -> NonZeroDuration {
         match self {
             Content::Audio(audio) => audio.duration,
+            Content::Drums(drums) => drums.duration(),
+            Content::Linked { duration, .. } => *duration,
+            Content::LinkedAudio(audio) => audio.duration(),
             Content::Notes(notes) => notes.duration(),
         }
     }
 
+    /// Returns a copy of the content, with new ids.
+    pub(crate) fn copied(&self) -> Content {
+        match self {
+            Content::Audio(audio) => Content::Audio(audio.clone()),
+            Content::Drums(drums) => Content::Drums(drums.clone()),
+            Content::Linked { link, duration } => Content::Linked {
+                link: *link,
+                duration: *duration,
+            },
+            Content::LinkedAudio(audio) => Content::LinkedAudio(audio.clone()),
+            Content::Notes(notes) => Content::Notes(notes.copied()),
+        }
+    }
+
+    /// Returns a copy of the content cropped to a duration.
+    pub(crate) fn cropped(&self, duration: NonZeroDuration) -> Result<Content, InsertionError> {
+        match self {
+            Content::Audio(audio) => Ok(Content::Audio(audio::FixedLength {
+                audio: audio.audio.clone(),
+                duration,
+                offset: audio.offset,
+            })),
+            Content::Drums(drums) => Ok(Content::Drums(drums.cropped(duration))),
+            Content::Linked { link, .. } => Ok(Content::Linked {
+                link: *link,
+                duration,
+            }),
+            Content::LinkedAudio(audio) => Ok(Content::LinkedAudio(
+                audio.with_extent(duration, audio.offset()),
+            )),
+            Content::Notes(notes) => Ok(Content::Notes(notes.cropped(duration)?)),
+        }
+    }
+
+    /// Returns a copy of the content whose start is moved from `start` to `new_start`
+    /// and whose duration is `duration`.
+    ///
+    /// The content keeps its position in the track.
+    ///
+    /// # Errors
+    ///
+    /// The starts of drum patterns and linked content cannot be moved,
+    /// and audio cannot be extended before its beginning.
+    pub(crate) fn with_start(
+        &self,
+        start: Instant,
+        new_start: Instant,
+        duration: NonZeroDuration,
+        time_context: &Changing<TimeContext>,
+    ) -> Result<Content, ContentError> {
+        match self {
+            Content::Audio(audio) => Ok(Content::Audio(audio::FixedLength {
+                audio: audio.audio.clone(),
+                duration,
+                offset: moved_offset(audio.offset, start, new_start, time_context)?,
+            })),
+            Content::Drums(_) | Content::Linked { .. } => Err(ContentError::ImmovableStart),
+            Content::LinkedAudio(audio) => Ok(Content::LinkedAudio(audio.with_extent(
+                duration,
+                moved_offset(audio.offset(), start, new_start, time_context)?,
+            ))),
+            Content::Notes(notes) => Ok(Content::Notes(
+                notes.with_start(start, new_start, duration)?,
+            )),
+        }
+    }
+
+    /// Splits the content, which starts at `start`, at an instant.
+    ///
+    /// Notes that cross the instant are truncated on both sides.
+    ///
+    /// # Errors
+    ///
+    /// Drum patterns and linked content cannot be split,
+    /// and neither can content that the instant is not inside of.
+    pub(crate) fn split_at(
+        &self,
+        start: Instant,
+        at: Instant,
+        time_context: &Changing<TimeContext>,
+    ) -> Result<(Content, Content), ContentError> {
+        let end = start + self.duration().get();
+
+        let first_duration =
+            NonZeroDuration::from_duration(at - start).ok_or(ContentError::InvalidSplitPoint)?;
+        let second_duration =
+            NonZeroDuration::from_duration(end - at).ok_or(ContentError::InvalidSplitPoint)?;
+
+        match self {
+            Content::Audio(audio) => {
+                let (first, second) = audio
+                    .split_at(start, at, time_context)
+                    .ok_or(ContentError::InvalidSplitPoint)?;
+
+                Ok((Content::Audio(first), Content::Audio(second)))
+            }
+            Content::Drums(_) | Content::Linked { .. } => Err(ContentError::Unsplittable),
+            Content::LinkedAudio(_) | Content::Notes(_) => Ok((
+                self.cropped(first_duration)?,
+                self.with_start(start, at, second_duration, time_context)?,
+            )),
+        }
+    }
+
+    /// Joins the content, which starts at `start`, with content that starts where it ends.
+    ///
+    /// # Errors
+    ///
+    /// Only note groups can be joined with note groups
+    /// and only embedded audio can be joined with embedded audio.
+    pub(crate) fn joined(
+        &self,
+        start: Instant,
+        other: &Content,
+        time_context: &Changing<TimeContext>,
+    ) -> Result<Content, ContentError> {
+        if let (Content::Audio(audio), Content::Audio(other)) = (self, other) {
+            let audio = audio
+                .joined(start, other, time_context)
+                .ok_or(ContentError::Unjoinable)?;
+
+            return Ok(Content::Audio(audio));
+        }
+
+        if let (Content::Notes(notes), Content::Notes(other)) = (self, other) {
+            return Ok(Content::Notes(notes.joined(other)?));
+        }
+
+        Err(ContentError::Unjoinable)
+    }
+
+    /// Resolves linked content to the content that it is linked to.
+    ///
+    /// Other content is returned as is.
+    pub(crate) fn resolve<'content>(
+        &'content self,
+        linked_contents: &'content BTreeMap<Link, Content>,
+    ) -> Option<&'content Content> {
+        match self {
+            Content::Linked { link, .. } => linked_contents.get(link),
+            Content::Audio(_) | Content::Drums(_) | Content::LinkedAudio(_) | Content::Notes(_) => {
+                Some(self)
+            }
+        }
+    }
+
+    /// Returns the link to the shared content if the content is linked.
+    #[must_use]
+    pub fn link(&self) -> Option<Link> {
+        match self {
+            Content::Linked { link, .. } => Some(*link),
+            Content::Audio(_) | Content::Drums(_) | Content::LinkedAudio(_) | Content::Notes(_) => {
+                None
+            }
+        }
+    }
+
     /// Tries to resolve the content to an audio clip.
     #[must_use]
     pub fn as_audio(&self) -> Option<&audio::FixedLength> {
         match self {
             Content::Audio(audio) => Some(audio),
-            Content::Notes(_) => None,
+            Content::Drums(_)
+            | Content::Linked { .. }
+            | Content::LinkedAudio(_)
+            | Content::Notes(_) => None,
+        }
+    }
+
+    /// Tries to resolve the content to a linked audio clip.
+    #[must_use]
+    pub fn as_linked_audio(&self) -> Option<&audio::Linked> {
+        match self {
+            Content::LinkedAudio(audio) => Some(audio),
+            Content::Audio(_) | Content::Drums(_) | Content::Linked { .. } | Content::Notes(_) => {
+                None
+            }
+        }
+    }
+
+    /// Tries to resolve the content to a drum pattern.
+    #[must_use]
+    pub fn as_drums(&self) -> Option<&note::Drums> {
+        match self {
+            Content::Drums(drums) => Some(drums),
+            Content::Audio(_)
+            | Content::Linked { .. }
+            | Content::LinkedAudio(_)
+            | Content::Notes(_) => None,
+        }
+    }
+
+    /// Tries to resolve the content to a drum pattern.
+    #[must_use]
+    pub fn as_drums_mut(&mut self) -> Option<&mut note::Drums> {
+        match self {
+            Content::Drums(drums) => Some(drums),
+            Content::Audio(_)
+            | Content::Linked { .. }
+            | Content::LinkedAudio(_)
+            | Content::Notes(_) => None,
         }
     }
 
@@ -42,7 +280,10 @@ impl Content {
     #[must_use]
     pub fn as_notes(&self) -> Option<&note::Group> {
         match self {
-            Content::Audio(_) => None,
+            Content::Audio(_)
+            | Content::Drums(_)
+            | Content::Linked { .. }
+            | Content::LinkedAudio(_) => None,
             Content::Notes(notes) => Some(notes),
         }
     }
@@ -51,20 +292,59 @@ impl Content {
     #[must_use]
     pub fn as_notes_mut(&mut self) -> Option<&mut note::Group> {
         match self {
-            Content::Audio(_) => None,
+            Content::Audio(_)
+            | Content::Drums(_)
+            | Content::Linked { .. }
+            | Content::LinkedAudio(_) => None,
             Content::Notes(notes) => Some(notes),
         }
     }
 
     /// Returns a [painter](Painter) that can paint the clip overview.
+    ///
+    /// Linked content is painted as the shared content that it is linked to.
     pub(super) fn overview_painter(
         &self,
         offset_mapping: OffsetMapping,
         crop_start: Length,
+        linked_contents: &BTreeMap<Link, Content>,
     ) -> Box<Painter> {
         match self {
             Content::Audio(audio) => audio.overview_painter(offset_mapping, crop_start),
+            Content::Drums(drums) => drums.overview_painter(),
+            Content::Linked { link, .. } => match linked_contents.get(link) {
+                // Shared content is never linked itself, so this does not recurse further.
+                Some(content) => {
+                    content.overview_painter(offset_mapping, crop_start, linked_contents)
+                }
+                None => Box::new(|_| ()),
+            },
+            // TODO: draw a loudness graph, as for embedded audio
+            Content::LinkedAudio(_) => Box::new(|_| ()),
             Content::Notes(notes) => notes.overview_painter(),
         }
     }
 }
+
+/// Returns the offset into some audio after the start of its clip is moved from `start` to `new_start`.
+fn moved_offset(
+    offset: time::Duration,
+    start: Instant,
+    new_start: Instant,
+    time_context: &Changing<TimeContext>,
+) -> Result<time::Duration, ContentError> {
+    let start = start * time_context;
+    let new_start = new_start * time_context;
+
+    if start <= new_start {
+        return Ok(offset + (new_start - start));
+    }
+
+    let extension = start - new_start;
+
+    if offset < extension {
+        return Err(ContentError::ImmovableStart);
+    }
+
+    Ok(offset - extension)
+}
@@ -0,0 +1,37 @@
+//! Items pertaining to [`Link`].
+
+use serde::Deserialize;
+use serde::Serialize;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// An identifier of [content](super::Content) that is shared between linked clips.
+///
+/// Unlike [`Id`](crate::Id), a link is stored in the project file,
+/// so clips stay linked across saves.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Link {
+    /// The internal number.
+    number: u32,
+}
+
+impl Link {
+    /// The first link.
+    pub(crate) const FIRST: Link = Link { number: 0 };
+
+    /// Returns the link following this one.
+    #[must_use]
+    pub(crate) fn next(self) -> Link {
+        Link {
+            number: self.number.saturating_add(1),
+        }
+    }
+}
+
+impl Display for Link {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "link {}", self.number)
+    }
+}
@@ -1,15 +1,19 @@
 //! Items pertaining to [`Clip`].
 
 mod content;
+mod link;
 mod overview;
 mod path;
 mod serial;
 
 pub use content::Content;
+pub use content::ContentError;
+pub use link::Link;
 pub use path::Path;
 
 pub(in crate::project) use overview::overview;
 pub(in crate::project) use serial::Serial;
+pub(in crate::project) use serial::SerialContent;
 
 use crate::Id;
 use crate::audio::FixedLength;
@@ -27,6 +31,7 @@ use getset::CloneGetters;
 use getset::CopyGetters;
 use getset::Getters;
 use getset::MutGetters;
+use std::collections::BTreeMap;
 
 /// The default colour for audio clips.
 const DEFAULT_AUDIO_COLOUR: Colour = Colour::LIME;
@@ -36,9 +41,14 @@ const DEFAULT_NOTES_NAME: ArcStr = literal!("some notes");
 /// The default colour for note-group clips.
 const DEFAULT_NOTES_COLOUR: Colour = Colour::MAGENTA;
 
+/// The default name for drum clips.
+const DEFAULT_DRUMS_NAME: ArcStr = literal!("some drums");
+/// The default colour for drum clips.
+const DEFAULT_DRUMS_COLOUR: Colour = Colour::CYAN;
+
 /// A part of a [track](super::Track).
 // TODO: Test that this isn't `Clone` (bc. id).
-#[derive(Debug, Getters, MutGetters, CopyGetters, CloneGetters, MutGetters)]
+#[derive(Debug, Getters, MutGetters, CopyGetters, CloneGetters)]
 pub struct Clip {
     /// The id.
     #[get_copy = "pub(crate)"]
@@ -63,6 +73,16 @@ impl Clip {
         &mut self.content
     }
 
+    /// Sets the name.
+    pub(in crate::project) fn set_name(&mut self, name: ArcStr) {
+        self.name = name;
+    }
+
+    /// Sets the colour.
+    pub(in crate::project) fn set_colour(&mut self, colour: Colour) {
+        self.colour = colour;
+    }
+
     /// Constructs a new clip with a generated id.
     fn new(name: ArcStr, colour: Colour, content: Content) -> Clip {
         Clip {
@@ -89,6 +109,49 @@ impl Clip {
         )
     }
 
+    /// Constructs an empty drum clip.
+    #[must_use]
+    pub(crate) fn empty_drums(duration: NonZeroDuration, step_duration: NonZeroDuration) -> Clip {
+        Clip::new(
+            DEFAULT_DRUMS_NAME,
+            DEFAULT_DRUMS_COLOUR,
+            Content::Drums(note::Drums::new(duration, step_duration)),
+        )
+    }
+
+    /// Constructs a new clip, with a generated id and the same name and colour as this one,
+    /// that is linked to some shared content.
+    pub(crate) fn linked(&self, link: Link) -> Clip {
+        Clip::new(
+            self.name.clone(),
+            self.colour,
+            Content::Linked {
+                link,
+                duration: self.duration(),
+            },
+        )
+    }
+
+    /// Constructs a new clip, with a generated id and the same name and colour as this one,
+    /// with some content.
+    pub(crate) fn with_content(&self, content: Content) -> Clip {
+        Clip::new(self.name.clone(), self.colour, content)
+    }
+
+    /// Returns a copy of the clip, with a new id.
+    pub(crate) fn copied(&self) -> Clip {
+        self.with_content(self.content.copied())
+    }
+
+    /// Returns a copy of the clip, with a new id, cropped to a duration.
+    pub(crate) fn cropped(&self, duration: NonZeroDuration) -> Result<Clip, note::InsertionError> {
+        Ok(Clip::new(
+            self.name.clone(),
+            self.colour,
+            self.content.cropped(duration)?,
+        ))
+    }
+
     /// Returns the duration of the clip.
     pub(crate) fn duration(&self) -> NonZeroDuration {
         self.content.duration()
@@ -100,11 +163,26 @@ impl Clip {
         clip_start: Instant,
         time_context: &Changing<TimeContext>,
         sample_rate: sample::Rate,
+        linked_contents: &BTreeMap<Link, Content>,
     ) -> Sequence {
-        let Some(notes) = self.content.as_notes() else {
+        let Some(content) = self.content.resolve(linked_contents) else {
+            return Sequence::new();
+        };
+
+        if let Some(drums) = content.as_drums() {
+            return drums.to_events(clip_start, self.duration(), time_context, sample_rate);
+        }
+
+        let Some(notes) = content.as_notes() else {
             return Sequence::new();
         };
 
-        notes.to_events(clip_start, time_context, sample_rate)
+        notes.to_events(clip_start, self.duration(), time_context, sample_rate)
+    }
+
+    /// Returns whether the clip is linked to other clips.
+    #[must_use]
+    pub fn is_linked(&self) -> bool {
+        self.content.link().is_some()
     }
 }
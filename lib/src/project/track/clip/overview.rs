@@ -3,38 +3,74 @@
 use crate::Holdable;
 use crate::Id;
 use crate::Selectable;
+use crate::UserInterface;
 use crate::View;
 use crate::metre::OffsetMapping;
 use crate::project::Track;
 use crate::project::track::Clip;
+use crate::project::track::clip::Content;
+use crate::project::track::clip::Link;
 use crate::project::track::clip::Path;
 use crate::ui::Length;
+use crate::view::RenderArea;
+use crate::view::context::Menu;
+use arcstr::ArcStr;
+use arcstr::literal;
+use std::collections::BTreeMap;
+
+/// The mark added to the titles of linked clips.
+const LINKED: ArcStr = literal!("linked");
 
 /// Returns a view of a clip's overview.
-pub(in crate::project) fn overview(
+///
+/// The edges of the clip may be grabbed to trim or resize it.
+/// Linked clips are painted using `linked_contents`.
+pub(in crate::project) fn overview<Ui: UserInterface>(
     clip: &Clip,
     selected: bool,
     offset_mapping: OffsetMapping,
     crop_start: Length,
     track: Id<Track>,
+    linked_contents: &BTreeMap<Link, Content>,
 ) -> View {
     let path = Path::new(track, clip.id);
 
+    let title = if clip.is_linked() {
+        arcstr::format!("{} ({LINKED})", clip.name)
+    } else {
+        clip.name()
+    };
+
+    let grab_edge = move |render_area: RenderArea| {
+        let x = render_area.relative_mouse_position()?.x;
+
+        if x < Ui::BORDER_THICKNESS {
+            Some(Holdable::ClipStart(path))
+        } else if render_area.area.size.width - Ui::BORDER_THICKNESS <= x {
+            Some(Holdable::ClipEnd(path))
+        } else {
+            None
+        }
+    };
+
     View::y_stack([
         View::TitleBar {
-            title: clip.name(),
+            title,
             highlighted: selected,
         }
-        .grabbable(move |_| Some(Holdable::Clip(path)))
+        .grabbable(move |render_area| grab_edge(render_area).or(Some(Holdable::Clip(path))))
         .quoted_minimally(),
         View::canvas(
             clip.colour,
-            clip.content.overview_painter(offset_mapping, crop_start),
+            clip.content
+                .overview_painter(offset_mapping, crop_start, linked_contents),
         )
+        .grabbable(grab_edge)
         .fill_remaining(),
     ])
     .selectable(Selectable::Clip(Path {
         track,
         clip: clip.id,
     }))
+    .contextual(Menu::clip(path, clip.name()))
 }
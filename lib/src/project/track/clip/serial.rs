@@ -2,10 +2,13 @@
 
 use crate::audio;
 use crate::metre::Instant;
+use crate::metre::NonZeroDuration;
 use crate::note;
+use crate::note::InsertionError;
 use crate::project::track::Clip;
 use crate::project::track::clip;
 use crate::ui::Colour;
+use arcstr::ArcStr;
 use serde::Deserialize;
 use serde::Serialize;
 use std::borrow::Cow;
@@ -42,6 +45,20 @@ impl<'data> Serial<'data> {
             content: SerialContent::from(content),
         }
     }
+
+    /// Converts the serial representation into a clip (with a generated id) and its position.
+    pub(crate) fn into_clip(self) -> Result<(Instant, Clip), InsertionError> {
+        let Serial {
+            name,
+            position,
+            colour,
+            content,
+        } = self;
+
+        let content = clip::Content::try_from(content)?;
+
+        Ok((position, Clip::new(ArcStr::from(name), colour, content)))
+    }
 }
 
 /// The serial representation of [`Content`].
@@ -50,6 +67,17 @@ impl<'data> Serial<'data> {
 pub(in crate::project) enum SerialContent<'data> {
     /// An audio clip.
     Audio(Cow<'data, audio::FixedLength>),
+    /// A drum pattern.
+    Drums(note::drums::Serial<'data>),
+    /// Content that is shared between linked clips.
+    Linked {
+        /// The link to the shared content.
+        link: clip::Link,
+        /// The duration of the clip.
+        duration: NonZeroDuration,
+    },
+    /// An audio clip whose audio is stored in a separate file.
+    LinkedAudio(Cow<'data, audio::Linked>),
     /// An note group.
     Notes(note::group::Serial),
 }
@@ -58,7 +86,27 @@ impl<'data> From<&'data clip::Content> for SerialContent<'data> {
     fn from(content: &'data clip::Content) -> Self {
         match content {
             clip::Content::Audio(audio) => SerialContent::Audio(Cow::Borrowed(audio)),
+            clip::Content::Drums(drums) => SerialContent::Drums(note::drums::Serial::from(drums)),
+            clip::Content::Linked { link, duration } => SerialContent::Linked {
+                link: *link,
+                duration: *duration,
+            },
+            clip::Content::LinkedAudio(audio) => SerialContent::LinkedAudio(Cow::Borrowed(audio)),
             clip::Content::Notes(notes) => SerialContent::Notes(note::group::Serial::from(notes)),
         }
     }
 }
+
+impl TryFrom<SerialContent<'_>> for clip::Content {
+    type Error = InsertionError;
+
+    fn try_from(serial: SerialContent<'_>) -> Result<Self, Self::Error> {
+        Ok(match serial {
+            SerialContent::Audio(audio) => clip::Content::Audio(audio.into_owned()),
+            SerialContent::Drums(drums) => clip::Content::Drums(note::Drums::from(drums)),
+            SerialContent::Linked { link, duration } => clip::Content::Linked { link, duration },
+            SerialContent::LinkedAudio(audio) => clip::Content::LinkedAudio(audio.into_owned()),
+            SerialContent::Notes(notes) => clip::Content::Notes(note::Group::try_from(notes)?),
+        })
+    }
+}
@@ -0,0 +1,42 @@
+//! Items pertaining to [`Mixing`].
+
+use crate::audio::Gain;
+use crate::audio::Pan;
+use crate::audio::PanLaw;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// How a [track](super::Track) is mixed into the master.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Mixing {
+    /// The gain of the track.
+    pub gain: Gain,
+    /// The stereo position of the track.
+    pub pan: Pan,
+    /// The pan law with which the track is panned.
+    pub pan_law: PanLaw,
+    /// Whether the track is muted.
+    pub muted: bool,
+    /// Whether the track is soloed.
+    pub soloed: bool,
+}
+
+impl Mixing {
+    /// Returns whether the track should be heard.
+    ///
+    /// `solo_active` should be whether any track in the project is soloed.
+    #[must_use]
+    pub fn is_audible(self, solo_active: bool) -> bool {
+        !self.muted && (self.soloed || !solo_active)
+    }
+
+    /// Returns the factors by which the left and right channels of the track are multiplied.
+    #[must_use]
+    pub fn amplitudes(self) -> [f32; 2] {
+        let gain = self.gain.amplitude();
+        let [left, right] = self.pan.amplitudes(self.pan_law);
+
+        [left * gain, right * gain]
+    }
+}
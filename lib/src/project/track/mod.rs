@@ -1,33 +1,50 @@
 //! Items pertaining to [`Track`].
 
+pub mod automation;
 pub mod clip;
+mod mixing;
+mod overlap_policy;
 mod overview;
 mod serial;
 mod settings;
 
+#[doc(inline)]
+pub use automation::Automation;
 #[doc(inline)]
 pub use clip::Clip;
 
+pub use mixing::Mixing;
+pub use overlap_policy::OverlapPolicy;
+pub use serial::DeserialisationError;
+pub use serial::DeserialisationErrorKind;
+
 pub(super) use overview::overview;
 pub(super) use serial::Serial;
 pub(crate) use settings::settings;
 
 use crate::Audio;
 use crate::Id;
+use crate::audio;
+use crate::audio::LinkError;
 use crate::audio::sample;
 use crate::metre::Changing;
 use crate::metre::Duration;
 use crate::metre::Instant;
+use crate::metre::NonZeroDuration;
 use crate::metre::TimeContext;
 use crate::note::event::Sequence;
+use crate::project::DEFAULT_TRACK_COLOUR;
 use crate::project::DEFAULT_TRACK_NAME;
+use crate::ui::Colour;
 use arcstr::ArcStr;
 use getset::CloneGetters;
 use getset::CopyGetters;
 use getset::Getters;
 use getset::MutGetters;
+use getset::Setters;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::path::Path;
 use thiserror::Error;
 
 /// An error occurred when trying to insert a clip.
@@ -43,6 +60,12 @@ pub struct ClipInsertionError {
 /// An error occurred when trying to insert a clip.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Error)]
 pub enum ClipInsertionErrorKind {
+    /// The clip would overlap another clip.
+    #[error("the clip would overlap another clip")]
+    Overlap {
+        /// The clip that would be overlapped.
+        clip: Id<Clip>,
+    },
     /// Tried inserting a clip at a position where there was already a clip.
     #[error("there is already a clip at that position")]
     PositionOccupied,
@@ -50,14 +73,27 @@ pub enum ClipInsertionErrorKind {
 
 /// A musical track.
 // TODO: Test that this isn't `Clone` (bc. id).
-#[derive(Debug, Getters, MutGetters, CopyGetters, CloneGetters)]
+#[derive(Debug, Getters, MutGetters, CopyGetters, CloneGetters, Setters)]
 pub struct Track {
     /// The id.
     #[get_copy = "pub(super)"]
     id: Id<Track>,
     /// The name.
     #[get_clone = "pub(super)"]
+    #[set = "pub(super)"]
     name: ArcStr,
+    /// The colour.
+    #[get_copy = "pub(crate)"]
+    #[set = "pub(super)"]
+    colour: Colour,
+    /// How the track is mixed into the master.
+    #[get_copy = "pub(crate)"]
+    #[get_mut = "pub(super)"]
+    mixing: Mixing,
+    /// How the parameters of the track change over time.
+    #[get = "pub(crate)"]
+    #[get_mut = "pub(super)"]
+    automation: Automation,
 
     // TODO: use a double-key map
     /// A map from clip positions to clip ids.
@@ -68,6 +104,54 @@ pub struct Track {
     clips: HashMap<Id<Clip>, Clip>,
 }
 
+/// A clip of a linked audio file, placed in a track.
+///
+/// The file is decoded when the track is rendered.
+#[derive(Clone, Debug)]
+pub(crate) struct LinkedAudioClip {
+    /// The linked audio.
+    pub audio: audio::Linked,
+    /// The offset of the clip from the start of the track.
+    pub start: sample::Duration,
+    /// The duration of the clip.
+    pub duration: sample::Duration,
+}
+
+/// A clip whose content has been [resolved](clip::Content::resolve).
+#[derive(Copy, Clone, Debug)]
+struct ResolvedClip<'track> {
+    /// The duration of the clip.
+    duration: NonZeroDuration,
+    /// The resolved content.
+    content: &'track clip::Content,
+}
+
+impl LinkedAudioClip {
+    /// Superposes the audio of the clip onto `audio`, decoding the file if needed.
+    ///
+    /// The path of the file is resolved relative to `directory`.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be read, an error is returned and `audio` is left unchanged.
+    pub(crate) fn superpose_onto(
+        &self,
+        audio: &mut Audio,
+        directory: &Path,
+    ) -> Result<(), LinkError> {
+        let sample_rate = audio.sample_rate;
+
+        audio.superpose_cropped(
+            self.audio.audio(directory)?,
+            self.start,
+            self.audio.offset() * sample_rate,
+            self.duration,
+        );
+
+        Ok(())
+    }
+}
+
 impl Track {
     /// Constructs a new, empty, track.
     #[must_use]
@@ -75,12 +159,44 @@ impl Track {
         Track {
             id: Id::generate(),
             name: DEFAULT_TRACK_NAME,
+            colour: DEFAULT_TRACK_COLOUR,
+            mixing: Mixing::default(),
+            automation: Automation::default(),
             clip_ids: BTreeMap::new(),
             clip_starts: HashMap::new(),
             clips: HashMap::new(),
         }
     }
 
+    /// Returns a copy of the track, and of its clips, with new ids.
+    pub(super) fn copied(&self) -> Track {
+        let mut track = Track {
+            id: Id::generate(),
+            name: self.name.clone(),
+            colour: self.colour,
+            mixing: self.mixing,
+            automation: self.automation.clone(),
+            clip_ids: BTreeMap::new(),
+            clip_starts: HashMap::new(),
+            clips: HashMap::new(),
+        };
+
+        for (start, clip) in &self.clip_ids {
+            let Some(clip) = self.clips.get(clip) else {
+                continue;
+            };
+
+            let copy = clip.copied();
+            let id = copy.id();
+
+            track.clip_ids.insert(*start, id);
+            track.clip_starts.insert(id, *start);
+            track.clips.insert(id, copy);
+        }
+
+        track
+    }
+
     /// Returns a reference to a clip.
     #[must_use]
     pub(super) fn clip(&self, id: Id<Clip>) -> Option<(Instant, &Clip)> {
@@ -89,6 +205,18 @@ impl Track {
         Some((*start, clip))
     }
 
+    /// Returns the links of all linked clips in the track.
+    pub(super) fn links(&self) -> impl Iterator<Item = clip::Link> {
+        self.clips.values().filter_map(|clip| clip.content().link())
+    }
+
+    /// Returns the paths of the files of all linked audio clips in the track.
+    pub(super) fn linked_files(&self) -> impl Iterator<Item = &Path> {
+        self.clips
+            .values()
+            .filter_map(|clip| Some(clip.content().as_linked_audio()?.path().as_path()))
+    }
+
     /// Returns a mutable reference to a clip.
     #[must_use]
     pub(super) fn clip_mut(&mut self, id: Id<Clip>) -> Option<(Instant, &mut Clip)> {
@@ -106,6 +234,48 @@ impl Track {
         Some((start, clip))
     }
 
+    /// Returns the clip that starts before an instant and ends after it, if any.
+    pub(super) fn clip_around(&self, instant: Instant) -> Option<(Instant, Id<Clip>)> {
+        let (start, id) = self.clip_ids.range(..instant).next_back()?;
+        let clip = self.clips.get(id)?;
+
+        (instant < *start + clip.duration().get()).then_some((*start, *id))
+    }
+
+    /// Returns the first clip that starts in the period from `start` (inclusive) to `end`.
+    pub(super) fn first_clip_in(
+        &self,
+        start: Instant,
+        end: Instant,
+    ) -> Option<(Instant, Id<Clip>)> {
+        self.clip_ids
+            .range(start..end)
+            .next()
+            .map(|(start, id)| (*start, *id))
+    }
+
+    /// Moves all clips that start at or after an instant later by a duration.
+    ///
+    /// Returns the moved clips along with their original positions, the last clip first.
+    pub(super) fn shift_clips(&mut self, from: Instant, by: Duration) -> Vec<(Id<Clip>, Instant)> {
+        let shifted = self.clip_ids.split_off(&from);
+
+        let moved = shifted
+            .iter()
+            .rev()
+            .map(|(start, id)| (*id, *start))
+            .collect();
+
+        for (start, id) in shifted {
+            let start = start + by;
+
+            self.clip_ids.insert(start, id);
+            self.clip_starts.insert(id, start);
+        }
+
+        moved
+    }
+
     /// Removes a clip from the track.
     fn minimum_duration(&self) -> Duration {
         let Some((start, clip_id)) = self.clip_ids.last_key_value() else {
@@ -119,11 +289,15 @@ impl Track {
         (*start + clip.duration().get()).since_start
     }
 
-    /// Returns the superposition of all audio clips.
+    /// Returns the superposition of all embedded audio clips.
+    ///
+    /// Linked clips are resolved using `linked_contents`.
+    /// Linked audio files are not decoded, see [`Track::linked_audio`].
     pub(crate) fn audio_superposition(
         &self,
         time_context: &Changing<TimeContext>,
         sample_rate: sample::Rate,
+        linked_contents: &BTreeMap<clip::Link, clip::Content>,
     ) -> Audio {
         let minimum_end = Instant {
             since_start: self.minimum_duration(),
@@ -133,15 +307,18 @@ impl Track {
 
         let mut audio = Audio::with_capacity(sample_rate, minimum_duration);
 
-        for (start, clip_id) in &self.clip_ids {
-            let Some(clip) = self.clips.get(clip_id) else {
-                continue;
-            };
-
-            if let Some(clip) = clip.content().as_audio() {
-                let clip_start = *start * time_context * sample_rate;
+        for (start, clip) in self.resolved_clips(linked_contents) {
+            if let Some(audio_clip) = clip.content.as_audio() {
+                let clip_start = start * time_context * sample_rate;
+                let clip_end = (start + clip.duration.get()) * time_context * sample_rate;
 
-                audio.superpose_with_offset(&clip.audio, clip_start.since_start);
+                // The audio is cropped to the duration of the clip.
+                audio.superpose_cropped(
+                    &audio_clip.audio,
+                    clip_start.since_start,
+                    audio_clip.offset * sample_rate,
+                    clip_end - clip_start,
+                );
             }
         }
 
@@ -150,16 +327,69 @@ impl Track {
         audio
     }
 
+    /// Returns the clips of linked audio files.
+    ///
+    /// These are kept apart from the [superposition](Track::audio_superposition) of the other clips
+    /// so that the files can be decoded while rendering.
+    /// Linked clips are resolved using `linked_contents`.
+    pub(crate) fn linked_audio(
+        &self,
+        time_context: &Changing<TimeContext>,
+        sample_rate: sample::Rate,
+        linked_contents: &BTreeMap<clip::Link, clip::Content>,
+    ) -> Vec<LinkedAudioClip> {
+        self.resolved_clips(linked_contents)
+            .filter_map(|(start, clip)| {
+                let audio = clip.content.as_linked_audio()?;
+
+                let clip_start = start * time_context * sample_rate;
+                let clip_end = (start + clip.duration.get()) * time_context * sample_rate;
+
+                Some(LinkedAudioClip {
+                    audio: audio.clone(),
+                    start: clip_start.since_start,
+                    duration: clip_end - clip_start,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the clips of the track in order, along with their starts,
+    /// durations and [resolved](clip::Content::resolve) contents.
+    fn resolved_clips<'track>(
+        &'track self,
+        linked_contents: &'track BTreeMap<clip::Link, clip::Content>,
+    ) -> impl Iterator<Item = (Instant, ResolvedClip<'track>)> {
+        self.clip_ids.iter().filter_map(|(start, clip_id)| {
+            let clip = self.clips.get(clip_id)?;
+            let content = clip.content().resolve(linked_contents)?;
+
+            Some((
+                *start,
+                ResolvedClip {
+                    duration: clip.duration(),
+                    content,
+                },
+            ))
+        })
+    }
+
     /// Returns all events in the track.
+    ///
+    /// Linked clips are resolved using `linked_contents`.
     pub(crate) fn events(
         &self,
         time_context: &Changing<TimeContext>,
         sample_rate: sample::Rate,
+        linked_contents: &BTreeMap<clip::Link, clip::Content>,
     ) -> Sequence {
         self.clip_ids
             .iter()
             .filter_map(|(start, clip_id)| Some((start, self.clips.get(clip_id)?)))
-            .flat_map(|(start, clip)| clip.events(*start, time_context, sample_rate).into_iter())
+            .flat_map(|(start, clip)| {
+                clip.events(*start, time_context, sample_rate, linked_contents)
+                    .into_iter()
+            })
             .collect()
     }
 
@@ -167,11 +397,41 @@ impl Track {
     ///
     /// # Errors
     ///
-    /// If there already exists a clip at the exact position, an error will be returned.
+    /// If there already exists a clip at the exact position,
+    /// or if the clip would overlap another clip, an error will be returned.
     pub(super) fn try_insert_clip(
         &mut self,
         position: Instant,
         clip: Clip,
+    ) -> Result<clip::Path, ClipInsertionError> {
+        let end = position + clip.duration().get();
+
+        // A clip at the exact position is reported as such, rather than as an overlap.
+        if let Some((start, overlapped)) = self
+            .clip_around(position)
+            .or_else(|| self.first_clip_in(position, end))
+            && start != position
+        {
+            return Err(ClipInsertionError {
+                clip: Box::new(clip),
+                kind: ClipInsertionErrorKind::Overlap { clip: overlapped },
+            });
+        }
+
+        self.insert_overlapping_clip(position, clip)
+    }
+
+    /// Tries to insert a clip at the given position, even if it overlaps other clips.
+    ///
+    /// This is used when loading projects that were saved before overlaps were rejected.
+    ///
+    /// # Errors
+    ///
+    /// If there already exists a clip at the exact position, an error will be returned.
+    fn insert_overlapping_clip(
+        &mut self,
+        position: Instant,
+        clip: Clip,
     ) -> Result<clip::Path, ClipInsertionError> {
         if self.clip_ids.contains_key(&position) {
             return Err(ClipInsertionError {
@@ -180,8 +440,6 @@ impl Track {
             });
         }
 
-        // TODO: check for overlap
-
         let clip_id = clip.id();
 
         self.clip_ids.insert(position, clip_id);
@@ -0,0 +1,35 @@
+//! Items pertaining to [`OverlapPolicy`].
+
+use enum_iterator::Sequence;
+use serde::Deserialize;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// What to do when a clip is placed so that it would overlap other clips.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default, Sequence, Deserialize)]
+#[remain::sorted]
+#[serde(rename_all = "snake_case")]
+pub enum OverlapPolicy {
+    /// The clips that start after the clip are moved later to make room for it.
+    ///
+    /// The clip may still not start inside another clip.
+    Push,
+    /// The clip is not placed.
+    #[default]
+    Reject,
+    /// The clip which the clip starts inside of is shortened to make room for it.
+    ///
+    /// The clip may still not overlap clips that start after it.
+    Trim,
+}
+
+impl Display for OverlapPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            OverlapPolicy::Push => write!(f, "push"),
+            OverlapPolicy::Reject => write!(f, "reject"),
+            OverlapPolicy::Trim => write!(f, "trim"),
+        }
+    }
+}
@@ -3,11 +3,13 @@
 use crate::Holdable;
 use crate::Id;
 use crate::Selectable;
+use crate::UserInterface;
 use crate::View;
 use crate::app::Action;
 use crate::audio::Player;
 use crate::metre::Changing;
 use crate::metre::Instant;
+use crate::metre::NonZeroDuration;
 use crate::metre::OffsetMapping;
 use crate::metre::TimeContext;
 use crate::project::Edit;
@@ -21,10 +23,13 @@ use crate::view::CursorWindow;
 use crate::view::RenderArea;
 use crate::view::context::Menu;
 use bon::builder;
+use std::collections::BTreeMap;
 
 /// Returns the overview of a track (the horizontally scrollable section of clip overviews).
+///
+/// Linked clips are painted using `linked_contents`.
 #[builder]
-pub fn overview(
+pub fn overview<Ui: UserInterface>(
     track: &Track,
     selection: &Selection,
     offset_mapping: OffsetMapping,
@@ -33,6 +38,7 @@ pub fn overview(
     cursor: Instant,
     player: Option<Player>,
     held_clip: Option<Id<Clip>>,
+    linked_contents: &BTreeMap<clip::Link, clip::Content>,
 ) -> View {
     let clips = View::Layers(
         track
@@ -61,8 +67,14 @@ pub fn overview(
 
                 let clip_width = clip_end_offset - clip_offset;
 
-                let overview =
-                    clip::overview(clip, selected, offset_mapping.clone(), start_crop, track.id);
+                let overview = clip::overview::<Ui>(
+                    clip,
+                    selected,
+                    offset_mapping.clone(),
+                    start_crop,
+                    track.id,
+                    linked_contents,
+                );
 
                 overview.quoted(clip_width).x_positioned(clip_offset)
             })
@@ -71,27 +83,49 @@ pub fn overview(
 
     let object_acceptor = {
         let offset_mapping = offset_mapping.clone();
+        let clip_starts = track.clip_starts.clone();
         let track = track.id;
 
         move |holdable, render_area: RenderArea| {
-            let Holdable::Clip(clip) = holdable else {
-                return None;
-            };
-
             let mouse = render_area.relative_mouse_position()?;
 
             let position = offset_mapping.quantised_instant(mouse.x + negative_overview_offset);
 
-            Some(Action::Edit(Edit::MoveClip {
-                clip,
-                track,
-                position,
-            }))
+            let edit = match holdable {
+                Holdable::Clip(clip) => Edit::MoveClip {
+                    clip,
+                    track,
+                    position,
+                },
+                Holdable::ClipEnd(clip) => {
+                    let start = clip_starts.get(&clip.clip)?;
+
+                    Edit::ResizeClip {
+                        clip,
+                        duration: NonZeroDuration::from_duration(position - *start)?,
+                    }
+                }
+                Holdable::ClipStart(clip) => Edit::TrimClipStart {
+                    clip,
+                    start: position,
+                },
+                Holdable::LoopRegion { .. }
+                | Holdable::Note { .. }
+                | Holdable::NoteCreation { .. }
+                | Holdable::NoteEnd { .. }
+                | Holdable::PianoRollHandle { .. }
+                | Holdable::Popup { .. }
+                | Holdable::PopupSide { .. }
+                | Holdable::SelectionBox { .. }
+                | Holdable::Track(_) => return None,
+            };
+
+            Some(Action::Edit(edit))
         }
     };
 
     let background = View::Empty
-        .contextual(Menu::track_overview())
+        .contextual(Menu::track_overview(offset_mapping.quantisation()))
         .grabbable(|render_area| {
             Some(Holdable::SelectionBox {
                 start: render_area.mouse_position,
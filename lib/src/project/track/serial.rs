@@ -1,25 +1,75 @@
 //! Items pertaining to [`Serial`].
 
+use crate::note::InsertionError;
+use crate::project::DEFAULT_TRACK_COLOUR;
 use crate::project::Track;
+use crate::project::track::Automation;
+use crate::project::track::ClipInsertionErrorKind;
+use crate::project::track::Mixing;
 use crate::project::track::clip;
+use crate::ui::Colour;
+use arcstr::ArcStr;
 use serde::Deserialize;
 use serde::Serialize;
 use std::borrow::Cow;
+use thiserror::Error;
 
 /// The serial representation of a [track](Track).
 #[derive(Serialize, Deserialize)]
 pub(in crate::project) struct Serial<'data> {
     /// The name.
     pub name: Cow<'data, str>,
+    /// The colour.
+    #[serde(default = "default_colour", skip_serializing_if = "is_default_colour")]
+    pub colour: Colour,
+    /// How the track is mixed.
+    #[serde(default)]
+    pub mixing: Mixing,
+    /// How the parameters of the track are automated.
+    #[serde(default, skip_serializing_if = "Automation::is_empty")]
+    pub automation: Automation,
     /// The clips.
     pub clips: Vec<clip::Serial<'data>>,
 }
 
+/// An error occurred when trying to deserialise a track.
+#[derive(Debug, Error)]
+#[error("clip \"{clip}\" in track \"{track}\": {kind}")]
+pub struct DeserialisationError {
+    /// The name of the track.
+    pub track: ArcStr,
+    /// The name of the clip.
+    pub clip: ArcStr,
+    /// The kind of error that occurred.
+    pub kind: DeserialisationErrorKind,
+}
+
+/// An error occurred when trying to deserialise a track.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Error)]
+#[remain::sorted]
+pub enum DeserialisationErrorKind {
+    /// The clip could not be inserted into the track.
+    #[error("{0}")]
+    ClipInsertion(#[from] ClipInsertionErrorKind),
+    /// The clip is linked to shared content that does not exist.
+    #[error("the clip is linked to content that does not exist ({link})")]
+    DanglingLink {
+        /// The link to the non-existent content.
+        link: clip::Link,
+    },
+    /// A note in the clip could not be inserted.
+    #[error("{0}")]
+    NoteInsertion(#[from] InsertionError),
+}
+
 impl<'data> From<&'data Track> for Serial<'data> {
     fn from(track: &'data Track) -> Self {
         let Track {
             id: _,
             name,
+            colour,
+            mixing,
+            automation,
             clip_ids,
             clip_starts: _,
             clips,
@@ -37,6 +87,69 @@ impl<'data> From<&'data Track> for Serial<'data> {
             })
             .collect();
 
-        Serial { name, clips }
+        Serial {
+            name,
+            colour: *colour,
+            mixing: *mixing,
+            automation: automation.clone(),
+            clips,
+        }
     }
 }
+
+impl TryFrom<Serial<'_>> for Track {
+    type Error = DeserialisationError;
+
+    fn try_from(serial: Serial<'_>) -> Result<Self, Self::Error> {
+        let Serial {
+            name,
+            colour,
+            mixing,
+            automation,
+            clips,
+        } = serial;
+
+        let mut track = Track::new();
+        track.name = ArcStr::from(name);
+        track.colour = colour;
+        track.mixing = mixing;
+        track.automation = automation;
+
+        for clip in clips {
+            let clip_name = ArcStr::from(&*clip.name);
+
+            let result = clip
+                .into_clip()
+                .map_err(DeserialisationErrorKind::from)
+                .and_then(|(position, clip)| {
+                    track
+                        .insert_overlapping_clip(position, clip)
+                        .map_err(|error| DeserialisationErrorKind::from(error.kind))
+                });
+
+            if let Err(kind) = result {
+                return Err(DeserialisationError {
+                    track: track.name(),
+                    clip: clip_name,
+                    kind,
+                });
+            }
+        }
+
+        Ok(track)
+    }
+}
+
+/// Returns the colour of tracks saved before tracks had colours.
+fn default_colour() -> Colour {
+    DEFAULT_TRACK_COLOUR
+}
+
+/// Returns whether the colour is that of [`default_colour`].
+#[expect(
+    clippy::trivially_copy_pass_by_ref,
+    reason = "this is used with `serde(skip_serializing_if)`"
+)]
+fn is_default_colour(colour: &Colour) -> bool {
+    *colour == default_colour()
+}
@@ -1,17 +1,216 @@
 //! File for the [`settings`] function.
 
+use crate::Holdable;
+use crate::Id;
 use crate::Selectable;
+use crate::UserInterface;
 use crate::View;
 use crate::app::Action;
+use crate::audio::Gain;
+use crate::project::Edit;
 use crate::project::Track;
+use crate::project::track::Mixing;
+use crate::string::ToArcStr as _;
+use crate::ui::ThemeColour;
+use crate::view::Axis;
+use crate::view::OnClick;
+use crate::view::RenderArea;
 use crate::view::ToText as _;
+use crate::view::context::Menu;
+use arcstr::ArcStr;
 use arcstr::literal;
+use enum_iterator::next_cycle;
+
+/// The label for the buttons that decrease the gain.
+const QUIETER: ArcStr = literal!("-");
+/// The label for the buttons that increase the gain.
+const LOUDER: ArcStr = literal!("+");
+/// The label for the buttons that pan to the left.
+const LEFT: ArcStr = literal!("<");
+/// The label for the buttons that pan to the right.
+const RIGHT: ArcStr = literal!(">");
+/// The label for the mute buttons.
+const MUTE: ArcStr = literal!("M");
+/// The label for the solo buttons.
+const SOLO: ArcStr = literal!("S");
+/// The label for the buttons that show the automation lanes.
+const AUTOMATION: ArcStr = literal!("A");
+
+/// The number of decibels by which the gain buttons change the gain.
+const GAIN_STEP: f32 = 1.0;
+/// The number of percentage points by which the left pan button moves the pan.
+const PAN_STEP_LEFT: i8 = -10;
+/// The number of percentage points by which the right pan button moves the pan.
+const PAN_STEP_RIGHT: i8 = 10;
 
 /// Returns the track settings.
-pub(crate) fn settings(track: &Track, selected: bool) -> View {
-    literal!("TODO")
-        .centred()
-        .bordered_with_title_and_thickness(track.name.clone(), selected)
-        .scrollable(Action::MoveOverview)
-        .selectable(Selectable::Track(track.id))
+///
+/// `index` is the index of the track in the track list
+/// and `expanded` is whether the automation lanes of the track are shown.
+///
+/// The settings can be dragged by their title, to move the track in the track list,
+/// and right-clicked to open the [track menu](Menu::track).
+pub(crate) fn settings<Ui: UserInterface>(
+    track: &Track,
+    index: usize,
+    selected: bool,
+    expanded: bool,
+) -> View {
+    let id = track.id;
+
+    let grab_title = move |render_area: RenderArea| {
+        let mouse_position = render_area.relative_mouse_position()?;
+
+        (mouse_position.y < Ui::BORDER_THICKNESS).then_some(Holdable::Track(id))
+    };
+
+    let drop_track = move |object, _| {
+        let Holdable::Track(moved) = object else {
+            return None;
+        };
+
+        (moved != id).then_some(Action::Edit(Edit::MoveTrack {
+            track: moved,
+            to_index: index,
+        }))
+    };
+
+    View::balanced_stack(
+        Axis::Y,
+        [
+            gain_buttons(id, track.mixing),
+            pan_buttons(id, track.mixing),
+            switches(track, expanded),
+        ],
+    )
+    .bordered_with_title_and_thickness(track.name.clone(), selected)
+    .grabbable(grab_title)
+    .object_accepting(drop_track)
+    .scrollable(Action::MoveOverview)
+    .selectable(Selectable::Track(track.id))
+    .contextual(Menu::track(track.id, track.name()))
+}
+
+/// Returns an action that sets the mixing of a track.
+fn set(track: Id<Track>, mixing: Mixing) -> OnClick {
+    OnClick::from(Edit::SetMixing { track, mixing })
+}
+
+/// Returns the gain of a track surrounded by buttons that change it.
+fn gain_buttons(track: Id<Track>, mixing: Mixing) -> View {
+    let gain_with_offset = |offset: f32| Gain {
+        decibels: mixing.gain.decibels + offset,
+    };
+
+    View::balanced_stack(
+        Axis::X,
+        [
+            View::standard_button(
+                QUIETER,
+                set(
+                    track,
+                    Mixing {
+                        gain: gain_with_offset(-GAIN_STEP),
+                        ..mixing
+                    },
+                ),
+            ),
+            mixing.gain.to_arc_str().centred(),
+            View::standard_button(
+                LOUDER,
+                set(
+                    track,
+                    Mixing {
+                        gain: gain_with_offset(GAIN_STEP),
+                        ..mixing
+                    },
+                ),
+            ),
+        ],
+    )
+}
+
+/// Returns the pan of a track surrounded by buttons that change it.
+fn pan_buttons(track: Id<Track>, mixing: Mixing) -> View {
+    View::balanced_stack(
+        Axis::X,
+        [
+            View::standard_button(
+                LEFT,
+                set(
+                    track,
+                    Mixing {
+                        pan: mixing.pan.moved_by(PAN_STEP_LEFT),
+                        ..mixing
+                    },
+                ),
+            ),
+            mixing.pan.to_arc_str().centred(),
+            View::standard_button(
+                RIGHT,
+                set(
+                    track,
+                    Mixing {
+                        pan: mixing.pan.moved_by(PAN_STEP_RIGHT),
+                        ..mixing
+                    },
+                ),
+            ),
+        ],
+    )
+}
+
+/// Returns the mute, solo, automation, pan law and colour buttons of a track.
+fn switches(track: &Track, expanded: bool) -> View {
+    let id = track.id;
+    let mixing = track.mixing;
+
+    View::balanced_stack(
+        Axis::X,
+        [
+            View::toggle(
+                MUTE,
+                set(
+                    id,
+                    Mixing {
+                        muted: !mixing.muted,
+                        ..mixing
+                    },
+                ),
+                mixing.muted,
+            ),
+            View::toggle(
+                SOLO,
+                set(
+                    id,
+                    Mixing {
+                        soloed: !mixing.soloed,
+                        ..mixing
+                    },
+                ),
+                mixing.soloed,
+            ),
+            View::toggle(
+                AUTOMATION,
+                OnClick::from(Action::ToggleAutomation(id)),
+                expanded,
+            ),
+            View::standard_button(
+                mixing.pan_law.to_arc_str(),
+                set(
+                    id,
+                    Mixing {
+                        pan_law: next_cycle(&mixing.pan_law),
+                        ..mixing
+                    },
+                ),
+            ),
+            View::Solid(ThemeColour::Custom(track.colour)).on_click(OnClick::from(
+                Edit::SetTrackColour {
+                    track: id,
+                    colour: track.colour.next_in_palette(),
+                },
+            )),
+        ],
+    )
 }
@@ -1,6 +1,7 @@
 //! File for the [`workspace`] function.
 
 use crate::Holdable;
+use crate::Id;
 use crate::Project;
 use crate::UserInterface;
 use crate::View;
@@ -12,26 +13,47 @@ use crate::metre::OffsetMapping;
 use crate::metre::Quantisation;
 use crate::metre::TimeContext;
 use crate::project;
+use crate::project::ADD_MARKER_DESCRIPTION;
+use crate::project::ADD_MARKER_LABEL;
 use crate::project::ADD_TRACK_DESCRIPTION;
 use crate::project::ADD_TRACK_LABEL;
+use crate::project::Track;
+use crate::project::track::automation;
+use crate::project::track::automation::Target;
 use crate::project::track::clip;
 use crate::project::track::overview;
 use crate::project::track::settings;
 use crate::select::Selection;
+use crate::string::ToArcStr as _;
 use crate::ui;
+use crate::ui::Colour;
 use crate::ui::Length;
 use crate::ui::Size;
+use crate::ui::ThemeColour;
 use crate::ui::relative;
 use crate::view::Axis;
 use crate::view::CursorWindow;
 use crate::view::OnClick;
+use crate::view::ToText as _;
+use crate::view::context::Menu;
 use crate::view::ruler;
+use arcstr::ArcStr;
+use bon::builder;
+use enum_iterator::all;
 use non_zero::non_zero;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::num::NonZeroU64;
 use std::sync::Arc;
 
+/// The colour of the loop region on the ruler.
+const LOOP_REGION_COLOUR: Colour = Colour::DODGER_BLUE;
+
 /// The project workspace.
 ///
-/// This includes the [track area](track_area) and the [ruler](Ruler) above it.
+/// This includes the [track area](track_area), the [ruler](Ruler) above it
+/// and the [marker lane](marker_lane) above that.
+#[builder]
 pub(crate) fn workspace<Ui: UserInterface>(
     project: &Project,
     selection: &Selection,
@@ -40,35 +62,99 @@ pub(crate) fn workspace<Ui: UserInterface>(
     cursor: Instant,
     player: Option<&Player>,
     held_object: Option<Holdable>,
+    expanded_tracks: &HashSet<Id<Track>>,
 ) -> View {
     let offset_mapping = OffsetMapping::new(project.time_signature.clone(), quantisation);
 
-    let ruler = ruler(ui_settings.negative_overview_offset, offset_mapping.clone());
+    let loop_region = project.loop_region.map_or(View::Empty, |region| {
+        let start = offset_mapping.offset(region.start) - ui_settings.negative_overview_offset;
+        let end = offset_mapping.offset(region.get().end()) - ui_settings.negative_overview_offset;
+
+        View::Solid(ThemeColour::Custom(LOOP_REGION_COLOUR))
+            .quoted(end - start)
+            .x_positioned(start)
+    });
+
+    let ruler = View::Layers(vec![
+        loop_region,
+        ruler(ui_settings.negative_overview_offset, offset_mapping.clone()),
+    ]);
     let ruler_row = ruler
         .scrollable(Action::MoveOverview)
         .fill_remaining()
         .x_positioned(ui_settings.track_settings_width.get());
 
-    let track_area = track_area(
-        project,
-        selection,
-        ui_settings,
-        offset_mapping,
-        cursor,
-        player,
-        held_object,
-    );
+    let add_marker_button =
+        View::hoverable(ADD_MARKER_LABEL.centred(), ADD_MARKER_DESCRIPTION.centred())
+            .on_click(OnClick::from(project::Edit::AddMarker));
+
+    let marker_row = View::x_stack([
+        add_marker_button.quoted(ui_settings.track_settings_width),
+        marker_lane(
+            &project.markers,
+            &offset_mapping,
+            ui_settings.negative_overview_offset,
+        )
+        .fill_remaining(),
+    ]);
+
+    let track_area = track_area::<Ui>()
+        .project(project)
+        .selection(selection)
+        .ui_settings(ui_settings)
+        .offset_mapping(offset_mapping)
+        .cursor(cursor)
+        .maybe_player(player)
+        .maybe_held_object(held_object)
+        .expanded_tracks(expanded_tracks)
+        .call();
 
     View::y_stack([
+        marker_row.quoted(Ui::RULER_HEIGHT),
         ruler_row.quoted(Ui::RULER_HEIGHT),
         track_area.fill_remaining(),
     ])
 }
 
+/// Returns a view for the marker lane.
+///
+/// Each marker spans until the next one and can be clicked to move the cursor to it.
+fn marker_lane(
+    markers: &BTreeMap<Instant, ArcStr>,
+    offset_mapping: &OffsetMapping,
+    negative_overview_offset: Length,
+) -> View {
+    let ends = markers
+        .keys()
+        .skip(1)
+        .map(|end| Some(offset_mapping.offset(*end) - negative_overview_offset))
+        .chain([None]);
+
+    let markers = markers.iter().zip(ends).map(|((at, name), end)| {
+        let offset = offset_mapping.offset(*at) - negative_overview_offset;
+
+        let marker = View::TitleBar {
+            title: name.clone(),
+            highlighted: false,
+        }
+        .on_click(OnClick::from(Action::MoveCursor(*at)))
+        .contextual(Menu::marker(*at, name.clone()));
+
+        match end {
+            Some(end) => marker.quoted(end - offset).x_positioned(offset),
+            None => marker.x_positioned(offset),
+        }
+    });
+
+    View::Layers(markers.collect()).scrollable(Action::MoveOverview)
+}
+
 /// Returns a view for the track area.
 ///
-/// This includes the track overview and the track settings.
-fn track_area(
+/// This includes the track overview and the track settings,
+/// as well as the automation lanes of expanded tracks.
+#[builder]
+fn track_area<Ui: UserInterface>(
     project: &Project,
     selection: &Selection,
     ui_settings: ui::Settings,
@@ -76,6 +162,7 @@ fn track_area(
     cursor: Instant,
     player: Option<&Player>,
     held_object: Option<Holdable>,
+    expanded_tracks: &HashSet<Id<Track>>,
 ) -> View {
     let mut track_settings = Vec::new();
     let mut track_overviews = Vec::new();
@@ -87,12 +174,13 @@ fn track_area(
         _ => None,
     };
 
-    for track in project.tracks.values() {
+    for (index, track) in project.tracks.values().enumerate() {
         let selected = selection.contains_track(track.id());
+        let expanded = expanded_tracks.contains(&track.id());
 
-        track_settings.push(settings(track, selected));
+        track_settings.push(settings::<Ui>(track, index, selected, expanded));
         track_overviews.push(
-            overview()
+            overview::<Ui>()
                 .track(track)
                 .selection(selection)
                 .offset_mapping(offset_mapping.clone())
@@ -101,8 +189,23 @@ fn track_area(
                 .cursor(cursor)
                 .maybe_player(player.cloned())
                 .maybe_held_clip(held_clip)
+                .linked_contents(project.linked_contents())
                 .call(),
         );
+
+        if expanded {
+            for target in all::<Target>() {
+                track_settings.push(target.to_arc_str().centred());
+                track_overviews.push(
+                    automation::overview::<Ui>()
+                        .track(track)
+                        .target(target)
+                        .offset_mapping(offset_mapping.clone())
+                        .negative_overview_offset(ui_settings.negative_overview_offset)
+                        .call(),
+                );
+            }
+        }
     }
 
     // The "add track" button
@@ -121,12 +224,15 @@ fn track_area(
         player.cloned(),
     ));
 
+    let row_count = NonZeroU64::new(track_overviews.len() as u64).unwrap_or(non_zero!(1));
+
     let settings_column = View::balanced_stack(Axis::Y, track_settings);
     let overview_column = View::balanced_stack(Axis::Y, track_overviews);
 
     let overview_column = View::Layers(vec![
         overview_column,
-        held_object_view(held_object, project, offset_mapping).unwrap_or(View::Empty),
+        held_object_view::<Ui>(held_object, project, offset_mapping, row_count)
+            .unwrap_or(View::Empty),
     ]);
 
     View::x_stack([
@@ -136,10 +242,13 @@ fn track_area(
 }
 
 /// Return the view for the held object in the track workspace.
-fn held_object_view(
+///
+/// `row_count` is the number of rows in the track area.
+fn held_object_view<Ui: UserInterface>(
     held_object: Option<Holdable>,
     project: &Project,
     offset_mapping: OffsetMapping,
+    row_count: NonZeroU64,
 ) -> Option<View> {
     Some(match held_object? {
         Holdable::Clip(path) => {
@@ -152,12 +261,17 @@ fn held_object_view(
 
             let width = clip_end_offset - clip_offset;
 
-            let overview = clip::overview(clip, true, offset_mapping, Length::ZERO, path.track);
+            let overview = clip::overview::<Ui>(
+                clip,
+                true,
+                offset_mapping,
+                Length::ZERO,
+                path.track,
+                project.linked_contents(),
+            );
 
             let overview = Arc::new(overview);
 
-            let row_count = non_zero!(1_u64).saturating_add(project.tracks.len() as u64);
-
             View::reactive(move |render_area| {
                 let height = render_area.area.size.height / row_count;
 
@@ -169,10 +283,16 @@ fn held_object_view(
                     .positioned(position)
             })
         }
-        Holdable::NoteCreation { .. }
+        Holdable::ClipEnd(_)
+        | Holdable::ClipStart(_)
+        | Holdable::LoopRegion { .. }
+        | Holdable::Note { .. }
+        | Holdable::NoteCreation { .. }
+        | Holdable::NoteEnd { .. }
         | Holdable::PianoRollHandle { .. }
         | Holdable::Popup { .. }
-        | Holdable::PopupSide { .. } => return None,
+        | Holdable::PopupSide { .. }
+        | Holdable::Track(_) => return None,
         Holdable::SelectionBox { start } => View::reactive(move |render_area| {
             let start = start.relative_to(render_area.area.position);
             let end = render_area.saturated_mouse_position();
@@ -2,6 +2,7 @@
 
 use crate::Ratio;
 use crate::ratio::Serial;
+use crate::ratio::util::big_greatest_common_divisor;
 use crate::ratio::util::greatest_common_divisor;
 use getset::CopyGetters;
 use non_zero::non_zero;
@@ -119,6 +120,14 @@ impl NonZeroRatio {
             Ordering::Greater => NonZeroRatio::MAX,
         };
 
+        // Reducing the ratio first keeps it exact whenever its reduced form is representable.
+        let divisor = big_greatest_common_divisor(numerator, denominator);
+        #[expect(clippy::integer_division, reason = "the divisor divides both numbers")]
+        {
+            numerator = NonZeroU128::new(numerator.get() / divisor).unwrap_or(numerator);
+            denominator = NonZeroU128::new(denominator.get() / divisor).unwrap_or(denominator);
+        }
+
         loop {
             if let Ok(numerator) = NonZeroU64::try_from(numerator)
                 && let Ok(denominator) = NonZeroU64::try_from(denominator)
@@ -12,7 +12,12 @@ pub(super) fn greatest_common_divisor(a: NonZeroU64, b: NonZeroU64) -> NonZeroU6
     NonZeroU64::new(Integer::gcd(&a.get(), &b.get())).unwrap_or(non_zero!(1))
 }
 
-/// Returns the greatest common divisor of two numbers.
+/// Returns the greatest common divisor of two big numbers.
+pub(super) fn big_greatest_common_divisor(a: NonZeroU128, b: NonZeroU128) -> NonZeroU128 {
+    NonZeroU128::new(Integer::gcd(&a.get(), &b.get())).unwrap_or(non_zero!(1))
+}
+
+/// Returns the lowest common multiple of two numbers.
 pub(super) fn lowest_common_multiple(a: NonZeroU128, b: NonZeroU128) -> NonZeroU128 {
     NonZeroU128::new(Integer::lcm(&a.get(), &b.get())).unwrap_or(a.saturating_mul(b))
 }
@@ -49,6 +49,14 @@ impl Selection {
         })
     }
 
+    /// Returns whether the selection contains a note.
+    pub fn contains_note(&self, note: note::Path) -> bool {
+        self.items.iter().any(|item| match *item {
+            Selectable::Track(_) | Selectable::Clip(_) => false,
+            Selectable::Note(id) => id == note,
+        })
+    }
+
     /// Adds an item to the top of the selection stack.
     pub fn push(&mut self, item: Selectable) {
         self.items.push(item);
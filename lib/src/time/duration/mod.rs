@@ -39,6 +39,16 @@ impl Duration {
     pub const MINUTE: Duration = Duration {
         nanoseconds: 60_000_000_000,
     };
+
+    /// Returns whether the duration is zero.
+    #[must_use]
+    #[expect(
+        clippy::trivially_copy_pass_by_ref,
+        reason = "this is used with `serde(skip_serializing_if)`"
+    )]
+    pub fn is_zero(&self) -> bool {
+        *self == Duration::ZERO
+    }
 }
 
 impl From<time::Duration> for Duration {
@@ -18,6 +18,9 @@ impl NonZeroDuration {
     /// One second.
     pub const SECOND: NonZeroDuration = NonZeroDuration::from_duration(Duration::SECOND).unwrap();
 
+    /// One minute.
+    pub const MINUTE: NonZeroDuration = NonZeroDuration::from_duration(Duration::MINUTE).unwrap();
+
     /// Converts the duration to a [zeroable one](Duration).
     #[must_use]
     pub fn get(self) -> Duration {
@@ -46,10 +46,10 @@ impl<R: Into<Ratio>> Mul<R> for Duration {
         let numerator = u128::from(rhs.numerator());
         let denominator = NonZeroU128::from(rhs.denominator());
 
-        // TODO: round
+        // Adding half of the denominator before dividing rounds to the nearest nanosecond.
         #[expect(clippy::arithmetic_side_effects, reason = "we encapsulate in u128")]
-        #[expect(clippy::integer_division, reason = "see TODO")]
-        let nanoseconds = nanoseconds * numerator / denominator;
+        #[expect(clippy::integer_division, reason = "we round")]
+        let nanoseconds = (nanoseconds * numerator + denominator.get() / 2) / denominator;
         let nanoseconds = u64::try_from(nanoseconds).unwrap_or(u64::MAX);
 
         Duration { nanoseconds }
@@ -1,38 +1,76 @@
 //! Items pertaining to [`Tempo`].
 
+use crate::NonZeroRatio;
+use crate::Ratio;
 use crate::time::Duration;
 use crate::time::NonZeroDuration;
+use getset::CopyGetters;
 use non_zero::non_zero;
 use serde::Deserialize;
 use serde::Serialize;
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
-use std::num::NonZeroU16;
 use std::num::NonZeroU64;
 
 /// A musical tempo.
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[derive(
+    Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Serialize, Deserialize,
+)]
+#[serde(from = "Serial", into = "Serial")]
 pub struct Tempo {
-    // TODO: support the psychopathy that is non-integral BPMs (note serde)
     /// The number of beats per minute.
-    bpm: NonZeroU16,
+    #[get_copy = "pub"]
+    bpm: NonZeroRatio,
+}
+
+/// The serial representation of a [tempo](Tempo).
+#[derive(Copy, Clone, Serialize, Deserialize)]
+struct Serial {
+    /// The number of beats per minute.
+    bpm: SerialBpm,
+}
+
+/// The serial representation of a number of beats per minute.
+///
+/// Integral tempos are written as plain integers, as they always have been.
+#[derive(Copy, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum SerialBpm {
+    /// An integral number of beats per minute.
+    Integer(NonZeroU64),
+    /// A non-integral number of beats per minute.
+    Ratio(NonZeroRatio),
 }
 
 impl Tempo {
-    /// The duration of a beat at this tempo.
+    /// Constructs a tempo from a number of beats per minute.
+    #[must_use]
+    pub const fn new(bpm: NonZeroRatio) -> Tempo {
+        Tempo { bpm }
+    }
+
+    /// The duration of a beat at this tempo, rounded to the nearest nanosecond.
     #[must_use]
     pub fn beat_duration(self) -> NonZeroDuration {
-        let bpm = NonZeroU64::from(self.bpm);
+        let duration = Duration::MINUTE / self.bpm;
 
-        // TODO: round.
-        #[expect(clippy::integer_division, reason = "see TODO")]
-        let nanoseconds = Duration::MINUTE.nanoseconds / bpm;
+        // The minimum value of `nanoseconds` is about 3, so we could technically unwrap.
+        NonZeroDuration::from_duration(duration).unwrap_or(NonZeroDuration::NANOSECOND)
+    }
 
-        // The minimum value of `nanoseconds` is 15,259, so we could technically unwrap.
-        let nanoseconds = NonZeroU64::new(nanoseconds).unwrap_or(NonZeroU64::MIN);
+    /// Returns the tempo sped up by a number of beats per minute.
+    #[must_use]
+    pub(crate) fn faster_by(self, bpm: Ratio) -> Tempo {
+        NonZeroRatio::from_ratio(self.bpm.get() + bpm).map_or(self, Tempo::new)
+    }
 
-        NonZeroDuration { nanoseconds }
+    /// Returns the tempo slowed down by a number of beats per minute.
+    ///
+    /// If the tempo would reach zero, it is left unchanged.
+    #[must_use]
+    pub(crate) fn slower_by(self, bpm: Ratio) -> Tempo {
+        NonZeroRatio::from_ratio(self.bpm.get() - bpm).map_or(self, Tempo::new)
     }
 }
 
@@ -43,13 +81,39 @@ impl Default for Tempo {
     // TODO: rationale
     fn default() -> Tempo {
         Tempo {
-            bpm: non_zero!(180),
+            bpm: NonZeroRatio::integer(non_zero!(180)),
         }
     }
 }
 
 impl Display for Tempo {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{:.1}", self.bpm)
+        // Displaying the tempo with more than two decimals is just noise.
+        let bpm = (self.bpm.get().to_float() * 100.0).round() / 100.0;
+
+        write!(f, "{bpm}")
+    }
+}
+
+impl From<Serial> for Tempo {
+    fn from(serial: Serial) -> Self {
+        let bpm = match serial.bpm {
+            SerialBpm::Integer(bpm) => NonZeroRatio::integer(bpm),
+            SerialBpm::Ratio(bpm) => bpm,
+        };
+
+        Tempo { bpm }
+    }
+}
+
+impl From<Tempo> for Serial {
+    fn from(tempo: Tempo) -> Self {
+        let bpm = if tempo.bpm.denominator() == NonZeroU64::MIN {
+            SerialBpm::Integer(tempo.bpm.numerator())
+        } else {
+            SerialBpm::Ratio(tempo.bpm)
+        };
+
+        Serial { bpm }
     }
 }
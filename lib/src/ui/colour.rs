@@ -51,10 +51,70 @@ impl Colour {
         blue: 0,
     };
 
+    /// The web-colour "cyan".
+    pub(crate) const CYAN: Colour = Colour {
+        red: 0,
+        green: 255,
+        blue: 255,
+    };
+
     /// The web-colour "magenta".
     pub(crate) const MAGENTA: Colour = Colour {
         red: 255,
         green: 0,
         blue: 255,
     };
+
+    /// The web-colour "red".
+    pub(crate) const RED: Colour = Colour {
+        red: 255,
+        green: 0,
+        blue: 0,
+    };
+
+    /// The web-colour "orange".
+    pub(crate) const ORANGE: Colour = Colour {
+        red: 255,
+        green: 165,
+        blue: 0,
+    };
+
+    /// The web-colour "yellow".
+    pub(crate) const YELLOW: Colour = Colour {
+        red: 255,
+        green: 255,
+        blue: 0,
+    };
+
+    /// The web-colour "dodger blue".
+    pub(crate) const DODGER_BLUE: Colour = Colour {
+        red: 30,
+        green: 144,
+        blue: 255,
+    };
+
+    /// The colours that tracks and clips can be given.
+    pub(crate) const PALETTE: [Colour; 8] = [
+        Colour::RED,
+        Colour::ORANGE,
+        Colour::YELLOW,
+        Colour::LIME,
+        Colour::CYAN,
+        Colour::DODGER_BLUE,
+        Colour::MAGENTA,
+        Colour::SILVER,
+    ];
+
+    /// Returns the colour after this one in the [palette](Colour::PALETTE).
+    ///
+    /// Colours not in the palette are followed by the first colour in it.
+    pub(crate) fn next_in_palette(self) -> Colour {
+        Colour::PALETTE
+            .iter()
+            .skip_while(|colour| **colour != self)
+            .nth(1)
+            .or(Colour::PALETTE.first())
+            .copied()
+            .unwrap_or(self)
+    }
 }
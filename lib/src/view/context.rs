@@ -1,10 +1,15 @@
 //! Types pertaining to context menus.
 
+use crate::Id;
 use crate::UserInterface;
 use crate::View;
 use crate::app::Action;
+use crate::metre::Instant;
+use crate::metre::Quantisation;
 use crate::popup::Specification;
 use crate::project::Edit;
+use crate::project::Track;
+use crate::project::track::clip;
 use crate::ui::Point;
 use crate::ui::Rectangle;
 use crate::ui::ThemeColour;
@@ -13,15 +18,45 @@ use crate::view::OnClick;
 use arcstr::ArcStr;
 use arcstr::literal;
 use mitsein::btree_map1::BTreeMap1;
+use non_zero::non_zero;
 use std::fmt;
 use std::fmt::Debug;
 use std::fmt::Formatter;
+use std::num::NonZeroU64;
 use std::sync::Arc;
 
+/// The label of the button to add a new drum clip.
+const ADD_DRUMS: ArcStr = literal!("add drums");
 /// The label of the button to add a new note group.
 const ADD_NOTES: ArcStr = literal!("add notes");
+/// The label of the button to choose the colour of a clip.
+const CHANGE_COLOUR: ArcStr = literal!("change colour");
+/// The label of the button to delete the selected items.
+const DELETE: ArcStr = literal!("delete");
+/// The label of the button to insert copies of clips after them.
+const DUPLICATE: ArcStr = literal!("duplicate");
 /// The label of the button to import an audio clip from a file.
 const IMPORT_AUDIO: ArcStr = literal!("import audio");
+/// The label of the button to join adjacent clips into one.
+const JOIN_CLIPS: ArcStr = literal!("join clips");
+/// The label of the button to insert a linked copy of a clip.
+const LINK_CLIP: ArcStr = literal!("link clip");
+/// The label of the button to detach a linked clip.
+const MAKE_UNIQUE: ArcStr = literal!("make unique");
+/// The label of the button to move the audio of an audio clip into a linked file.
+const LINK_AUDIO: ArcStr = literal!("link audio");
+/// The label of the button to move notes towards the grid.
+const QUANTISE_NOTES: ArcStr = literal!("quantise notes");
+/// The label of the button to remove a marker.
+const REMOVE: ArcStr = literal!("remove");
+/// The label of the button to rename a clip, marker or track.
+const RENAME: ArcStr = literal!("rename");
+/// The label of the button to insert multiple copies of clips after them.
+const REPEAT: ArcStr = literal!("repeat 4 times");
+/// The number of copies inserted by the [repeat button](REPEAT).
+const REPEAT_COUNT: NonZeroU64 = non_zero!(4);
+/// The label of the button to split clips at the cursor.
+const SPLIT_CLIP: ArcStr = literal!("split clip");
 /// The button to toggle the pianoroll.
 const TOGGLE_PIANO_ROLL: ArcStr = literal!("toggle piano roll");
 
@@ -34,20 +69,97 @@ pub struct Menu {
 
 impl Menu {
     /// The context menu for the track overview.
+    ///
+    /// New drum clips get one step per cell of the quantisation.
     #[must_use]
-    pub fn track_overview() -> Menu {
+    pub fn track_overview(quantisation: Quantisation) -> Menu {
         Menu {
             buttons: BTreeMap1::from([
+                (
+                    ADD_DRUMS,
+                    Action::Edit(Edit::AddDrums {
+                        step_duration: quantisation.cell_duration,
+                    }),
+                ),
                 (ADD_NOTES, Action::Edit(Edit::AddNoteGroup)),
+                (DUPLICATE, Action::Edit(Edit::Duplicate)),
                 (
                     IMPORT_AUDIO,
                     Action::OpenPopup(Specification::AudioImporter),
                 ),
+                (JOIN_CLIPS, Action::Edit(Edit::JoinClips)),
+                (LINK_AUDIO, Action::Edit(Edit::LinkAudio)),
+                (LINK_CLIP, Action::Edit(Edit::LinkClip)),
+                (MAKE_UNIQUE, Action::Edit(Edit::MakeUnique)),
+                (
+                    QUANTISE_NOTES,
+                    Action::Edit(Edit::QuantiseNotes {
+                        cell_duration: quantisation.cell_duration,
+                        strength: 100,
+                        swing: 0,
+                        include_ends: false,
+                    }),
+                ),
+                (
+                    REPEAT,
+                    Action::Edit(Edit::Repeat {
+                        count: REPEAT_COUNT,
+                    }),
+                ),
+                (SPLIT_CLIP, Action::Edit(Edit::SplitClip)),
                 (TOGGLE_PIANO_ROLL, Action::TogglePianoRoll),
             ]),
         }
     }
 
+    /// The context menu for a clip.
+    #[must_use]
+    pub fn clip(clip: clip::Path, name: ArcStr) -> Menu {
+        Menu {
+            buttons: BTreeMap1::from([
+                (
+                    CHANGE_COLOUR,
+                    Action::OpenPopup(Specification::ClipColourPicker { clip }),
+                ),
+                (DELETE, Action::Edit(Edit::Delete)),
+                (DUPLICATE, Action::Edit(Edit::Duplicate)),
+                (
+                    RENAME,
+                    Action::OpenPopup(Specification::ClipRenamer { clip, name }),
+                ),
+            ]),
+        }
+    }
+
+    /// The context menu for a marker.
+    #[must_use]
+    pub fn marker(at: Instant, name: ArcStr) -> Menu {
+        Menu {
+            buttons: BTreeMap1::from([
+                (REMOVE, Action::Edit(Edit::RemoveMarker { at })),
+                (
+                    RENAME,
+                    Action::OpenPopup(Specification::MarkerRenamer { at, name }),
+                ),
+            ]),
+        }
+    }
+
+    /// The context menu for the settings of a track.
+    #[must_use]
+    pub fn track(track: Id<Track>, name: ArcStr) -> Menu {
+        Menu {
+            buttons: BTreeMap1::from([
+                (DELETE, Action::Edit(Edit::Delete)),
+                (DUPLICATE, Action::Edit(Edit::DuplicateTrack)),
+                (
+                    RENAME,
+                    Action::OpenPopup(Specification::TrackRenamer { track, name }),
+                ),
+            ]),
+        }
+    }
+
     /// Returns the view of the menu.
     pub fn into_view(self) -> View {
         View::Layers(vec![
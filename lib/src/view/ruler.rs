@@ -1,14 +1,20 @@
 //! Items pertaining to [rulers](View::Rule).
 
+use crate::Holdable;
 use crate::View;
 use crate::app::Action;
 use crate::metre::OffsetMapping;
+use crate::project::Edit;
 use crate::ui::Length;
 use crate::view::Axis;
 use crate::view::OnClick;
 use crate::view::RenderArea;
+use std::cmp::max;
+use std::cmp::min;
 
 /// A clickable, automatically resizing [ruler](View::Rule).
+///
+/// A loop region can be set by dragging along the ruler.
 pub fn ruler(offset: Length, offset_mapping: OffsetMapping) -> View {
     let view = {
         let offset_mapping = offset_mapping.clone();
@@ -16,19 +22,50 @@ pub fn ruler(offset: Length, offset_mapping: OffsetMapping) -> View {
         View::reactive(move |render_area| reactive_ruler(offset, &offset_mapping, render_area))
     };
 
-    let on_click = OnClick::new(move |render_area, actions| {
-        let Some(mouse_position) = render_area.relative_mouse_position() else {
-            return;
-        };
+    let instant = move |render_area: RenderArea| {
+        let mouse_position = render_area.relative_mouse_position()?;
 
         let offset_from_ruler_start = offset + mouse_position.x;
 
-        let instant = offset_mapping.quantised_instant(offset_from_ruler_start);
+        Some(offset_mapping.quantised_instant(offset_from_ruler_start))
+    };
+
+    let on_click = {
+        let instant = instant.clone();
 
-        actions.push(Action::MoveCursor(instant));
-    });
+        OnClick::new(move |render_area, actions| {
+            if let Some(instant) = instant(render_area) {
+                actions.push(Action::MoveCursor(instant));
+            }
+        })
+    };
+
+    let grab_loop_region = {
+        let instant = instant.clone();
+
+        move |render_area: RenderArea| {
+            Some(Holdable::LoopRegion {
+                start: instant(render_area)?,
+            })
+        }
+    };
+
+    let drop_loop_region = move |object: Holdable, render_area: RenderArea| {
+        let Holdable::LoopRegion { start } = object else {
+            return None;
+        };
+
+        let end = instant(render_area)?;
+
+        (start != end).then_some(Action::Edit(Edit::SetLoopRegion {
+            start: min(start, end),
+            end: max(start, end),
+        }))
+    };
 
     view.on_click(on_click)
+        .grabbable(grab_loop_region)
+        .object_accepting(drop_loop_region)
 }
 
 /// A [ruler](View::Rule) filling an [area](RenderArea).
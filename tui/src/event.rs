@@ -5,6 +5,7 @@ use crate::Tui;
 use crate::convert::to_point;
 use crate::convert::to_size;
 use crossterm::event::Event;
+use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
@@ -42,7 +43,7 @@ pub(crate) fn handle_events(events: &[Event], app: &mut App<Tui>) {
 fn handle_event(event: &Event, app: &mut App<Tui>, actions: &mut Actions) {
     match *event {
         Event::FocusGained | Event::FocusLost | Event::Paste(_) => (),
-        Event::Key(event) => handle_key_event(event, app.ui(), actions),
+        Event::Key(event) => handle_key_event(event, app, actions),
         Event::Mouse(event) => handle_mouse_event(event, app, actions),
         Event::Resize(width, height) => app.ui().area.set(Rectangle {
             position: Point::ZERO,
@@ -52,14 +53,31 @@ fn handle_event(event: &Event, app: &mut App<Tui>, actions: &mut Actions) {
 }
 
 /// Processes a [terminal keyboard events](KeyEvent).
-fn handle_key_event(event: KeyEvent, ui: &Tui, actions: &mut Actions) {
+fn handle_key_event(event: KeyEvent, app: &App<Tui>, actions: &mut Actions) {
     if event.kind != KeyEventKind::Press {
         return;
     }
 
+    if app.is_typing() {
+        let unmodified =
+            event.modifiers == KeyModifiers::NONE || event.modifiers == KeyModifiers::SHIFT;
+
+        if let KeyCode::Char(character) = event.code
+            && unmodified
+        {
+            actions.push(Action::Type(character));
+            return;
+        }
+
+        if event.code == KeyCode::Backspace {
+            actions.push(Action::Erase);
+            return;
+        }
+    }
+
     let key = Key::from(event);
 
-    if let Some(action) = ui.configuration.key_map.get(&key) {
+    if let Some(action) = app.ui().configuration.key_map.get(&key) {
         actions.push(action.clone());
     }
 }